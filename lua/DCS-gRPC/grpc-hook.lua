@@ -61,6 +61,31 @@ function handler.onPlayerTrySendChat(playerID, msg, side)
     end
   end
 
+  local allow, reason = grpc.decide({
+    type = "trySendChat",
+    playerId = playerID,
+    message = msg,
+    coalition = coalition,
+  })
+  if not allow then
+    if reason ~= nil then
+      net.send_chat_to(reason, playerID)
+    end
+    return ""
+  end
+
+  -- Parse registered chat commands, and reply directly with their usage if
+  -- they are used incorrectly
+  local ucid = net.get_player_info(playerID, "ucid") or ""
+  local hide, reply = grpc.chatCommand(DCS.getModelTime(), playerID, ucid, msg, coalition)
+  if reply ~= nil then
+    net.send_chat_to(reply, playerID)
+  end
+  if hide then
+    return ""
+  end
+
+  -- only announce messages that are actually sent
   grpc.event({
     time = DCS.getModelTime(),
     event = {
@@ -71,10 +96,28 @@ function handler.onPlayerTrySendChat(playerID, msg, side)
     },
   })
 
-  -- We do not return here, to allow other handlers to decide
+  -- Otherwise, we do not return here, to allow other handlers to decide
 end
 
 function handler.onPlayerTryConnect(addr, name, ucid, id)
+  -- enforce bans and the whitelist managed via the `AccessService`
+  local allowed, banReason = grpc.checkAccess(DCS.getModelTime(), addr, name, ucid)
+  if not allowed then
+    return false, banReason
+  end
+
+  local allow, reason = grpc.decide({
+    type = "tryConnect",
+    addr = addr,
+    name = name,
+    ucid = ucid,
+    playerId = id,
+  })
+  if not allow then
+    return false, reason or "Connection denied"
+  end
+
+  -- only announce players that passed all checks
   grpc.event({
     time = DCS.getModelTime(),
     event = {
@@ -85,6 +128,24 @@ function handler.onPlayerTryConnect(addr, name, ucid, id)
       id = id,
     },
   })
+
+  -- not returning `true` here to allow other scripts to handle this hook
+end
+
+function handler.onPlayerTryChangeSlot(playerID, side, slotID)
+  local allow, reason = grpc.decide({
+    type = "tryChangeSlot",
+    playerId = playerID,
+    coalition = side + 1, -- offsetting for grpc COALITION enum
+    slotId = tostring(slotID),
+  })
+  if not allow then
+    if reason ~= nil then
+      net.send_chat_to(reason, playerID)
+    end
+    return false
+  end
+
   -- not returning `true` here to allow other scripts to handle this hook
 end
 
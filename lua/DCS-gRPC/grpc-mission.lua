@@ -3,6 +3,8 @@ if not GRPC then
     -- scaffold nested tables to allow direct assignment in config file
     tts = { provider = { gcloud = {}, aws = {}, azure = {}, win = {} } },
     srs = {},
+    rotation = {},
+    gci = {},
   }
 end
 
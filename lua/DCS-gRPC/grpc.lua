@@ -19,8 +19,12 @@ if isMissionEnv then
     debug = GRPC.debug,
     evalEnabled = GRPC.evalEnabled,
     integrityCheckDisabled = GRPC.integrityCheckDisabled,
+    decisionTimeout = GRPC.decisionTimeout,
+    decisionDefault = GRPC.decisionDefault,
     tts = GRPC.tts,
     srs = GRPC.srs,
+    rotation = GRPC.rotation,
+    gci = GRPC.gci,
   }))
 end
 
@@ -30,6 +34,8 @@ end
 
 GRPC.state = {}
 GRPC.state.tracked_weapons = {}
+-- The last known inventory of every warehouse accessed via the `WarehouseService`, by airbase name
+GRPC.state.tracked_warehouses = {}
 
 --
 -- Export methods
@@ -154,17 +160,20 @@ GRPC.event = grpc.event
 --
 
 GRPC.methods = {}
+dofile(GRPC.luaPath .. [[methods\airbase.lua]])
 dofile(GRPC.luaPath .. [[methods\atmosphere.lua]])
 dofile(GRPC.luaPath .. [[methods\coalitions.lua]])
 dofile(GRPC.luaPath .. [[methods\controllers.lua]])
 dofile(GRPC.luaPath .. [[methods\custom.lua]])
 dofile(GRPC.luaPath .. [[methods\group.lua]])
 dofile(GRPC.luaPath .. [[methods\hook.lua]])
+dofile(GRPC.luaPath .. [[methods\land.lua]])
 dofile(GRPC.luaPath .. [[methods\mission.lua]])
 dofile(GRPC.luaPath .. [[methods\net.lua]])
 dofile(GRPC.luaPath .. [[methods\timer.lua]])
 dofile(GRPC.luaPath .. [[methods\trigger.lua]])
 dofile(GRPC.luaPath .. [[methods\unit.lua]])
+dofile(GRPC.luaPath .. [[methods\warehouse.lua]])
 dofile(GRPC.luaPath .. [[methods\world.lua]])
 dofile(GRPC.luaPath .. [[methods\weapon.lua]])
 
@@ -280,6 +289,18 @@ if isMissionEnv then
     return timer.getTime() + 0.5
   end, nil, timer.getTime() + 0.5)
 
+  -- Emit changes made to tracked warehouses by DCS or other scripts
+  timer.scheduleFunction(function()
+    if not stopped then
+      local ok, err = pcall(GRPC.pollWarehouses)
+      if not ok then
+        GRPC.logError("Error polling warehouses: "..tostring(err))
+      end
+
+      return timer.getTime() + 10
+    end
+  end, nil, timer.getTime() + 10)
+
   -- Start listening for events
   world.addEventHandler(eventHandler)
 else -- hook env
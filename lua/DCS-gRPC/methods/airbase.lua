@@ -0,0 +1,128 @@
+--
+-- RPC airbase actions
+-- https://wiki.hoggitworld.com/view/DCS_Class_Airbase
+--
+
+local GRPC = GRPC
+
+local getAirbase = function(name)
+  -- https://wiki.hoggitworld.com/view/DCS_func_getByName
+  local airbase = Airbase.getByName(name)
+  if airbase == nil or not airbase:isExist() then
+    return nil, GRPC.errorNotFound("airbase `" .. tostring(name) .. "` does not exist")
+  end
+  return airbase
+end
+
+-- The name of the first unit or static object found within a few meters of the given point.
+local findOccupant = function(point)
+  local occupant
+  world.searchObjects(
+    { Object.Category.UNIT, Object.Category.STATIC },
+    {
+      id = world.VolumeType.SPHERE,
+      params = { point = point, radius = 15 },
+    },
+    function(object)
+      occupant = object:getName()
+      return false -- stop searching
+    end
+  )
+  return occupant
+end
+
+GRPC.methods.getRunways = function(params)
+  local airbase, err = getAirbase(params.name)
+  if airbase == nil then
+    return err
+  end
+
+  local runways = {}
+  for _, runway in ipairs(airbase:getRunways() or {}) do
+    table.insert(runways, {
+      name = tostring(runway.Name),
+      -- the course is counter-clockwise and in radians
+      heading = math.deg(-runway.course) % 360,
+      length = runway.length,
+      width = runway.width,
+      position = GRPC.exporters.position(runway.position),
+    })
+  end
+
+  return GRPC.success({ runways = runways })
+end
+
+GRPC.methods.getParking = function(params)
+  local airbase, err = getAirbase(params.name)
+  if airbase == nil then
+    return err
+  end
+
+  local free = {}
+  for _, spot in ipairs(airbase:getParking(true) or {}) do
+    free[spot.Term_Index] = true
+  end
+
+  local parkingSpots = {}
+  for _, spot in ipairs(airbase:getParking(false) or {}) do
+    local isFree = free[spot.Term_Index] == true
+    if isFree or not params.freeOnly then
+      local parkingSpot = {
+        id = spot.Term_Index,
+        termType = spot.Term_Type,
+        position = GRPC.exporters.position(spot.vTerminalPos),
+        free = isFree,
+        distanceToRunway = spot.fDistToRW or 0,
+      }
+      if not isFree then
+        parkingSpot.occupiedBy = findOccupant(spot.vTerminalPos)
+      end
+      table.insert(parkingSpots, parkingSpot)
+    end
+  end
+
+  return GRPC.success({ parkingSpots = parkingSpots })
+end
+
+GRPC.methods.setAirbaseCoalition = function(params)
+  if params.coalition == 0 then
+    return GRPC.errorInvalidArgument("coalition must be specified")
+  end
+
+  local airbase, err = getAirbase(params.name)
+  if airbase == nil then
+    return err
+  end
+
+  airbase:setCoalition(params.coalition - 1) -- Decrement for non zero-indexed gRPC enum
+  return GRPC.success({})
+end
+
+GRPC.methods.airbaseAutoCapture = function(params)
+  local airbase, err = getAirbase(params.name)
+  if airbase == nil then
+    return err
+  end
+
+  airbase:autoCapture(params.enabled)
+  return GRPC.success({})
+end
+
+GRPC.methods.setRadioSilentMode = function(params)
+  local airbase, err = getAirbase(params.name)
+  if airbase == nil then
+    return err
+  end
+
+  airbase:setRadioSilentMode(params.silent)
+  return GRPC.success({})
+end
+
+GRPC.methods.getRadioSilentMode = function(params)
+  local airbase, err = getAirbase(params.name)
+  if airbase == nil then
+    return err
+  end
+
+  return GRPC.success({ silent = airbase:getRadioSilentMode() })
+end
@@ -21,12 +21,13 @@ local skill = {
   Player = 5
 }
 
---local altitudeType = {
---  [1] = "BARO",
---  [2] = "RADIO",
---  BARO = 1,
---  RADIO = 2
---}
+-- Maps the gRPC `Modulation` enum to DCS's modulation (also used by the tasks and commands of
+-- controllers).
+GRPC.modulation = {
+  [0] = 0, -- AM
+  [1] = 0, -- AM
+  [2] = 1, -- FM
+}
 
 local createGroundUnitsTemplate = function(unitListTemplate)
   local units = {}
@@ -135,30 +136,34 @@ local createGroundGroupTemplate = function(groupTemplate)
     uncontrollable = groupTemplate.uncontrollable
   end
 
+  local route, err = GRPC.createRoute(groupTemplate.route or {})
+  if route == nil then
+    return nil, err
+  end
+
+  -- the group's tasks are set at its start position, which the rest of the route follows
+  table.insert(route.points, 1, {
+    x = groupTemplate.position.lat,
+    y = groupTemplate.position.lon,
+    type = "Turning Point",
+    eta = 0,
+    eta_locked = true,
+    alt_type = "BARO",
+    formation_template = "",
+    speed = route.points[1] and route.points[1].speed or 0,
+    action = "Off Road",
+    task = {
+      id = "ComboTask",
+      params = {
+        tasks = comboTasks
+      }
+    }
+  })
+  route.spans = {}
+
   local groupTable = {
     name = groupTemplate.name,
-    route = {
-      spans = {},
-      points = {
-        {
-          x = groupTemplate.position.lat,
-          y = groupTemplate.position.lon,
-          type = "Turning Point",
-          eta = 0,
-          eta_locked = true,
-          alt_type = "BARO",
-          formation_template = "",
-          speed = 0,
-          action = "Off Road",
-          task = {
-            id = "ComboTask",
-            params = {
-                tasks = comboTasks
-            }
-          }
-        }
-      }
-    },
+    route = route,
     task = "Ground Nothing",
     taskSelected = true,
     tasks = {},
@@ -194,6 +199,209 @@ local createGroundGroupTemplate = function(groupTemplate)
   return groupTable
 end
 
+local createPayload = function(unitType, payloadTemplate)
+  local payload = {
+    pylons = {},
+    fuel = nil,
+    flare = 0,
+    chaff = 0,
+    gun = 100,
+  }
+
+  if payloadTemplate ~= nil then
+    for _, pylon in ipairs(payloadTemplate.pylons) do
+      payload.pylons[pylon.number] = { CLSID = pylon.clsid }
+    end
+    payload.fuel = payloadTemplate.fuel
+    payload.flare = payloadTemplate.flare
+    payload.chaff = payloadTemplate.chaff
+    if payloadTemplate.gun ~= nil then
+      payload.gun = payloadTemplate.gun
+    end
+  end
+
+  if payload.fuel == nil then
+    local desc = Unit.getDescByName(unitType)
+    payload.fuel = desc and desc.fuelMassMax or 0
+  end
+
+  return payload
+end
+
+local createAircraftUnitsTemplate = function(unitListTemplate, route)
+  local units = {}
+  local firstPoint = route.points[1]
+
+  for _, unitTemplate in ipairs(unitListTemplate) do
+    local unit = {
+      name = unitTemplate.name,
+      type = unitTemplate.type,
+      x = firstPoint.x,
+      y = firstPoint.y,
+      alt = firstPoint.alt,
+      alt_type = firstPoint.alt_type,
+      speed = firstPoint.speed,
+      skill = skill[unitTemplate.skill],
+      heading = math.rad(unitTemplate.heading or 0),
+      payload = createPayload(unitTemplate.type, unitTemplate.payload),
+    }
+
+    if unitTemplate.position ~= nil then
+      local position = coord.LLtoLO(unitTemplate.position.lat, unitTemplate.position.lon, 0)
+      unit.x = position.x
+      unit.y = position.z
+      unit.alt = unitTemplate.position.alt
+    end
+    if unitTemplate.unitId ~= nil then
+      unit.unitId = unitTemplate.unitId
+    end
+    if unitTemplate.liveryId ~= nil then
+      unit.livery_id = unitTemplate.liveryId
+    end
+    if unitTemplate.onboardNum ~= nil then
+      unit.onboard_num = unitTemplate.onboardNum
+    end
+    if unitTemplate.callsign ~= nil then
+      if unitTemplate.callsign.westernCallsign ~= nil then
+        local callsign = unitTemplate.callsign.westernCallsign
+        unit.callsign = {
+          [1] = callsign.nameIndex,
+          [2] = callsign.group,
+          [3] = callsign.element,
+          name = callsign.name,
+        }
+      elseif unitTemplate.callsign.numericCallsign ~= nil then
+        unit.callsign = unitTemplate.callsign.numericCallsign
+      end
+    end
+    if unitTemplate.parking ~= nil then
+      unit.parking = unitTemplate.parking
+    end
+    if unitTemplate.parkingId ~= nil then
+      unit.parking_id = unitTemplate.parkingId
+    end
+
+    table.insert(units, unit)
+  end
+
+  return units
+end
+
+local createAircraftGroupTemplate = function(groupTemplate)
+  local route, err = GRPC.createRoute(groupTemplate.route)
+  if route == nil then
+    return nil, err
+  end
+  local firstPoint = route.points[1]
+
+  local groupTable = {
+    name = groupTemplate.name,
+    task = groupTemplate.task,
+    route = route,
+    units = createAircraftUnitsTemplate(groupTemplate.units, route),
+    x = firstPoint.x,
+    y = firstPoint.y,
+    hidden = groupTemplate.hidden,
+    lateActivation = groupTemplate.lateActivation,
+    uncontrolled = groupTemplate.uncontrolled,
+    communication = true,
+    modulation = GRPC.modulation[groupTemplate.modulation] or 0,
+  }
+
+  if groupTemplate.groupId ~= nil then
+    groupTable.groupId = groupTemplate.groupId
+  end
+  if groupTemplate.startTime ~= nil and groupTemplate.startTime > 0 then
+    groupTable.start_time = groupTemplate.startTime
+  end
+  if groupTemplate.frequency ~= nil then
+    groupTable.frequency = groupTemplate.frequency / 1000000 -- DCS expects MHz
+  end
+
+  return groupTable
+end
+
+local createShipUnitsTemplate = function(unitListTemplate)
+  local units = {}
+
+  for _, unitTemplate in ipairs(unitListTemplate) do
+    local position = coord.LLtoLO(unitTemplate.position.lat, unitTemplate.position.lon, 0)
+    local unit = {
+      name = unitTemplate.name,
+      type = unitTemplate.type,
+      x = position.x,
+      y = position.z,
+      skill = skill[unitTemplate.skill],
+      heading = math.rad(unitTemplate.heading or 0),
+      transportable = { randomTransportable = false },
+      modulation = GRPC.modulation[unitTemplate.modulation] or 0,
+    }
+
+    if unitTemplate.unitId ~= nil then
+      unit.unitId = unitTemplate.unitId
+    end
+    if unitTemplate.liveryId ~= nil then
+      unit.livery_id = unitTemplate.liveryId
+    end
+    if unitTemplate.frequency ~= nil then
+      unit.frequency = unitTemplate.frequency -- ships expect Hz
+    end
+
+    table.insert(units, unit)
+  end
+
+  return units
+end
+
+local createShipGroupTemplate = function(groupTemplate)
+  local units = createShipUnitsTemplate(groupTemplate.units)
+  local route, err = GRPC.createRoute(groupTemplate.route)
+  if route == nil then
+    return nil, err
+  end
+
+  -- ships spawn at the position of their units, so the route has to start there
+  table.insert(route.points, 1, {
+    x = units[1].x,
+    y = units[1].y,
+    alt = 0,
+    alt_type = "BARO",
+    type = "Turning Point",
+    action = "Turning Point",
+    speed = route.points[1] and route.points[1].speed or 0,
+    ETA = 0,
+    ETA_locked = true,
+    formation_template = "",
+    task = {
+      id = "ComboTask",
+      params = {
+        tasks = {}
+      }
+    }
+  })
+
+  local groupTable = {
+    name = groupTemplate.name,
+    route = route,
+    units = units,
+    x = units[1].x,
+    y = units[1].y,
+    hidden = groupTemplate.hidden,
+    lateActivation = groupTemplate.lateActivation,
+    uncontrollable = groupTemplate.uncontrollable,
+    visible = groupTemplate.visible,
+  }
+
+  if groupTemplate.groupId ~= nil then
+    groupTable.groupId = groupTemplate.groupId
+  end
+  if groupTemplate.startTime ~= nil and groupTemplate.startTime > 0 then
+    groupTable.start_time = groupTemplate.startTime
+  end
+
+  return groupTable
+end
+
 GRPC.methods.addGroup = function(params)
   if params.groupCategory == 0 then
     return GRPC.errorInvalidArgument("group category must be specified")
@@ -201,14 +409,456 @@ GRPC.methods.addGroup = function(params)
   if params.country_id == 0 or params.country_id == 15 then
     return GRPC.errorInvalidArgument("invalid country code")
   end
+  if params.template == nil then
+    return GRPC.errorInvalidArgument("template must be specified")
+  end
 
-  local template = createGroundGroupTemplate(params.template.groundTemplate)
+  local template, err
+  if params.template.groundTemplate ~= nil then
+    template, err = createGroundGroupTemplate(params.template.groundTemplate)
+  elseif params.template.shipTemplate ~= nil then
+    if #params.template.shipTemplate.units == 0 then
+      return GRPC.errorInvalidArgument("ship group requires at least one unit")
+    end
+    template, err = createShipGroupTemplate(params.template.shipTemplate)
+  else
+    local groupTemplate = params.template.planeTemplate or params.template.helicopterTemplate
+    if #groupTemplate.units == 0 then
+      return GRPC.errorInvalidArgument("aircraft group requires at least one unit")
+    end
+    if #groupTemplate.route == 0 then
+      return GRPC.errorInvalidArgument("aircraft group requires at least one waypoint")
+    end
+    template, err = createAircraftGroupTemplate(groupTemplate)
+  end
+  if template == nil then
+    return GRPC.errorInvalidArgument(err)
+  end
 
   coalition.addGroup(params.country - 1, params.groupCategory - 1, template) -- Decrement for non zero-indexed gRPC enum
 
   return GRPC.success({group = GRPC.exporters.group(Group.getByName(template.name))})
 end
 
+-- The `common.GroupCategory` for each category of the groups in `env.mission`
+local missionGroupCategories = {
+  plane = 1,
+  helicopter = 2,
+  vehicle = 3,
+  ship = 4,
+}
+
+-- Find the mission editor definition of a group. Also returns the DCS id of the group's country
+-- and the group's `common.GroupCategory`.
+local findMissionGroup = function(groupName)
+  for _, side in pairs(env.mission.coalition) do
+    for _, country in ipairs(side.country or {}) do
+      for category, groupCategory in pairs(missionGroupCategories) do
+        if country[category] ~= nil then
+          for _, group in ipairs(country[category].group or {}) do
+            if group.name == groupName then
+              return group, country.id, groupCategory
+            end
+          end
+        end
+      end
+    end
+  end
+
+  return nil
+end
+
+local getHeading = function(unit)
+  local orientation = unit:getPosition()
+  local heading = math.atan2(orientation.x.z, orientation.x.x)
+  if heading < 0 then
+    heading = heading + 2 * math.pi
+  end
+  return heading
+end
+
+-- The units of a group in a common format, preferring the current state of live units over their
+-- mission editor definition (`missionUnit`).
+local captureUnits = function(liveUnits, missionGroup)
+  local missionUnits = {}
+  if missionGroup ~= nil then
+    for _, missionUnit in ipairs(missionGroup.units) do
+      missionUnits[missionUnit.name] = missionUnit
+    end
+  end
+
+  local units = {}
+  if #liveUnits > 0 then
+    for _, unit in ipairs(liveUnits) do
+      local point = unit:getPoint()
+      local velocity = unit:getVelocity()
+      table.insert(units, {
+        name = unit:getName(),
+        type = unit:getTypeName(),
+        x = point.x,
+        y = point.z,
+        alt = point.y,
+        heading = getHeading(unit),
+        speed = math.sqrt(velocity.x ^ 2 + velocity.y ^ 2 + velocity.z ^ 2),
+        missionUnit = missionUnits[unit:getName()],
+      })
+    end
+  else
+    for _, missionUnit in ipairs(missionGroup.units) do
+      table.insert(units, {
+        name = missionUnit.name,
+        type = missionUnit.type,
+        x = missionUnit.x,
+        y = missionUnit.y,
+        alt = missionUnit.alt or land.getHeight({ x = missionUnit.x, y = missionUnit.y }),
+        heading = missionUnit.heading or 0,
+        speed = missionUnit.speed or 0,
+        missionUnit = missionUnit,
+      })
+    end
+  end
+
+  return units
+end
+
+local captureSkill = function(unit)
+  return unit.missionUnit and skill[unit.missionUnit.skill] or skill.Average
+end
+
+local capturePosition = function(unit)
+  local lat, lon = coord.LOtoLL({ x = unit.x, y = 0, z = unit.y })
+  return { lat = lat, lon = lon, alt = unit.alt }
+end
+
+local captureGroundGroupTemplate = function(groupName, units, missionGroup)
+  local groupTemplate = {
+    name = groupName,
+    -- ground groups are positioned using DCS' x and y (see `createGroundGroupTemplate`)
+    position = { lat = units[1].x, lon = units[1].y, alt = 0 },
+    route = {},
+    startTime = 0,
+    task = "Ground Nothing",
+    taskSelected = true,
+    tasks = {},
+    hidden = false,
+    lateActivation = false,
+    uncontrollable = false,
+    visible = false,
+    units = {},
+  }
+
+  if missionGroup ~= nil then
+    groupTemplate.hidden = missionGroup.hidden or false
+    groupTemplate.lateActivation = missionGroup.lateActivation or false
+    groupTemplate.uncontrollable = missionGroup.uncontrollable or false
+    groupTemplate.visible = missionGroup.visible or false
+    groupTemplate.startTime = missionGroup.start_time or 0
+
+    if missionGroup.route ~= nil and missionGroup.route.points ~= nil then
+      -- the first point is the group's start position, which `createGroundGroupTemplate` adds
+      -- back
+      local points = {}
+      for i = 2, #missionGroup.route.points do
+        table.insert(points, missionGroup.route.points[i])
+      end
+      groupTemplate.route = GRPC.exportRoute(points)
+    end
+  end
+
+  for _, unit in ipairs(units) do
+    local unitTemplate = {
+      name = unit.name,
+      type = unit.type,
+      position = { lat = unit.x, lon = unit.y, alt = 0 },
+      heading = unit.heading, -- ground units expect radians
+      skill = captureSkill(unit),
+    }
+    if unit.missionUnit ~= nil then
+      unitTemplate.liveryId = unit.missionUnit.livery_id
+      unitTemplate.playerCanDrive = unit.missionUnit.playerCanDrive
+    end
+    table.insert(groupTemplate.units, unitTemplate)
+  end
+
+  return groupTemplate
+end
+
+local captureShipGroupTemplate = function(groupName, units, missionGroup)
+  local groupTemplate = {
+    name = groupName,
+    hidden = false,
+    lateActivation = false,
+    startTime = 0,
+    visible = false,
+    uncontrollable = false,
+    route = {},
+    units = {},
+  }
+
+  if missionGroup ~= nil then
+    groupTemplate.hidden = missionGroup.hidden or false
+    groupTemplate.lateActivation = missionGroup.lateActivation or false
+    groupTemplate.uncontrollable = missionGroup.uncontrollable or false
+    groupTemplate.visible = missionGroup.visible or false
+    groupTemplate.startTime = missionGroup.start_time or 0
+
+    if missionGroup.route ~= nil and missionGroup.route.points ~= nil then
+      -- the first point is where the ships start, which `createShipGroupTemplate` adds back
+      local points = {}
+      for i = 2, #missionGroup.route.points do
+        table.insert(points, missionGroup.route.points[i])
+      end
+      groupTemplate.route = GRPC.exportRoute(points)
+    end
+  end
+
+  for _, unit in ipairs(units) do
+    local unitTemplate = {
+      name = unit.name,
+      type = unit.type,
+      position = capturePosition(unit),
+      heading = math.deg(unit.heading),
+      skill = captureSkill(unit),
+      modulation = 0,
+    }
+    if unit.missionUnit ~= nil then
+      unitTemplate.liveryId = unit.missionUnit.livery_id
+      if unit.missionUnit.frequency ~= nil then
+        unitTemplate.frequency = math.floor(unit.missionUnit.frequency)
+      end
+      if unit.missionUnit.modulation ~= nil then
+        unitTemplate.modulation = unit.missionUnit.modulation + 1 -- Increment for non zero-indexed gRPC enum
+      end
+    end
+    table.insert(groupTemplate.units, unitTemplate)
+  end
+
+  return groupTemplate
+end
+
+local capturePayload = function(payload)
+  local pylons = {}
+  for number, pylon in pairs(payload.pylons or {}) do
+    if pylon.CLSID ~= nil then
+      table.insert(pylons, { number = number, clsid = pylon.CLSID })
+    end
+  end
+  table.sort(pylons, function(a, b) return a.number < b.number end)
+
+  return {
+    pylons = pylons,
+    fuel = tonumber(payload.fuel),
+    flare = payload.flare or 0,
+    chaff = payload.chaff or 0,
+    gun = payload.gun,
+  }
+end
+
+local captureAircraftGroupTemplate = function(groupName, units, missionGroup)
+  local groupTemplate = {
+    name = groupName,
+    task = "Nothing",
+    hidden = false,
+    lateActivation = false,
+    uncontrolled = false,
+    startTime = 0,
+    modulation = 0,
+    route = {},
+    units = {},
+  }
+
+  if missionGroup ~= nil then
+    groupTemplate.task = missionGroup.task or groupTemplate.task
+    groupTemplate.hidden = missionGroup.hidden or false
+    groupTemplate.lateActivation = missionGroup.lateActivation or false
+    groupTemplate.uncontrolled = missionGroup.uncontrolled or false
+    groupTemplate.startTime = missionGroup.start_time or 0
+    if missionGroup.frequency ~= nil then
+      groupTemplate.frequency = math.floor(missionGroup.frequency * 1000000 + 0.5) -- DCS uses MHz
+    end
+    if missionGroup.modulation ~= nil then
+      groupTemplate.modulation = missionGroup.modulation + 1 -- Increment for non zero-indexed gRPC enum
+    end
+    if missionGroup.route ~= nil and missionGroup.route.points ~= nil then
+      groupTemplate.route = GRPC.exportRoute(missionGroup.route.points)
+    end
+  end
+
+  if #groupTemplate.route == 0 then
+    -- aircraft require a route, so let them continue from where the leader currently is
+    local leader = units[1]
+    table.insert(groupTemplate.route, {
+      position = capturePosition(leader),
+      altitudeType = 1,
+      speed = leader.speed,
+      action = 1,
+      tasks = {},
+    })
+  end
+
+  for _, unit in ipairs(units) do
+    local unitTemplate = {
+      name = unit.name,
+      type = unit.type,
+      position = capturePosition(unit),
+      heading = math.deg(unit.heading),
+      skill = captureSkill(unit),
+    }
+
+    local missionUnit = unit.missionUnit
+    if missionUnit ~= nil then
+      unitTemplate.liveryId = missionUnit.livery_id
+      unitTemplate.onboardNum = missionUnit.onboard_num
+      unitTemplate.parking = tonumber(missionUnit.parking)
+      unitTemplate.parkingId = missionUnit.parking_id
+      if type(missionUnit.callsign) == "table" then
+        unitTemplate.callsign = {
+          westernCallsign = {
+            nameIndex = missionUnit.callsign[1] or 0,
+            group = missionUnit.callsign[2] or 0,
+            element = missionUnit.callsign[3] or 0,
+            name = missionUnit.callsign.name or "",
+          }
+        }
+      elseif type(missionUnit.callsign) == "number" then
+        unitTemplate.callsign = { numericCallsign = missionUnit.callsign }
+      end
+      if missionUnit.payload ~= nil then
+        unitTemplate.payload = capturePayload(missionUnit.payload)
+      end
+    end
+
+    table.insert(groupTemplate.units, unitTemplate)
+  end
+
+  return groupTemplate
+end
+
+GRPC.methods.captureGroupTemplate = function(params)
+  local missionGroup, countryId, groupCategory = findMissionGroup(params.groupName)
+
+  local liveUnits = {}
+  local group = Group.getByName(params.groupName)
+  if group ~= nil then
+    liveUnits = group:getUnits()
+    if #liveUnits > 0 then
+      countryId = liveUnits[1]:getCountry()
+      groupCategory = group:getCategory() + 1 -- Increment for non zero-indexed gRPC enum
+    end
+  end
+
+  if #liveUnits == 0 and missionGroup == nil then
+    return GRPC.errorNotFound("group does not exist")
+  end
+
+  local units = captureUnits(liveUnits, missionGroup)
+  if #units == 0 then
+    return GRPC.errorNotFound("group does not have any units")
+  end
+
+  local template
+  if groupCategory == 1 then
+    template = { planeTemplate = captureAircraftGroupTemplate(params.groupName, units, missionGroup) }
+  elseif groupCategory == 2 then
+    template = {
+      helicopterTemplate = captureAircraftGroupTemplate(params.groupName, units, missionGroup)
+    }
+  elseif groupCategory == 3 then
+    template = { groundTemplate = captureGroundGroupTemplate(params.groupName, units, missionGroup) }
+  elseif groupCategory == 4 then
+    template = { shipTemplate = captureShipGroupTemplate(params.groupName, units, missionGroup) }
+  else
+    return GRPC.errorInvalidArgument("groups of this category cannot be captured")
+  end
+
+  return GRPC.success({
+    country = countryId + 1, -- Increment for non zero-indexed gRPC enum
+    groupCategory = groupCategory,
+    template = template,
+  })
+end
+
+-- Move all positions of a group template (as accepted by `addGroup`) according to the placement
+-- of a `template.SpawnFromTemplateRequest`.
+local moveGroupTemplate = function(template, placement)
+  if template.groundTemplate ~= nil then
+    -- ground groups are positioned using DCS' x and y (see `createGroundGroupTemplate`)
+    local groupTemplate = template.groundTemplate
+    local reference = groupTemplate.units[1].position
+    local dx, dy
+    if placement.offset ~= nil then
+      dx, dy = placement.offset.north, placement.offset.east
+    else
+      local position = coord.LLtoLO(placement.position.lat, placement.position.lon, 0)
+      dx, dy = position.x - reference.lat, position.z - reference.lon
+    end
+
+    local positions = { groupTemplate.position }
+    for _, unit in ipairs(groupTemplate.units) do
+      table.insert(positions, unit.position)
+    end
+    for _, position in ipairs(positions) do
+      position.lat = position.lat + dx
+      position.lon = position.lon + dy
+    end
+
+    -- unlike the group and its units, the route uses latitude and longitude
+    for _, waypoint in ipairs(groupTemplate.route or {}) do
+      if waypoint.position ~= nil then
+        local point = coord.LLtoLO(waypoint.position.lat, waypoint.position.lon, 0)
+        waypoint.position.lat, waypoint.position.lon =
+          coord.LOtoLL({ x = point.x + dx, y = 0, z = point.z + dy })
+      end
+    end
+    return
+  end
+
+  local groupTemplate = template.shipTemplate or template.planeTemplate
+    or template.helicopterTemplate
+  local positions = {}
+  for _, unit in ipairs(groupTemplate.units) do
+    if unit.position ~= nil then
+      table.insert(positions, unit.position)
+    end
+  end
+  for _, waypoint in ipairs(groupTemplate.route) do
+    -- waypoints at airbases stay where they are
+    if waypoint.position ~= nil and waypoint.airdromeId == nil and waypoint.helipadId == nil then
+      table.insert(positions, waypoint.position)
+    end
+  end
+  if #positions == 0 then
+    return
+  end
+
+  local reference = coord.LLtoLO(positions[1].lat, positions[1].lon, 0)
+  local dx, dy
+  if placement.offset ~= nil then
+    dx, dy = placement.offset.north, placement.offset.east
+  else
+    local position = coord.LLtoLO(placement.position.lat, placement.position.lon, 0)
+    dx, dy = position.x - reference.x, position.z - reference.z
+  end
+
+  for _, position in ipairs(positions) do
+    local point = coord.LLtoLO(position.lat, position.lon, 0)
+    position.lat, position.lon = coord.LOtoLL({ x = point.x + dx, y = 0, z = point.z + dy })
+  end
+end
+
+GRPC.methods.spawnGroupTemplate = function(params)
+  local group = params.group
+  if group.template == nil then
+    return GRPC.errorInvalidArgument("template must be specified")
+  end
+
+  if params.placement ~= nil then
+    moveGroupTemplate(group.template, params.placement)
+  end
+
+  return GRPC.methods.addGroup(group)
+end
+
 GRPC.methods.getStaticObjects = function(params)
   local result = {}
   for _, coalitionId in pairs(coalition.side) do
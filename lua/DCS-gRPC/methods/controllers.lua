@@ -92,3 +92,340 @@ GRPC.methods.getDetectedTargets = function(params)
     contacts = results
   })
 end
+
+local expend = {
+  [0] = "Auto",
+  [1] = "Auto",
+  [2] = "One",
+  [3] = "Two",
+  [4] = "Four",
+  [5] = "Quarter",
+  [6] = "Half",
+  [7] = "All",
+}
+
+local orbitPattern = {
+  [0] = "Circle",
+  [1] = "Circle",
+  [2] = "Race-Track",
+}
+
+local toVec2 = function(position)
+  local point = coord.LLtoLO(position.lat, position.lon, 0)
+  return { x = point.x, y = point.z }
+end
+
+-- Create the DCS task table for a `common.Task`. Returns `nil` and an error message if the task is
+-- invalid.
+GRPC.createTask = function(task)
+  if task == nil then
+    return nil, "task must be specified"
+  end
+
+  if task.orbit ~= nil then
+    local params = {
+      pattern = orbitPattern[task.orbit.pattern] or "Circle",
+      speed = task.orbit.speed,
+      altitude = task.orbit.altitude,
+    }
+    if task.orbit.point ~= nil then
+      params.point = toVec2(task.orbit.point)
+    end
+    if task.orbit.point2 ~= nil then
+      params.point2 = toVec2(task.orbit.point2)
+    end
+    return { id = "Orbit", params = params }
+
+  elseif task.engageTargets ~= nil then
+    if #task.engageTargets.targetTypes == 0 then
+      return nil, "at least one target type is required"
+    end
+    return {
+      id = "EngageTargets",
+      params = {
+        targetTypes = task.engageTargets.targetTypes,
+        maxDist = task.engageTargets.maxDistance,
+        priority = task.engageTargets.priority or 0,
+      }
+    }
+
+  elseif task.attackGroup ~= nil then
+    local group = Group.getByName(task.attackGroup.groupName)
+    if group == nil then
+      return nil, "group `" .. task.attackGroup.groupName .. "` not found"
+    end
+    return {
+      id = "AttackGroup",
+      params = {
+        groupId = group:getID(),
+        weaponType = task.attackGroup.weaponType,
+        expend = expend[task.attackGroup.expend] or "Auto",
+        directionEnabled = task.attackGroup.direction ~= nil,
+        direction = task.attackGroup.direction and math.rad(task.attackGroup.direction),
+        altitudeEnabled = task.attackGroup.altitude ~= nil,
+        altitude = task.attackGroup.altitude,
+        attackQtyLimit = task.attackGroup.attackQuantity ~= nil,
+        attackQty = task.attackGroup.attackQuantity,
+      }
+    }
+
+  elseif task.escort ~= nil then
+    local group = Group.getByName(task.escort.groupName)
+    if group == nil then
+      return nil, "group `" .. task.escort.groupName .. "` not found"
+    end
+    local targetTypes = task.escort.targetTypes
+    if #targetTypes == 0 then
+      targetTypes = { "Planes" }
+    end
+    return {
+      id = "Escort",
+      params = {
+        groupId = group:getID(),
+        pos = task.escort.offset or { x = -500, y = 0, z = 200 },
+        lastWptIndexFlag = task.escort.lastWaypointIndex ~= nil,
+        lastWptIndex = task.escort.lastWaypointIndex,
+        engagementDistMax = task.escort.engagementDistance or 60000,
+        targetTypes = targetTypes,
+      }
+    }
+
+  elseif task.fireAtPoint ~= nil then
+    if task.fireAtPoint.point == nil then
+      return nil, "point must be specified"
+    end
+    return {
+      id = "FireAtPoint",
+      params = {
+        point = toVec2(task.fireAtPoint.point),
+        radius = task.fireAtPoint.radius,
+        expendQtyEnabled = task.fireAtPoint.expendQuantity ~= nil,
+        expendQty = task.fireAtPoint.expendQuantity,
+        weaponType = task.fireAtPoint.weaponType,
+      }
+    }
+
+  elseif task.hold ~= nil then
+    return { id = "Hold", params = {} }
+
+  elseif task.refueling ~= nil then
+    return { id = "Refueling", params = {} }
+
+  elseif task.fac ~= nil then
+    return {
+      id = "FAC",
+      params = {
+        frequency = task.fac.frequency,
+        modulation = GRPC.modulation[task.fac.modulation] or 0,
+        callname = task.fac.callname,
+        number = task.fac.number,
+        priority = task.fac.priority or 0,
+      }
+    }
+  end
+
+  return nil, "task must be specified"
+end
+
+local createCommand = function(command)
+  if command.start ~= nil then
+    return { id = "Start", params = {} }
+  elseif command.setFrequency ~= nil then
+    return {
+      id = "SetFrequency",
+      params = {
+        frequency = command.setFrequency.frequency,
+        modulation = GRPC.modulation[command.setFrequency.modulation] or 0,
+        power = command.setFrequency.power or 10,
+      }
+    }
+  elseif command.setInvisible ~= nil then
+    return { id = "SetInvisible", params = { value = command.setInvisible.value } }
+  elseif command.setImmortal ~= nil then
+    return { id = "SetImmortal", params = { value = command.setImmortal.value } }
+  elseif command.switchWaypoint ~= nil then
+    return {
+      id = "SwitchWaypoint",
+      params = {
+        fromWaypointIndex = command.switchWaypoint.fromWaypointIndex,
+        goToWaypointIndex = command.switchWaypoint.toWaypointIndex,
+      }
+    }
+  elseif command.stopRoute ~= nil then
+    return { id = "StopRoute", params = { value = command.stopRoute.value } }
+  elseif command.setCallsign ~= nil then
+    return {
+      id = "SetCallsign",
+      params = {
+        callname = command.setCallsign.callname,
+        number = command.setCallsign.number,
+      }
+    }
+  end
+
+  return nil
+end
+
+-- Get the group or unit referenced by the `name` oneof of a request.
+local getControllable = function(params)
+  local obj
+  if params.name == nil then
+    return nil, GRPC.errorInvalidArgument("No Group or Unit name provided")
+  elseif params.name.groupName then
+    obj = Group.getByName(params.name.groupName)
+  else
+    obj = Unit.getByName(params.name.unitName)
+  end
+
+  if obj == nil then
+    return nil, GRPC.errorNotFound("Could not find group or unit with provided name")
+  end
+
+  return obj
+end
+
+-- The `AI.Option` category (Air, Ground or Naval) for a group or unit.
+local getOptionCategory = function(obj)
+  local category
+  if obj.getUnits ~= nil then
+    category = obj:getCategory()
+  else
+    category = obj:getDesc().category
+  end
+
+  if category == Group.Category.AIRPLANE or category == Group.Category.HELICOPTER then
+    return "Air"
+  elseif category == Group.Category.SHIP then
+    return "Naval"
+  else
+    return "Ground"
+  end
+end
+
+GRPC.methods.setTask = function(params)
+  local obj, err = getControllable(params)
+  if obj == nil then
+    return err
+  end
+
+  local task, msg = GRPC.createTask(params.task)
+  if task == nil then
+    return GRPC.errorInvalidArgument(msg)
+  end
+
+  obj:getController():setTask(task)
+
+  return GRPC.success({})
+end
+
+GRPC.methods.pushTask = function(params)
+  local obj, err = getControllable(params)
+  if obj == nil then
+    return err
+  end
+
+  local task, msg = GRPC.createTask(params.task)
+  if task == nil then
+    return GRPC.errorInvalidArgument(msg)
+  end
+
+  obj:getController():pushTask(task)
+
+  return GRPC.success({})
+end
+
+GRPC.methods.popTask = function(params)
+  local obj, err = getControllable(params)
+  if obj == nil then
+    return err
+  end
+
+  obj:getController():popTask()
+
+  return GRPC.success({})
+end
+
+GRPC.methods.resetTask = function(params)
+  local obj, err = getControllable(params)
+  if obj == nil then
+    return err
+  end
+
+  obj:getController():resetTask()
+
+  return GRPC.success({})
+end
+
+GRPC.methods.setOption = function(params)
+  local obj, err = getControllable(params)
+  if obj == nil then
+    return err
+  end
+
+  local category = getOptionCategory(obj)
+  local option = params.option or {}
+
+  local name, value
+  if option.roe ~= nil then
+    name, value = "ROE", option.roe - 1
+  elseif option.reactionToThreat ~= nil then
+    name, value = "REACTION_ON_THREAT", option.reactionToThreat - 1
+  elseif option.radarUsing ~= nil then
+    name, value = "RADAR_USING", option.radarUsing - 1
+  elseif option.formation ~= nil then
+    if option.formation.type == 0 then
+      return GRPC.errorInvalidArgument("formation type cannot be unspecified (0)")
+    end
+    local distance = option.formation.distance
+    if distance == 0 then
+      distance = 1
+    end
+    -- DCS encodes formations as `type << 16 | distance`
+    name, value = "FORMATION", option.formation.type * 65536 + distance
+  else
+    return GRPC.errorInvalidArgument("option must be specified")
+  end
+
+  if value < 0 then
+    return GRPC.errorInvalidArgument("option value cannot be unspecified (0)")
+  end
+
+  local id = AI.Option[category].id[name]
+  if id == nil then
+    return GRPC.errorInvalidArgument(name .. " is not available for " .. category .. " groups")
+  end
+
+  -- formations are validated by DCS itself
+  local values = AI.Option[category].val[name]
+  if name ~= "FORMATION" and values ~= nil then
+    local valid = false
+    for _, v in pairs(values) do
+      if v == value then
+        valid = true
+      end
+    end
+    if not valid then
+      return GRPC.errorInvalidArgument("value not available for " .. category .. " groups")
+    end
+  end
+
+  obj:getController():setOption(id, value)
+
+  return GRPC.success({})
+end
+
+GRPC.methods.setCommand = function(params)
+  local obj, err = getControllable(params)
+  if obj == nil then
+    return err
+  end
+
+  local command = createCommand(params.command or {})
+  if command == nil then
+    return GRPC.errorInvalidArgument("command must be specified")
+  end
+
+  obj:getController():setCommand(command)
+
+  return GRPC.success({})
+end
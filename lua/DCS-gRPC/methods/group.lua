@@ -5,6 +5,130 @@
 
 local GRPC = GRPC
 
+local altitudeType = {
+  [0] = "BARO",
+  [1] = "BARO",
+  [2] = "RADIO",
+}
+
+-- DCS' waypoint `type` and `action` for each `common.WaypointAction`
+local waypointAction = {
+  [0] = { type = "Turning Point", action = "Turning Point" },
+  [1] = { type = "Turning Point", action = "Turning Point" },
+  [2] = { type = "Turning Point", action = "Fly Over Point" },
+  [3] = { type = "TakeOff", action = "From Runway" },
+  [4] = { type = "TakeOffParking", action = "From Parking Area" },
+  [5] = { type = "TakeOffParkingHot", action = "From Parking Area Hot" },
+  [6] = { type = "TakeOffGround", action = "From Ground Area" },
+  [7] = { type = "TakeOffGroundHot", action = "From Ground Area Hot" },
+  [8] = { type = "Land", action = "Landing" },
+  [9] = { type = "Turning Point", action = "Off Road" },
+  [10] = { type = "Turning Point", action = "On Road" },
+}
+
+-- Create the DCS route table for a list of `common.Waypoint`s. Returns `nil` and an error message
+-- if any of the waypoint's tasks is invalid.
+GRPC.createRoute = function(waypoints)
+  local points = {}
+
+  for i, waypoint in ipairs(waypoints) do
+    local tasks = {}
+    for j, waypointTask in ipairs(waypoint.tasks or {}) do
+      local task, err = GRPC.createTask(waypointTask)
+      if task == nil then
+        -- report zero-based indices, like the validation in the gRPC server does
+        return nil, "waypoints[" .. (i - 1) .. "].tasks[" .. (j - 1) .. "]: " .. err
+      end
+      task.number = j
+      task.enabled = true
+      task.auto = false
+      table.insert(tasks, task)
+    end
+
+    local position = coord.LLtoLO(waypoint.position.lat, waypoint.position.lon, 0)
+    local action = waypointAction[waypoint.action] or waypointAction[0]
+
+    local point = {
+      x = position.x,
+      y = position.z,
+      alt = waypoint.position.alt,
+      alt_type = altitudeType[waypoint.altitudeType] or "BARO",
+      type = action.type,
+      action = action.action,
+      speed = waypoint.speed,
+      speed_locked = true,
+      ETA = waypoint.eta or 0,
+      ETA_locked = waypoint.eta ~= nil,
+      formation_template = "",
+      task = {
+        id = "ComboTask",
+        params = {
+          tasks = tasks
+        }
+      }
+    }
+
+    if waypoint.airdromeId ~= nil then
+      point.airdromeId = waypoint.airdromeId
+    end
+    if waypoint.helipadId ~= nil then
+      point.helipadId = waypoint.helipadId
+      point.linkUnit = waypoint.helipadId
+    end
+    if waypoint.name ~= nil then
+      point.name = waypoint.name
+    end
+
+    table.insert(points, point)
+  end
+
+  return {
+    points = points
+  }
+end
+
+-- Convert the points of a DCS route table into `common.Waypoint`s. The reverse of
+-- `GRPC.createRoute`, except that the tasks of the waypoints are not converted.
+GRPC.exportRoute = function(points)
+  local waypoints = {}
+
+  for _, point in ipairs(points) do
+    local lat, lon = coord.LOtoLL({ x = point.x, y = 0, z = point.y })
+    local action = 1
+    for i, pointAction in ipairs(waypointAction) do
+      if pointAction.type == point.type and pointAction.action == point.action then
+        action = i
+        break
+      end
+    end
+
+    local waypoint = {
+      position = { lat = lat, lon = lon, alt = point.alt or 0 },
+      altitudeType = point.alt_type == "RADIO" and 2 or 1,
+      speed = point.speed or 0,
+      action = action,
+      tasks = {},
+    }
+
+    if point.ETA_locked and point.ETA ~= nil then
+      waypoint.eta = point.ETA
+    end
+    if point.airdromeId ~= nil then
+      waypoint.airdromeId = point.airdromeId
+    end
+    if point.helipadId ~= nil then
+      waypoint.helipadId = point.helipadId
+    end
+    if type(point.name) == "string" then
+      waypoint.name = point.name
+    end
+
+    table.insert(waypoints, waypoint)
+  end
+
+  return waypoints
+end
+
 GRPC.methods.getUnits = function(params)
   -- https://wiki.hoggitworld.com/view/DCS_func_getByName
   local group = Group.getByName(params.groupName)
@@ -47,4 +171,26 @@ GRPC.methods.groupDestroy = function(params)
   group:destroy()
 
   return GRPC.success({})
-end
\ No newline at end of file
+end
+
+GRPC.methods.setRoute = function(params)
+  local group = Group.getByName(params.groupName)
+  if group == nil then
+    return GRPC.errorNotFound("group does not exist")
+  end
+
+  local route, err = GRPC.createRoute(params.waypoints)
+  if route == nil then
+    return GRPC.errorInvalidArgument(err)
+  end
+
+  -- https://wiki.hoggitworld.com/view/DCS_task_mission
+  group:getController():setTask({
+    id = "Mission",
+    params = {
+      route = route,
+    },
+  })
+
+  return GRPC.success({})
+end
@@ -0,0 +1,111 @@
+--
+-- RPC land actions
+-- https://wiki.hoggitworld.com/view/DCS_singleton_land
+--
+
+local GRPC = GRPC
+local land = land
+
+local toPoint = function(position)
+  return coord.LLtoLO(position.lat, position.lon, position.alt or 0)
+end
+
+-- Answer a single request using `handler`, which returns the response, or `nil` and an error
+-- message.
+local single = function(handler)
+  return function(params)
+    local response, err = handler(params)
+    if response == nil then
+      return GRPC.errorInvalidArgument(err)
+    end
+    return GRPC.success(response)
+  end
+end
+
+-- Answer a batch of requests using `handler` (see `single`).
+local batch = function(handler)
+  return function(params)
+    local responses = {}
+    for i, request in ipairs(params.requests) do
+      local response, err = handler(request)
+      if response == nil then
+        return GRPC.errorInvalidArgument("requests[" .. (i - 1) .. "]: " .. err)
+      end
+      table.insert(responses, response)
+    end
+    return GRPC.success({ responses = responses })
+  end
+end
+
+local getHeight = function(request)
+  if request.position == nil then
+    return nil, "position must be specified"
+  end
+
+  local point = toPoint(request.position)
+  return { height = land.getHeight({ x = point.x, y = point.z }) }
+end
+
+local getSurfaceType = function(request)
+  if request.position == nil then
+    return nil, "position must be specified"
+  end
+
+  local point = toPoint(request.position)
+  -- the values of `land.SurfaceType` match the gRPC enum
+  return { surfaceType = land.getSurfaceType({ x = point.x, y = point.z }) }
+end
+
+local isVisible = function(request)
+  if request.from == nil or request.to == nil then
+    return nil, "from and to must be specified"
+  end
+
+  return { visible = land.isVisible(toPoint(request.from), toPoint(request.to)) }
+end
+
+local getIP = function(request)
+  if request.origin == nil then
+    return nil, "origin must be specified"
+  end
+  if request.distance <= 0 then
+    return nil, "distance must be positive"
+  end
+
+  local heading = math.rad(request.heading)
+  local pitch = math.rad(request.pitch)
+  local direction = {
+    x = math.cos(pitch) * math.cos(heading),
+    y = math.sin(pitch),
+    z = math.cos(pitch) * math.sin(heading),
+  }
+
+  local point = land.getIP(toPoint(request.origin), direction, request.distance)
+  if point == nil then
+    return {}
+  end
+  return { position = GRPC.exporters.position(point) }
+end
+
+local profileBetween = function(request)
+  if request.from == nil or request.to == nil then
+    return nil, "from and to must be specified"
+  end
+
+  local points = {}
+  for _, point in ipairs(land.profile(toPoint(request.from), toPoint(request.to)) or {}) do
+    table.insert(points, GRPC.exporters.position(point))
+  end
+  return { points = points }
+end
+
+GRPC.methods.getHeight = single(getHeight)
+GRPC.methods.getHeightBatch = batch(getHeight)
+GRPC.methods.getSurfaceType = single(getSurfaceType)
+GRPC.methods.getSurfaceTypeBatch = batch(getSurfaceType)
+GRPC.methods.isVisible = single(isVisible)
+GRPC.methods.isVisibleBatch = batch(isVisible)
+GRPC.methods.getIP = single(getIP)
+GRPC.methods.getIPBatch = batch(getIP)
+GRPC.methods.profileBetween = single(profileBetween)
+GRPC.methods.profileBetweenBatch = batch(profileBetween)
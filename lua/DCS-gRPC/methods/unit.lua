@@ -59,18 +59,33 @@ GRPC.methods.getUnitPosition = function(params)
   })
 end
 
-GRPC.methods.getUnitTransform = function(params)
-  -- https://wiki.hoggitworld.com/view/DCS_func_getByName
-  local unit = Unit.getByName(params.name)
-  if unit == nil then
-    return GRPC.errorNotFound("unit does not exist")
+-- https://wiki.hoggitworld.com/view/DCS_func_getAmmo
+local function unitAmmo(unit)
+  local ammo = {}
+  for _, item in ipairs(unit:getAmmo() or {}) do
+    table.insert(ammo, {
+      typeName = item.desc.typeName,
+      count = item.count,
+      category = item.desc.category,
+    })
   end
+  return ammo
+end
+
+local function unitVitals(unit)
+  return {
+    life = unit:getLife(),
+    initialLife = unit:getLife0(),
+    ammo = unitAmmo(unit),
+  }
+end
 
+local function unitTransform(unit)
   local cargos_on_board = {}
   if unit.getCargosOnBoard ~= nil then
-    cargo_on_board = unit:getCargosOnBoard()
+    local cargo_on_board = unit:getCargosOnBoard()
     if cargo_on_board ~= nil then
-      for k, v in pairs(cargo_on_board) do
+      for _, v in pairs(cargo_on_board) do
         table.insert(cargos_on_board, v:getName())
       end
     end
@@ -82,9 +97,42 @@ GRPC.methods.getUnitTransform = function(params)
   xform.fuel = unit:getFuel()
   xform.cargosOnBoard = cargos_on_board
 
+  return xform
+end
+
+GRPC.methods.getUnitTransform = function(params)
+  -- https://wiki.hoggitworld.com/view/DCS_func_getByName
+  local unit = Unit.getByName(params.name)
+  if unit == nil then
+    return GRPC.errorNotFound("unit does not exist")
+  end
+
   return GRPC.success({
     time = timer.getTime(),
-    rawTransform = xform,
+    rawTransform = unitTransform(unit),
+  })
+end
+
+GRPC.methods.getUnitTransforms = function(params)
+  local time = timer.getTime()
+  local transforms = {}
+
+  for _, name in ipairs(params.names) do
+    local unit = Unit.getByName(name)
+    -- units that do not exist are simply omitted from the result
+    if unit ~= nil then
+      transforms[name] = {
+        time = time,
+        rawTransform = unitTransform(unit),
+      }
+      if params.includeVitals then
+        transforms[name].vitals = unitVitals(unit)
+      end
+    end
+  end
+
+  return GRPC.success({
+    transforms = transforms,
   })
 end
 
@@ -108,6 +156,26 @@ GRPC.methods.getStaticTransform = function(params)
   })
 end
 
+GRPC.methods.getStaticTransforms = function(params)
+  local time = timer.getTime()
+  local transforms = {}
+
+  for _, name in ipairs(params.names) do
+    local object = StaticObject.getByName(name)
+    -- static objects that do not exist are simply omitted from the result
+    if object ~= nil then
+      transforms[name] = {
+        time = time,
+        rawTransform = GRPC.exporters.rawTransform(object),
+      }
+    end
+  end
+
+  return GRPC.success({
+    transforms = transforms,
+  })
+end
+
 GRPC.methods.getUnitPlayerName = function(params)
   -- https://wiki.hoggitworld.com/view/DCS_func_getByName
   local unit = Unit.getByName(params.name)
@@ -174,3 +242,83 @@ GRPC.methods.staticDestroy = function(params)
   static:destroy()
   return GRPC.success({})
 end
+
+GRPC.methods.getUnitLife = function(params)
+  local unit = Unit.getByName(params.name)
+  if unit == nil then
+    return GRPC.errorNotFound("unit `" .. tostring(params.name) .. "` does not exist")
+  end
+
+  return GRPC.success({
+    -- https://wiki.hoggitworld.com/view/DCS_func_getLife
+    life = unit:getLife(),
+    -- https://wiki.hoggitworld.com/view/DCS_func_getLife0
+    initialLife = unit:getLife0(),
+  })
+end
+
+GRPC.methods.getUnitFuel = function(params)
+  local unit = Unit.getByName(params.name)
+  if unit == nil then
+    return GRPC.errorNotFound("unit `" .. tostring(params.name) .. "` does not exist")
+  end
+
+  return GRPC.success({
+    fuel = unit:getFuel(),
+  })
+end
+
+GRPC.methods.getUnitAmmo = function(params)
+  local unit = Unit.getByName(params.name)
+  if unit == nil then
+    return GRPC.errorNotFound("unit `" .. tostring(params.name) .. "` does not exist")
+  end
+
+  return GRPC.success({
+    ammo = unitAmmo(unit),
+  })
+end
+
+GRPC.methods.getUnitSensors = function(params)
+  local unit = Unit.getByName(params.name)
+  if unit == nil then
+    return GRPC.errorNotFound("unit `" .. tostring(params.name) .. "` does not exist")
+  end
+
+  -- sensors are grouped by their `Unit.SensorType`
+  local sensors = {}
+  for sensorType, typeSensors in pairs(unit:getSensors() or {}) do
+    for _, sensor in ipairs(typeSensors) do
+      table.insert(sensors, {
+        type = sensorType + 1, -- Increment for non zero-indexed gRPC enum
+        typeName = sensor.typeName or "",
+      })
+    end
+  end
+
+  return GRPC.success({
+    sensors = sensors,
+  })
+end
+
+GRPC.methods.isUnitInAir = function(params)
+  local unit = Unit.getByName(params.name)
+  if unit == nil then
+    return GRPC.errorNotFound("unit `" .. tostring(params.name) .. "` does not exist")
+  end
+
+  return GRPC.success({
+    inAir = unit:inAir(),
+  })
+end
+
+GRPC.methods.getUnitNumber = function(params)
+  local unit = Unit.getByName(params.name)
+  if unit == nil then
+    return GRPC.errorNotFound("unit `" .. tostring(params.name) .. "` does not exist")
+  end
+
+  return GRPC.success({
+    number = unit:getNumber(),
+  })
+end
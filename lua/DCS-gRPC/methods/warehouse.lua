@@ -0,0 +1,248 @@
+--
+-- RPC warehouse actions
+-- https://wiki.hoggitworld.com/view/DCS_Class_Warehouse
+--
+
+local GRPC = GRPC
+
+-- The names of DCS' liquid types, as used in `WarehouseUpdate`s
+local liquidNames = {
+  [0] = "jet_fuel",
+  [1] = "aviation_gasoline",
+  [2] = "mw50",
+  [3] = "diesel",
+}
+
+local getWarehouse = function(airbaseName)
+  local airbase = Airbase.getByName(airbaseName)
+  if airbase == nil or not airbase:isExist() then
+    return nil, GRPC.errorNotFound("airbase `" .. tostring(airbaseName) .. "` does not exist")
+  end
+
+  -- https://wiki.hoggitworld.com/view/DCS_func_getWarehouse
+  local warehouse = airbase:getWarehouse()
+  if warehouse == nil then
+    return nil, GRPC.errorNotFound("airbase `" .. tostring(airbaseName) .. "` has no warehouse")
+  end
+  return warehouse
+end
+
+local getItem = function(params)
+  if params.itemName == nil or params.itemName == "" then
+    return nil, GRPC.errorInvalidArgument("item name must be specified")
+  end
+  return getWarehouse(params.airbaseName)
+end
+
+local getLiquid = function(params)
+  local liquidType = params.type - 1 -- Decrement for non zero-indexed gRPC enum
+  if liquidNames[liquidType] == nil then
+    return nil, nil, GRPC.errorInvalidArgument("liquid type must be specified")
+  end
+  if params.amount < 0 then
+    return nil, nil, GRPC.errorInvalidArgument("amount must not be negative")
+  end
+
+  local warehouse, err = getWarehouse(params.airbaseName)
+  return warehouse, liquidType, err
+end
+
+local emitUpdates = function(airbaseName, updates)
+  if #updates == 0 then
+    return
+  end
+
+  GRPC.event({
+    time = timer.getTime(),
+    event = {
+      type = "warehouseUpdate",
+      airbaseName = airbaseName,
+      updates = updates,
+    },
+  })
+end
+
+local emitUpdate = function(airbaseName, wsType, adjustment)
+  adjustment = math.floor(adjustment + 0.5)
+  if adjustment ~= 0 then
+    emitUpdates(airbaseName, { { wsType = wsType, adjustment = adjustment } })
+  end
+end
+
+-- The count of all aircraft and weapons, and the (rounded) amount of all liquids by their name
+local snapshot = function(warehouse)
+  local inventory = warehouse:getInventory()
+  local items = {}
+  for name, count in pairs(inventory.aircraft or {}) do
+    items[name] = count
+  end
+  for name, count in pairs(inventory.weapon or {}) do
+    items[name] = count
+  end
+  for liquidType, name in pairs(liquidNames) do
+    items[name] = math.floor((warehouse:getLiquidAmount(liquidType) or 0) + 0.5)
+  end
+  return items
+end
+
+-- Emit all changes of the warehouse since it got polled the last time, and start tracking it if
+-- it isn't tracked yet.
+local poll = function(airbaseName, warehouse)
+  local current = snapshot(warehouse)
+  local previous = GRPC.state.tracked_warehouses[airbaseName]
+  GRPC.state.tracked_warehouses[airbaseName] = current
+  if previous == nil then
+    return
+  end
+
+  local updates = {}
+  for name, count in pairs(current) do
+    if count ~= (previous[name] or 0) then
+      table.insert(updates, { wsType = name, adjustment = count - (previous[name] or 0) })
+    end
+  end
+  for name, count in pairs(previous) do
+    if current[name] == nil and count ~= 0 then
+      table.insert(updates, { wsType = name, adjustment = -count })
+    end
+  end
+  table.sort(updates, function(a, b) return a.wsType < b.wsType end)
+  emitUpdates(airbaseName, updates)
+end
+
+-- Emit the changes made to all tracked warehouses by DCS (e.g. by rearming or refueling) or by
+-- other scripts.
+GRPC.pollWarehouses = function()
+  for airbaseName, _ in pairs(GRPC.state.tracked_warehouses) do
+    local warehouse = getWarehouse(airbaseName)
+    if warehouse == nil then
+      GRPC.state.tracked_warehouses[airbaseName] = nil
+    else
+      poll(airbaseName, warehouse)
+    end
+  end
+end
+
+-- Apply a change to the warehouse via `apply`, which returns the adjustment of the given item or
+-- liquid, and emit it. Changes made by others in the meantime are emitted separately beforehand.
+local change = function(airbaseName, warehouse, wsType, apply)
+  poll(airbaseName, warehouse)
+  emitUpdate(airbaseName, wsType, apply())
+  GRPC.state.tracked_warehouses[airbaseName] = snapshot(warehouse)
+end
+
+local sortedItems = function(items)
+  local result = {}
+  for name, count in pairs(items or {}) do
+    table.insert(result, { name = name, count = count })
+  end
+  table.sort(result, function(a, b) return a.name < b.name end)
+  return result
+end
+
+GRPC.methods.getInventory = function(params)
+  local warehouse, err = getWarehouse(params.airbaseName)
+  if warehouse == nil then
+    return err
+  end
+
+  poll(params.airbaseName, warehouse)
+
+  local inventory = warehouse:getInventory()
+  local liquids = {}
+  for liquidType = 0, 3 do
+    table.insert(liquids, {
+      type = liquidType + 1, -- Increment for non zero-indexed gRPC enum
+      amount = warehouse:getLiquidAmount(liquidType) or 0,
+    })
+  end
+
+  return GRPC.success({
+    aircraft = sortedItems(inventory.aircraft),
+    weapons = sortedItems(inventory.weapon),
+    liquids = liquids,
+  })
+end
+
+GRPC.methods.warehouseAddItem = function(params)
+  local warehouse, err = getItem(params)
+  if warehouse == nil then
+    return err
+  end
+
+  change(params.airbaseName, warehouse, params.itemName, function()
+    warehouse:addItem(params.itemName, params.count)
+    return params.count
+  end)
+  return GRPC.success({})
+end
+
+GRPC.methods.warehouseRemoveItem = function(params)
+  local warehouse, err = getItem(params)
+  if warehouse == nil then
+    return err
+  end
+
+  change(params.airbaseName, warehouse, params.itemName, function()
+    -- DCS does not remove more items than there are
+    local before = warehouse:getItemCount(params.itemName)
+    warehouse:removeItem(params.itemName, params.count)
+    return warehouse:getItemCount(params.itemName) - before
+  end)
+  return GRPC.success({})
+end
+
+GRPC.methods.warehouseSetItem = function(params)
+  local warehouse, err = getItem(params)
+  if warehouse == nil then
+    return err
+  end
+
+  change(params.airbaseName, warehouse, params.itemName, function()
+    local before = warehouse:getItemCount(params.itemName)
+    warehouse:setItem(params.itemName, params.count)
+    return params.count - before
+  end)
+  return GRPC.success({})
+end
+
+GRPC.methods.warehouseAddLiquid = function(params)
+  local warehouse, liquidType, err = getLiquid(params)
+  if warehouse == nil then
+    return err
+  end
+
+  change(params.airbaseName, warehouse, liquidNames[liquidType], function()
+    warehouse:addLiquid(liquidType, params.amount)
+    return params.amount
+  end)
+  return GRPC.success({})
+end
+
+GRPC.methods.warehouseRemoveLiquid = function(params)
+  local warehouse, liquidType, err = getLiquid(params)
+  if warehouse == nil then
+    return err
+  end
+
+  change(params.airbaseName, warehouse, liquidNames[liquidType], function()
+    local before = warehouse:getLiquidAmount(liquidType)
+    warehouse:removeLiquid(liquidType, params.amount)
+    return warehouse:getLiquidAmount(liquidType) - before
+  end)
+  return GRPC.success({})
+end
+
+GRPC.methods.warehouseSetLiquid = function(params)
+  local warehouse, liquidType, err = getLiquid(params)
+  if warehouse == nil then
+    return err
+  end
+
+  change(params.airbaseName, warehouse, liquidNames[liquidType], function()
+    local before = warehouse:getLiquidAmount(liquidType)
+    warehouse:setLiquidAmount(liquidType, params.amount)
+    return params.amount - before
+  end)
+  return GRPC.success({})
+end
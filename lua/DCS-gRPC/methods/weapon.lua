@@ -28,6 +28,26 @@ GRPC.methods.getWeaponTransform = function(params)
   })
 end
 
+GRPC.methods.getWeaponTransforms = function(params)
+  local time = timer.getTime()
+  local transforms = {}
+
+  for _, id in ipairs(params.ids) do
+    local weapon = GRPC.state.tracked_weapons[id]
+    if weapon ~= nil and weapon.isExist ~= nil and weapon:isExist() then
+      transforms[id] = {
+        time = time,
+        rawTransform = GRPC.exporters.rawTransform(weapon)
+      }
+    end
+    -- Weapons that do not exist anymore are omitted from the result, but are left to be pruned by
+    -- the weapon update polling, which also emits that they are gone.
+  end
+
+  return GRPC.success({
+    transforms = transforms
+  })
+end
 
 GRPC.methods.weaponDestroy = function(params)
   local weapon = GRPC.state.tracked_weapons[params.id]
@@ -60,3 +80,33 @@ GRPC.methods.getTrackedWeaponIds = function()
   })
 
 end
+
+
+GRPC.methods.getTrackedWeapons = function()
+
+  local weapons = {}
+
+  for _, weapon in pairs(GRPC.state.tracked_weapons) do
+    if weapon.isExist ~= nil and weapon:isExist() then
+      local tracked = {
+        weapon = GRPC.exporters.weapon(weapon)
+      }
+
+      -- https://wiki.hoggitworld.com/view/DCS_func_getLauncher
+      local launcher = weapon:getLauncher()
+      if launcher ~= nil and launcher:isExist()
+        and Object.getCategory(launcher) == Object.Category.UNIT then
+        tracked.launcher = GRPC.exporters.unit(launcher)
+      end
+
+      table.insert(weapons, tracked)
+    end
+    -- weapons that do not exist anymore are left to be pruned by the weapon update polling
+  end
+
+  return GRPC.success({
+    weapons = weapons,
+    time = timer.getTime()
+  })
+
+end
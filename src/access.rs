@@ -0,0 +1,474 @@
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection, Row};
+use stubs::access::v0::{Ban, WhitelistEntry};
+
+use crate::db::Database;
+
+/// Bans and a whitelist that are enforced when players try to connect. All entries are kept in
+/// memory (to check connecting players without touching the disk) and persisted in an SQLite
+/// database.
+#[derive(Clone)]
+pub struct Access {
+    db: Database,
+    entries: Arc<Mutex<Entries>>,
+}
+
+struct Entries {
+    bans: Vec<Ban>,
+    whitelist: Vec<WhitelistEntry>,
+    whitelist_enabled: bool,
+}
+
+/// Why a player is not allowed to connect.
+pub struct Denied {
+    /// The id of the ban that got enforced, `None` if rejected due to the whitelist.
+    pub ban_id: Option<i64>,
+    /// The reason shown to the player.
+    pub reason: String,
+}
+
+impl Access {
+    /// Open (or create) the database at the given path and load all its entries.
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        let db = Database::open(
+            path,
+            "CREATE TABLE IF NOT EXISTS bans (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                ucid TEXT,
+                ip_address TEXT,
+                player_name TEXT,
+                reason TEXT NOT NULL,
+                issuer TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                expires_at INTEGER
+            );
+            CREATE TABLE IF NOT EXISTS whitelist (
+                ucid TEXT PRIMARY KEY NOT NULL,
+                note TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY NOT NULL,
+                value TEXT NOT NULL
+            );",
+        )?;
+
+        let entries = db.run_blocking(|db| -> Result<_, Error> {
+            let bans = db
+                .prepare(
+                    "SELECT id, ucid, ip_address, player_name, reason, issuer, created_at, expires_at
+                    FROM bans ORDER BY id",
+                )?
+                .query_map([], ban_from_row)?
+                .collect::<Result<Vec<_>, _>>()?;
+            let whitelist = db
+                .prepare("SELECT ucid, note, created_at FROM whitelist ORDER BY created_at")?
+                .query_map([], |row| {
+                    Ok(WhitelistEntry {
+                        ucid: row.get(0)?,
+                        note: row.get(1)?,
+                        created_at: row.get(2)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            let whitelist_enabled = db
+                .prepare("SELECT value FROM settings WHERE key = 'whitelist_enabled'")?
+                .query_map([], |row| row.get::<_, String>(0))?
+                .next()
+                .transpose()?
+                .as_deref()
+                == Some("true");
+            Ok(Entries {
+                bans,
+                whitelist,
+                whitelist_enabled,
+            })
+        })?;
+
+        Ok(Self {
+            db,
+            entries: Arc::new(Mutex::new(entries)),
+        })
+    }
+
+    /// The in-memory entries. The lock is only ever held briefly and never across any I/O, so
+    /// that checking connecting players (on the simulation thread) doesn't block.
+    fn entries(&self) -> MutexGuard<'_, Entries> {
+        // the entries are only replaced after the database got updated, so they are consistent
+        // even if another thread panicked while holding the lock
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Check whether the player with the given UCID and address (`ip:port`) is allowed to
+    /// connect.
+    pub fn check(&self, ucid: &str, addr: &str) -> Option<Denied> {
+        let entries = self.entries();
+        let ip = ip_address(addr);
+        let now = now();
+
+        let ban = entries.bans.iter().find(|ban| {
+            !is_expired(ban, now)
+                && (ban.ucid.as_deref() == Some(ucid) || ban.ip_address.as_deref() == Some(ip))
+        });
+        if let Some(ban) = ban {
+            let mut reason = String::from("You are banned from this server");
+            if let Some(expires_at) = ban.expires_at {
+                let remaining = expires_at.saturating_sub(now);
+                reason += &format!(" for another {}", format_duration(remaining));
+            }
+            if !ban.reason.is_empty() {
+                reason += &format!(": {}", ban.reason);
+            }
+            return Some(Denied {
+                ban_id: Some(ban.id),
+                reason,
+            });
+        }
+
+        if entries.whitelist_enabled && !entries.whitelist.iter().any(|entry| entry.ucid == ucid) {
+            return Some(Denied {
+                ban_id: None,
+                reason: String::from("This server only allows whitelisted players"),
+            });
+        }
+
+        None
+    }
+
+    pub async fn add_ban(&self, mut ban: Ban) -> Result<Ban, Error> {
+        if ban.ucid.is_none() && ban.ip_address.is_none() {
+            return Err(Error::MissingBanTarget);
+        }
+
+        ban = self
+            .with_db(move |db| {
+                db.execute(
+                    "INSERT INTO bans
+                    (ucid, ip_address, player_name, reason, issuer, created_at, expires_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        ban.ucid,
+                        ban.ip_address,
+                        ban.player_name,
+                        ban.reason,
+                        ban.issuer,
+                        ban.created_at,
+                        ban.expires_at
+                    ],
+                )?;
+                ban.id = db.last_insert_rowid();
+                Ok(ban)
+            })
+            .await?;
+        self.entries().bans.push(ban.clone());
+        Ok(ban)
+    }
+
+    /// List all bans, optionally including the ones that already expired.
+    pub fn bans(&self, include_expired: bool) -> Vec<Ban> {
+        let now = now();
+        self.entries()
+            .bans
+            .iter()
+            .filter(|ban| include_expired || !is_expired(ban, now))
+            .cloned()
+            .collect()
+    }
+
+    /// Update the reason, issuer and expiry of a ban. Returns `None` if there is no ban with the
+    /// given id.
+    pub async fn update_ban(
+        &self,
+        id: i64,
+        reason: String,
+        issuer: String,
+        expires_at: Option<u64>,
+    ) -> Result<Option<Ban>, Error> {
+        let updated = {
+            let (reason, issuer) = (reason.clone(), issuer.clone());
+            self.with_db(move |db| {
+                Ok(db.execute(
+                    "UPDATE bans SET reason = ?2, issuer = ?3, expires_at = ?4 WHERE id = ?1",
+                    params![id, reason, issuer, expires_at],
+                )?)
+            })
+            .await?
+        };
+        if updated == 0 {
+            return Ok(None);
+        }
+
+        Ok(self
+            .entries()
+            .bans
+            .iter_mut()
+            .find(|ban| ban.id == id)
+            .map(|ban| {
+                ban.reason = reason;
+                ban.issuer = issuer;
+                ban.expires_at = expires_at;
+                ban.clone()
+            }))
+    }
+
+    /// Remove a ban. Returns `false` if there is no ban with the given id.
+    pub async fn remove_ban(&self, id: i64) -> Result<bool, Error> {
+        let removed = self
+            .with_db(move |db| Ok(db.execute("DELETE FROM bans WHERE id = ?1", params![id])?))
+            .await?;
+        self.entries().bans.retain(|ban| ban.id != id);
+        Ok(removed > 0)
+    }
+
+    /// Add a player to the whitelist, or update the note of an already whitelisted player.
+    pub async fn add_to_whitelist(&self, entry: WhitelistEntry) -> Result<(), Error> {
+        let entry = self
+            .with_db(move |db| {
+                db.execute(
+                    "INSERT INTO whitelist (ucid, note, created_at) VALUES (?1, ?2, ?3)
+                    ON CONFLICT (ucid) DO UPDATE SET note = ?2",
+                    params![entry.ucid, entry.note, entry.created_at],
+                )?;
+                Ok(entry)
+            })
+            .await?;
+        let mut entries = self.entries();
+        match entries.whitelist.iter_mut().find(|e| e.ucid == entry.ucid) {
+            Some(existing) => existing.note = entry.note,
+            None => entries.whitelist.push(entry),
+        }
+        Ok(())
+    }
+
+    /// Remove a player from the whitelist. Returns `false` if the player wasn't whitelisted.
+    pub async fn remove_from_whitelist(&self, ucid: String) -> Result<bool, Error> {
+        let (removed, ucid) = self
+            .with_db(move |db| {
+                let removed = db.execute("DELETE FROM whitelist WHERE ucid = ?1", params![ucid])?;
+                Ok((removed, ucid))
+            })
+            .await?;
+        self.entries().whitelist.retain(|entry| entry.ucid != ucid);
+        Ok(removed > 0)
+    }
+
+    /// Whether the whitelist is enforced, and all whitelisted players.
+    pub fn whitelist(&self) -> (bool, Vec<WhitelistEntry>) {
+        let entries = self.entries();
+        (entries.whitelist_enabled, entries.whitelist.clone())
+    }
+
+    pub async fn set_whitelist_enabled(&self, enabled: bool) -> Result<(), Error> {
+        self.with_db(move |db| {
+            db.execute(
+                "INSERT INTO settings (key, value) VALUES ('whitelist_enabled', ?1)
+                    ON CONFLICT (key) DO UPDATE SET value = ?1",
+                params![enabled.to_string()],
+            )?;
+            Ok(())
+        })
+        .await?;
+        self.entries().whitelist_enabled = enabled;
+        Ok(())
+    }
+
+    /// Run the given database operation on a thread where blocking is acceptable.
+    async fn with_db<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> Result<T, Error> + Send + 'static,
+    {
+        self.db.run(f).await
+    }
+}
+
+fn ban_from_row(row: &Row<'_>) -> rusqlite::Result<Ban> {
+    Ok(Ban {
+        id: row.get(0)?,
+        ucid: row.get(1)?,
+        ip_address: row.get(2)?,
+        player_name: row.get(3)?,
+        reason: row.get(4)?,
+        issuer: row.get(5)?,
+        created_at: row.get(6)?,
+        expires_at: row.get(7)?,
+    })
+}
+
+fn is_expired(ban: &Ban, now: u64) -> bool {
+    matches!(ban.expires_at, Some(expires_at) if expires_at <= now)
+}
+
+/// Strip the port from the `ip:port` address DCS provides for connecting players.
+pub fn ip_address(addr: &str) -> &str {
+    match addr.parse::<SocketAddr>() {
+        Ok(_) => addr
+            .rsplit_once(':')
+            .map(|(ip, _)| ip.trim_start_matches('[').trim_end_matches(']'))
+            .unwrap_or(addr),
+        Err(_) => addr,
+    }
+}
+
+/// Format a duration in seconds in a human readable way, e.g. `2d 3h` or `15m`.
+fn format_duration(secs: u64) -> String {
+    let days = secs / 86400;
+    let hours = secs % 86400 / 3600;
+    let minutes = secs % 3600 / 60;
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        // round up to not show `0m` for the last minute
+        format!("{}m", minutes.max(1))
+    }
+}
+
+/// The current time in unixtime.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Db(#[from] crate::db::Error),
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("either a ucid or an ip address is required to ban a player")]
+    MissingBanTarget,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn access(bans: Vec<Ban>, whitelist: &[&str], whitelist_enabled: bool) -> Access {
+        let access = Access::open(Path::new(":memory:")).unwrap();
+        *access.entries() = Entries {
+            bans,
+            whitelist: whitelist
+                .iter()
+                .map(|ucid| WhitelistEntry {
+                    ucid: ucid.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            whitelist_enabled,
+        };
+        access
+    }
+
+    fn ban(id: i64, ucid: Option<&str>, ip_address: Option<&str>) -> Ban {
+        Ban {
+            id,
+            ucid: ucid.map(String::from),
+            ip_address: ip_address.map(String::from),
+            reason: String::from("griefing"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_ucid() {
+        let access = access(vec![ban(1, Some("banned"), None)], &[], false);
+        let denied = access.check("banned", "1.2.3.4:10308").unwrap();
+        assert_eq!(denied.ban_id, Some(1));
+        assert_eq!(denied.reason, "You are banned from this server: griefing");
+        assert!(access.check("other", "1.2.3.4:10308").is_none());
+    }
+
+    #[test]
+    fn test_check_ip_address() {
+        let access = access(
+            vec![
+                ban(1, None, Some("1.2.3.4")),
+                ban(2, None, Some("2001:db8::1")),
+            ],
+            &[],
+            false,
+        );
+        assert_eq!(
+            access.check("player", "1.2.3.4:10308").unwrap().ban_id,
+            Some(1)
+        );
+        assert_eq!(
+            access
+                .check("player", "[2001:db8::1]:10308")
+                .unwrap()
+                .ban_id,
+            Some(2)
+        );
+        assert!(access.check("player", "1.2.3.5:10308").is_none());
+    }
+
+    #[test]
+    fn test_check_expiry() {
+        let now = now();
+        let mut expired = ban(1, Some("player"), None);
+        expired.expires_at = Some(now - 1);
+        let access = access(vec![expired], &[], false);
+        assert!(access.check("player", "1.2.3.4:10308").is_none());
+        assert!(access.bans(false).is_empty());
+        assert_eq!(access.bans(true).len(), 1);
+
+        let mut temporary = ban(2, Some("player"), None);
+        temporary.expires_at = Some(now + 2 * 3600 + 300);
+        access.entries().bans.push(temporary);
+        let denied = access.check("player", "1.2.3.4:10308").unwrap();
+        assert_eq!(denied.ban_id, Some(2));
+        assert!(denied
+            .reason
+            .starts_with("You are banned from this server for another 2h "));
+    }
+
+    #[test]
+    fn test_check_whitelist() {
+        let access = access(
+            vec![ban(1, Some("banned"), None)],
+            &["friend", "banned"],
+            true,
+        );
+        assert!(access.check("friend", "1.2.3.4:10308").is_none());
+        // bans take precedence over the whitelist
+        assert_eq!(
+            access.check("banned", "1.2.3.4:10308").unwrap().ban_id,
+            Some(1)
+        );
+        let denied = access.check("stranger", "1.2.3.4:10308").unwrap();
+        assert_eq!(denied.ban_id, None);
+        assert_eq!(denied.reason, "This server only allows whitelisted players");
+
+        access.entries().whitelist_enabled = false;
+        assert!(access.check("stranger", "1.2.3.4:10308").is_none());
+    }
+
+    #[test]
+    fn test_ip_address() {
+        assert_eq!(ip_address("1.2.3.4:10308"), "1.2.3.4");
+        assert_eq!(ip_address("[2001:db8::1]:10308"), "2001:db8::1");
+        assert_eq!(ip_address("1.2.3.4"), "1.2.3.4");
+        assert_eq!(ip_address("unknown"), "unknown");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "1m");
+        assert_eq!(format_duration(59), "1m");
+        assert_eq!(format_duration(60), "1m");
+        assert_eq!(format_duration(3599), "59m");
+        assert_eq!(format_duration(3600), "1h 0m");
+        assert_eq!(format_duration(3660), "1h 1m");
+        assert_eq!(format_duration(86399), "23h 59m");
+        assert_eq!(format_duration(86400), "1d 0h");
+        assert_eq!(format_duration(2 * 86400 + 3 * 3600 + 59), "2d 3h");
+    }
+}
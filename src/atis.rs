@@ -0,0 +1,538 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use stubs::airbase::v0::airbase_service_server::AirbaseService;
+use stubs::airbase::v0::{GetRunwaysRequest, Runway};
+use stubs::atis::v0::Atis;
+use stubs::atmosphere::v0::atmosphere_service_server::AtmosphereService;
+use stubs::atmosphere::v0::{GetTemperatureAndPressureRequest, GetWindRequest};
+use stubs::common::v0::{Coalition, InputPosition, Vector};
+use stubs::custom::v0::custom_service_server::CustomService;
+use stubs::custom::v0::GetMagneticDeclinationRequest;
+use stubs::tts::v0::tts_service_server::TtsService;
+use stubs::tts::v0::TransmitRequest;
+use stubs::world::v0::world_service_server::WorldService;
+use stubs::world::v0::GetAirbasesRequest;
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+use tonic::{Request, Status};
+
+use crate::brevity::METERS_PER_NAUTICAL_MILE;
+use crate::rpc::{MissionRpc, Tts};
+use crate::shutdown::ShutdownHandle;
+
+/// The pause between two transmissions of the report.
+const PAUSE: Duration = Duration::from_secs(5);
+/// How long to wait before retrying after the report could not be assembled or transmitted.
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+/// The altitude above the airbase (in meters) at which the wind is measured.
+const WIND_MEASUREMENT_HEIGHT: f64 = 10.0;
+/// Winds below this speed (in knots) are reported as calm.
+const CALM_WIND: u32 = 3;
+const PASCALS_PER_INCH_OF_MERCURY: f64 = 3386.389;
+
+const PHONETIC_ALPHABET: [&str; 26] = [
+    "Alpha", "Bravo", "Charlie", "Delta", "Echo", "Foxtrot", "Golf", "Hotel", "India", "Juliett",
+    "Kilo", "Lima", "Mike", "November", "Oscar", "Papa", "Quebec", "Romeo", "Sierra", "Tango",
+    "Uniform", "Victor", "Whiskey", "X-ray", "Yankee", "Zulu",
+];
+
+/// The running ATIS broadcasts, keyed by the name of their airbase. The broadcasts run until they
+/// are stopped or the server shuts down.
+#[derive(Clone)]
+pub struct AtisBroadcasts(Arc<Inner>);
+
+struct Inner {
+    mission: MissionRpc,
+    tts: Arc<Tts>,
+    shutdown_signal: ShutdownHandle,
+    broadcasts: Mutex<HashMap<String, Broadcast>>,
+}
+
+struct Broadcast {
+    frequency: u64,
+    coalition: i32,
+    state: Arc<Mutex<State>>,
+    task: JoinHandle<()>,
+}
+
+#[derive(Default)]
+struct State {
+    /// The index of the current information letter.
+    information: usize,
+    weather: Option<Weather>,
+    report: Option<String>,
+}
+
+/// The conditions as reported, i.e. already rounded. Changes increment the information letter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Weather {
+    /// Magnetic direction the wind is coming from (in degrees, rounded to ten degrees), or `None`
+    /// if calm.
+    wind_direction: Option<u32>,
+    /// In knots.
+    wind_speed: u32,
+    /// In degrees Celsius.
+    temperature: i32,
+    /// In hectopascals.
+    qnh: u32,
+    /// In hundredths of inches of mercury.
+    altimeter: u32,
+    active_runway: Option<String>,
+}
+
+/// What is needed to assemble the report of an airbase.
+struct Airbase {
+    name: String,
+    position: InputPosition,
+    runways: Vec<Runway>,
+}
+
+impl AtisBroadcasts {
+    pub fn new(mission: MissionRpc, tts: Arc<Tts>, shutdown_signal: ShutdownHandle) -> Self {
+        Self(Arc::new(Inner {
+            mission,
+            tts,
+            shutdown_signal,
+            broadcasts: Default::default(),
+        }))
+    }
+
+    /// Start broadcasting the ATIS of the given airbase, replacing a running broadcast of the same
+    /// airbase.
+    pub async fn start(
+        &self,
+        airbase: String,
+        frequency: u64,
+        coalition: i32,
+    ) -> Result<(), Status> {
+        if frequency == 0 {
+            return Err(Status::invalid_argument("frequency is required"));
+        }
+
+        let position = self
+            .0
+            .mission
+            .get_airbases(Request::new(GetAirbasesRequest {
+                coalition: Coalition::All.into(),
+            }))
+            .await?
+            .into_inner()
+            .airbases
+            .into_iter()
+            .find(|a| a.name == airbase)
+            .and_then(|a| a.position)
+            .ok_or_else(|| Status::not_found(format!("airbase `{airbase}` not found")))?;
+        let runways = self
+            .0
+            .mission
+            .get_runways(Request::new(GetRunwaysRequest {
+                name: airbase.clone(),
+            }))
+            .await?
+            .into_inner()
+            .runways;
+
+        let state = Arc::new(Mutex::new(State::default()));
+        let task = tokio::spawn(self.clone().broadcast(
+            Airbase {
+                name: airbase.clone(),
+                position: InputPosition {
+                    lat: position.lat,
+                    lon: position.lon,
+                    alt: position.alt,
+                },
+                runways,
+            },
+            frequency,
+            coalition,
+            state.clone(),
+        ));
+
+        let previous = self.0.broadcasts.lock().unwrap().insert(
+            airbase,
+            Broadcast {
+                frequency,
+                coalition,
+                state,
+                task,
+            },
+        );
+        if let Some(previous) = previous {
+            previous.task.abort();
+        }
+
+        Ok(())
+    }
+
+    /// Stop broadcasting the ATIS of the given airbase. Returns `false` if there was no broadcast
+    /// for it.
+    pub fn stop(&self, airbase: &str) -> bool {
+        match self.0.broadcasts.lock().unwrap().remove(airbase) {
+            Some(broadcast) => {
+                broadcast.task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn list(&self) -> Vec<Atis> {
+        let broadcasts = self.0.broadcasts.lock().unwrap();
+        let mut list = broadcasts
+            .iter()
+            .map(|(airbase, broadcast)| {
+                let state = broadcast.state.lock().unwrap();
+                Atis {
+                    airbase: airbase.clone(),
+                    frequency: broadcast.frequency,
+                    coalition: broadcast.coalition,
+                    information: PHONETIC_ALPHABET[state.information].to_string(),
+                    report: state.report.clone(),
+                }
+            })
+            .collect::<Vec<_>>();
+        list.sort_by(|a, b| a.airbase.cmp(&b.airbase));
+        list
+    }
+
+    async fn broadcast(
+        self,
+        airbase: Airbase,
+        frequency: u64,
+        coalition: i32,
+        state: Arc<Mutex<State>>,
+    ) {
+        let mut shutdown_signal = self.0.shutdown_signal.signal();
+        loop {
+            let delay = match self.transmit(&airbase, frequency, coalition, &state).await {
+                Ok(()) => PAUSE,
+                Err(err) => {
+                    log::error!("Failed to broadcast ATIS of {}: {}", airbase.name, err);
+                    RETRY_DELAY
+                }
+            };
+
+            tokio::select! {
+                _ = &mut shutdown_signal => break,
+                _ = sleep(delay) => {}
+            }
+        }
+    }
+
+    /// Assemble the current report and transmit it once.
+    async fn transmit(
+        &self,
+        airbase: &Airbase,
+        frequency: u64,
+        coalition: i32,
+        state: &Mutex<State>,
+    ) -> Result<(), Status> {
+        let weather = self.weather(airbase).await?;
+        let report = {
+            let mut state = state.lock().unwrap();
+            if matches!(&state.weather, Some(previous) if *previous != weather) {
+                state.information = (state.information + 1) % PHONETIC_ALPHABET.len();
+            }
+            let report = format_report(
+                &airbase.name,
+                PHONETIC_ALPHABET[state.information],
+                &weather,
+            );
+            state.weather = Some(weather);
+            state.report = Some(report.clone());
+            report
+        };
+
+        self.0
+            .tts
+            .transmit(Request::new(TransmitRequest {
+                ssml: report.clone(),
+                plaintext: None,
+                frequency,
+                srs_client_name: Some(format!("ATIS {}", airbase.name)),
+                position: Some(airbase.position.clone()),
+                coalition,
+                r#async: false,
+                provider: None,
+            }))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn weather(&self, airbase: &Airbase) -> Result<Weather, Status> {
+        let mission = &self.0.mission;
+        let position = &airbase.position;
+
+        let wind: Vector = mission
+            .request(
+                "getWind",
+                Request::new(GetWindRequest {
+                    position: Some(InputPosition {
+                        alt: position.alt + WIND_MEASUREMENT_HEIGHT,
+                        ..position.clone()
+                    }),
+                }),
+            )
+            .await?;
+        let temperature = mission
+            .get_temperature_and_pressure(Request::new(GetTemperatureAndPressureRequest {
+                position: Some(position.clone()),
+            }))
+            .await?
+            .into_inner()
+            .temperature;
+        // the pressure at sea level is the QNH
+        let qnh = mission
+            .get_temperature_and_pressure(Request::new(GetTemperatureAndPressureRequest {
+                position: Some(InputPosition {
+                    alt: 0.0,
+                    ..position.clone()
+                }),
+            }))
+            .await?
+            .into_inner()
+            .pressure;
+        // `True North` + `declination` = `Magnetic North`
+        let declination = mission
+            .get_magnetic_declination(Request::new(GetMagneticDeclinationRequest {
+                lat: position.lat,
+                lon: position.lon,
+                alt: position.alt,
+            }))
+            .await?
+            .into_inner()
+            .declination;
+
+        let (wind_heading, wind_strength) = wind_from(&wind);
+        let wind_speed = (wind_strength * 3600.0 / METERS_PER_NAUTICAL_MILE).round();
+        let wind_speed = wind_speed as u32;
+        let wind_direction = if wind_speed < CALM_WIND {
+            None
+        } else {
+            // rounded to ten degrees, with north being 360
+            match ((wind_heading + declination).rem_euclid(360.0) / 10.0).round() as u32 % 36 {
+                0 => Some(360),
+                tens => Some(tens * 10),
+            }
+        };
+
+        Ok(Weather {
+            wind_direction,
+            wind_speed,
+            temperature: (f64::from(temperature) - 273.15).round() as i32,
+            qnh: (f64::from(qnh) / 100.0).round() as u32,
+            altimeter: (f64::from(qnh) / PASCALS_PER_INCH_OF_MERCURY * 100.0).round() as u32,
+            active_runway: active_runway(&airbase.runways, wind_heading, declination),
+        })
+    }
+}
+
+/// The true direction (in degrees) the wind is coming from and its horizontal speed (in m/s),
+/// given its velocity in DCS' map coordinates (x north, z east).
+fn wind_from(velocity: &Vector) -> (f64, f64) {
+    let heading = velocity.z.atan2(velocity.x).to_degrees();
+    (
+        (heading + 180.0).rem_euclid(360.0),
+        velocity.x.hypot(velocity.z),
+    )
+}
+
+/// The designator of the runway end most aligned with the wind (landing and taking off into the
+/// wind), e.g. `22L`.
+fn active_runway(runways: &[Runway], wind_heading: f64, declination: f64) -> Option<String> {
+    runways
+        .iter()
+        .flat_map(|runway| {
+            let (forward, reverse) = designators(runway, declination);
+            [
+                (runway.heading, forward),
+                ((runway.heading + 180.0) % 360.0, reverse),
+            ]
+        })
+        .map(|(heading, designator)| {
+            let diff = (heading - wind_heading).rem_euclid(360.0);
+            (diff.min(360.0 - diff), designator)
+        })
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, designator)| designator)
+}
+
+/// The designators of both ends of a runway. Uses the runway's name (e.g. `04-22` or `04L`)
+/// where possible, and falls back to its magnetic heading otherwise.
+fn designators(runway: &Runway, declination: f64) -> (String, String) {
+    let from_heading = |heading: f64| {
+        let number = ((heading + declination).rem_euclid(360.0) / 10.0).round() as u32 % 36;
+        format!("{:02}", if number == 0 { 36 } else { number })
+    };
+
+    let mut parts = runway.name.split('-').map(str::trim);
+    let forward = parts
+        .next()
+        .filter(|part| part.starts_with(|c: char| c.is_ascii_digit()))
+        .map(str::to_string);
+    let reverse = parts
+        .next()
+        .filter(|part| part.starts_with(|c: char| c.is_ascii_digit()))
+        .map(str::to_string)
+        .or_else(|| forward.as_deref().and_then(reciprocal));
+
+    (
+        forward.unwrap_or_else(|| from_heading(runway.heading)),
+        reverse.unwrap_or_else(|| from_heading(runway.heading + 180.0)),
+    )
+}
+
+/// The designator of the opposite end of the runway, e.g. `22R` for `04L`.
+fn reciprocal(designator: &str) -> Option<String> {
+    let digits = designator
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>();
+    let number = digits.parse::<u32>().ok()?;
+    let suffix = match &designator[digits.len()..] {
+        "L" => "R",
+        "R" => "L",
+        suffix => suffix,
+    };
+    Some(format!("{:02}{}", (number + 17) % 36 + 1, suffix))
+}
+
+fn format_report(airbase: &str, information: &str, weather: &Weather) -> String {
+    let wind = match weather.wind_direction {
+        Some(direction) => format!("Wind {direction:03} at {} knots.", weather.wind_speed),
+        None => String::from("Wind calm."),
+    };
+    let runway = match &weather.active_runway {
+        Some(runway) => format!(" Active runway {runway}."),
+        None => String::new(),
+    };
+    format!(
+        "{airbase} information {information}. {wind} Temperature {}. QNH {}, altimeter {} {:02}.\
+         {runway} Advise on initial contact you have information {information}.",
+        weather.temperature,
+        weather.qnh,
+        weather.altimeter / 100,
+        weather.altimeter % 100,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runway(name: &str, heading: f64) -> Runway {
+        Runway {
+            name: name.to_string(),
+            heading,
+            ..Default::default()
+        }
+    }
+
+    fn weather(wind_direction: Option<u32>, active_runway: Option<&str>) -> Weather {
+        Weather {
+            wind_direction,
+            wind_speed: 12,
+            temperature: -2,
+            qnh: 1013,
+            altimeter: 2992,
+            active_runway: active_runway.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_wind_from() {
+        // blowing towards the south, i.e. a northerly wind
+        let (heading, strength) = wind_from(&Vector {
+            x: -5.0,
+            y: 0.0,
+            z: 0.0,
+        });
+        assert_eq!(heading, 0.0);
+        assert_eq!(strength, 5.0);
+
+        // blowing towards the east, i.e. a westerly wind
+        let (heading, _) = wind_from(&Vector {
+            x: 0.0,
+            y: 1.0,
+            z: 5.0,
+        });
+        assert_eq!(heading, 270.0);
+
+        // blowing towards the north-west
+        let (heading, strength) = wind_from(&Vector {
+            x: 3.0,
+            y: 0.0,
+            z: -3.0,
+        });
+        assert!((heading - 135.0).abs() < 1e-9);
+        assert!((strength - 18f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_active_runway() {
+        let runways = [runway("04-22", 40.0)];
+        assert_eq!(active_runway(&runways, 220.0, 6.0).as_deref(), Some("22"));
+        assert_eq!(active_runway(&runways, 50.0, 6.0).as_deref(), Some("04"));
+        // the closest end wins, even if the wind is almost perpendicular
+        assert_eq!(active_runway(&runways, 125.0, 6.0).as_deref(), Some("04"));
+        assert_eq!(active_runway(&runways, 135.0, 6.0).as_deref(), Some("22"));
+
+        let runways = [runway("13-31", 130.0), runway("04-22", 40.0)];
+        assert_eq!(active_runway(&runways, 300.0, 0.0).as_deref(), Some("31"));
+        assert_eq!(active_runway(&[], 300.0, 0.0), None);
+    }
+
+    #[test]
+    fn test_designators() {
+        assert_eq!(
+            designators(&runway("04-22", 40.0), 6.0),
+            ("04".to_string(), "22".to_string())
+        );
+        assert_eq!(
+            designators(&runway("04L", 40.0), 6.0),
+            ("04L".to_string(), "22R".to_string())
+        );
+        // falls back to the magnetic heading
+        assert_eq!(
+            designators(&runway("", 90.0), -5.0),
+            ("09".to_string(), "27".to_string())
+        );
+        assert_eq!(
+            designators(&runway("Runway 1", 2.0), 0.0),
+            ("36".to_string(), "18".to_string())
+        );
+    }
+
+    #[test]
+    fn test_reciprocal() {
+        assert_eq!(reciprocal("04L").as_deref(), Some("22R"));
+        assert_eq!(reciprocal("22R").as_deref(), Some("04L"));
+        assert_eq!(reciprocal("09C").as_deref(), Some("27C"));
+        assert_eq!(reciprocal("18").as_deref(), Some("36"));
+        assert_eq!(reciprocal("36").as_deref(), Some("18"));
+        assert_eq!(reciprocal("L"), None);
+    }
+
+    #[test]
+    fn test_format_report() {
+        assert_eq!(
+            format_report("Batumi", "Alpha", &weather(Some(240), Some("31"))),
+            "Batumi information Alpha. Wind 240 at 12 knots. Temperature -2. QNH 1013, \
+             altimeter 29 92. Active runway 31. Advise on initial contact you have information \
+             Alpha."
+        );
+        assert_eq!(
+            format_report("Kobuleti", "Bravo", &weather(None, None)),
+            "Kobuleti information Bravo. Wind calm. Temperature -2. QNH 1013, altimeter 29 92. \
+             Advise on initial contact you have information Bravo."
+        );
+        assert_eq!(
+            format_report("Senaki", "Charlie", &weather(Some(40), None)),
+            "Senaki information Charlie. Wind 040 at 12 knots. Temperature -2. QNH 1013, \
+             altimeter 29 92. Advise on initial contact you have information Charlie."
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Formatting of bearings, ranges, altitudes and tracks as used in brevity calls.
+
+pub const METERS_PER_NAUTICAL_MILE: f64 = 1852.0;
+pub const METERS_PER_FOOT: f64 = 0.3048;
+
+/// Format a bearing with three digits, e.g. `090` (with north being `360`).
+pub fn format_bearing(bearing: f64) -> String {
+    match bearing.round() as u32 % 360 {
+        0 => "360".to_string(),
+        bearing => format!("{bearing:03}"),
+    }
+}
+
+/// Format a range (in meters) in nautical miles.
+pub fn format_range(range: f64) -> String {
+    format!("{}", (range / METERS_PER_NAUTICAL_MILE).round() as u32)
+}
+
+/// Format an altitude (in meters) in thousands (or, if lower, hundreds) of feet.
+pub fn format_altitude(alt: f64) -> String {
+    let feet = (alt / METERS_PER_FOOT).max(0.0);
+    let thousands = (feet / 1000.0).round() as u32;
+    if thousands > 0 {
+        format!("{thousands} thousand")
+    } else {
+        format!("{} hundred", (feet / 100.0).round() as u32)
+    }
+}
+
+/// Format a heading (in degrees) as one of the eight cardinal directions, e.g. `northeast`.
+pub fn format_track(heading: f64) -> &'static str {
+    const DIRECTIONS: [&str; 8] = [
+        "north",
+        "northeast",
+        "east",
+        "southeast",
+        "south",
+        "southwest",
+        "west",
+        "northwest",
+    ];
+    DIRECTIONS[((heading.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bearing() {
+        assert_eq!(format_bearing(0.0), "360");
+        assert_eq!(format_bearing(359.6), "360");
+        assert_eq!(format_bearing(5.2), "005");
+        assert_eq!(format_bearing(45.0), "045");
+        assert_eq!(format_bearing(270.4), "270");
+    }
+
+    #[test]
+    fn test_format_range() {
+        assert_eq!(format_range(0.0), "0");
+        assert_eq!(format_range(METERS_PER_NAUTICAL_MILE * 12.4), "12");
+        assert_eq!(format_range(METERS_PER_NAUTICAL_MILE * 12.6), "13");
+    }
+
+    #[test]
+    fn test_format_altitude() {
+        assert_eq!(format_altitude(-10.0), "0 hundred");
+        assert_eq!(format_altitude(100.0 * METERS_PER_FOOT), "1 hundred");
+        assert_eq!(format_altitude(400.0 * METERS_PER_FOOT), "4 hundred");
+        assert_eq!(format_altitude(600.0 * METERS_PER_FOOT), "1 thousand");
+        assert_eq!(format_altitude(24_600.0 * METERS_PER_FOOT), "25 thousand");
+    }
+
+    #[test]
+    fn test_format_track() {
+        assert_eq!(format_track(0.0), "north");
+        assert_eq!(format_track(350.0), "north");
+        assert_eq!(format_track(-10.0), "north");
+        assert_eq!(format_track(30.0), "northeast");
+        assert_eq!(format_track(180.0), "south");
+        assert_eq!(format_track(250.0), "west");
+        assert_eq!(format_track(300.0), "northwest");
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use stubs::chat::v0::{Argument, ArgumentType, Command};
+use stubs::mission::v0::stream_events_response::chat_command_event::{argument, Argument as Value};
+use stubs::mission::v0::stream_events_response::ChatCommandEvent;
+
+/// The chat commands registered by clients, and the permission levels of players.
+#[derive(Clone, Default)]
+pub struct ChatCommands(Arc<RwLock<Registry>>);
+
+#[derive(Default)]
+struct Registry {
+    /// The registered commands by their lowercase name.
+    commands: HashMap<String, Command>,
+    /// The permission level of players by their UCID.
+    permission_levels: HashMap<String, u32>,
+}
+
+/// The result of handling a chat message that matched a registered command.
+pub struct Handled {
+    /// Whether to hide the chat message from all other players.
+    pub hide: bool,
+    /// Either the parsed command, or the reply to send to the player if the command could not
+    /// be parsed.
+    pub result: Result<ChatCommandEvent, String>,
+}
+
+impl ChatCommands {
+    // every change to the registry is a single insert or remove, so it is consistent even if
+    // another thread panicked while holding the lock
+    fn read(&self) -> RwLockReadGuard<'_, Registry> {
+        self.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Registry> {
+        self.0.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn register(&self, command: Command) -> Result<(), String> {
+        validate(&command)?;
+        let mut registry = self.write();
+        registry
+            .commands
+            .insert(command.name.to_lowercase(), command);
+        Ok(())
+    }
+
+    /// Unregister the command with the given name. Returns `false` if there was no such command.
+    pub fn unregister(&self, name: &str) -> bool {
+        let mut registry = self.write();
+        registry.commands.remove(&name.to_lowercase()).is_some()
+    }
+
+    pub fn list(&self) -> Vec<Command> {
+        let registry = self.read();
+        let mut commands = registry.commands.values().cloned().collect::<Vec<_>>();
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+        commands
+    }
+
+    pub fn set_permission_level(&self, ucid: String, level: u32) {
+        let mut registry = self.write();
+        if level == 0 {
+            registry.permission_levels.remove(&ucid);
+        } else {
+            registry.permission_levels.insert(ucid, level);
+        }
+    }
+
+    /// Parse the given chat message of a player. Returns `None` if the message doesn't match any
+    /// registered command.
+    pub fn handle(
+        &self,
+        player_id: u32,
+        ucid: &str,
+        message: &str,
+        coalition: i32,
+    ) -> Option<Handled> {
+        let registry = self.read();
+        let message = message.trim_start();
+        let (name, rest) = match message.split_once(char::is_whitespace) {
+            Some((name, rest)) => (name, rest),
+            None => (message, ""),
+        };
+        let command = registry.commands.get(&name.to_lowercase())?;
+
+        let permission_level = registry.permission_levels.get(ucid).copied().unwrap_or(0);
+        if permission_level < command.permission_level {
+            return Some(Handled {
+                hide: command.hide,
+                result: Err(format!("You are not allowed to use {}.", command.name)),
+            });
+        }
+
+        let result = parse_args(command, rest)
+            .map(|args| ChatCommandEvent {
+                player_id,
+                command: command.name.clone(),
+                args,
+                coalition,
+            })
+            .map_err(|err| format!("{err} {}", usage(command)));
+
+        Some(Handled {
+            hide: command.hide,
+            result,
+        })
+    }
+}
+
+fn validate(command: &Command) -> Result<(), String> {
+    if command.name.is_empty() || command.name.contains(char::is_whitespace) {
+        return Err("command name must be a single non-empty word".to_string());
+    }
+
+    let mut optional = false;
+    for (i, arg) in command.args.iter().enumerate() {
+        if arg.name.is_empty() {
+            return Err(format!("argument #{} is missing a name", i + 1));
+        }
+        if command.args[..i].iter().any(|other| other.name == arg.name) {
+            return Err(format!("argument `{}` is declared twice", arg.name));
+        }
+        match arg.r#type() {
+            ArgumentType::Unspecified => {
+                return Err(format!("argument `{}` is missing a type", arg.name))
+            }
+            ArgumentType::Text if i + 1 < command.args.len() => {
+                return Err(format!(
+                    "text argument `{}` must be the last argument",
+                    arg.name
+                ))
+            }
+            _ => {}
+        }
+        if optional && !arg.optional {
+            return Err(format!(
+                "required argument `{}` must not follow optional arguments",
+                arg.name
+            ));
+        }
+        optional = arg.optional;
+    }
+
+    Ok(())
+}
+
+fn parse_args(command: &Command, mut rest: &str) -> Result<HashMap<String, Value>, String> {
+    let mut args = HashMap::with_capacity(command.args.len());
+
+    for arg in &command.args {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            if arg.optional {
+                break;
+            }
+            return Err(format!("Missing argument <{}>.", arg.name));
+        }
+
+        let input = if arg.r#type() == ArgumentType::Text {
+            std::mem::take(&mut rest).trim_end()
+        } else {
+            let (input, remaining) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            rest = remaining;
+            input
+        };
+
+        args.insert(arg.name.clone(), parse_value(arg, input)?);
+    }
+
+    if !rest.trim().is_empty() {
+        return Err(format!("Unexpected argument `{}`.", rest.trim()));
+    }
+
+    Ok(args)
+}
+
+fn parse_value(arg: &Argument, input: &str) -> Result<Value, String> {
+    let value = match arg.r#type() {
+        ArgumentType::Unspecified | ArgumentType::String | ArgumentType::Text => {
+            argument::Value::StringValue(input.to_string())
+        }
+        ArgumentType::Integer => input
+            .parse()
+            .map(argument::Value::IntegerValue)
+            .map_err(|_| format!("<{}> must be a whole number.", arg.name))?,
+        ArgumentType::Number => input
+            .parse()
+            .ok()
+            .filter(|n: &f64| n.is_finite())
+            .map(argument::Value::NumberValue)
+            .ok_or_else(|| format!("<{}> must be a number.", arg.name))?,
+        ArgumentType::Bool => match input.to_lowercase().as_str() {
+            "true" | "on" | "yes" => argument::Value::BoolValue(true),
+            "false" | "off" | "no" => argument::Value::BoolValue(false),
+            _ => return Err(format!("<{}> must be either on or off.", arg.name)),
+        },
+    };
+
+    Ok(Value { value: Some(value) })
+}
+
+/// The usage of the command, e.g. `Usage: -lase <code> [target]`.
+fn usage(command: &Command) -> String {
+    let mut usage = format!("Usage: {}", command.name);
+    for arg in &command.args {
+        if arg.optional {
+            usage += &format!(" [{}]", arg.name);
+        } else {
+            usage += &format!(" <{}>", arg.name);
+        }
+    }
+    if let Some(description) = &command.description {
+        usage += &format!(" - {description}");
+    }
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arg(name: &str, r#type: ArgumentType, optional: bool) -> Argument {
+        Argument {
+            name: name.to_string(),
+            r#type: r#type.into(),
+            optional,
+        }
+    }
+
+    fn test_command(args: Vec<Argument>) -> Command {
+        Command {
+            name: "-test".to_string(),
+            args,
+            ..Default::default()
+        }
+    }
+
+    fn value(value: argument::Value) -> Value {
+        Value { value: Some(value) }
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let command = test_command(vec![
+            arg("code", ArgumentType::Integer, false),
+            arg("speed", ArgumentType::Number, false),
+            arg("on", ArgumentType::Bool, false),
+            arg("target", ArgumentType::String, true),
+        ]);
+
+        let args = parse_args(&command, "  1688 4.5   YES  sam ").unwrap();
+        assert_eq!(args.len(), 4);
+        assert_eq!(args["code"], value(argument::Value::IntegerValue(1688)));
+        assert_eq!(args["speed"], value(argument::Value::NumberValue(4.5)));
+        assert_eq!(args["on"], value(argument::Value::BoolValue(true)));
+        assert_eq!(
+            args["target"],
+            value(argument::Value::StringValue("sam".to_string()))
+        );
+
+        // optional arguments can be omitted
+        let args = parse_args(&command, "1688 4.5 off").unwrap();
+        assert_eq!(args.len(), 3);
+        assert_eq!(args["on"], value(argument::Value::BoolValue(false)));
+    }
+
+    #[test]
+    fn test_parse_args_text() {
+        let command = test_command(vec![
+            arg("to", ArgumentType::String, false),
+            arg("message", ArgumentType::Text, false),
+        ]);
+
+        let args = parse_args(&command, "all  hello   world  ").unwrap();
+        assert_eq!(
+            args["message"],
+            value(argument::Value::StringValue("hello   world".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        let command = test_command(vec![
+            arg("code", ArgumentType::Integer, false),
+            arg("speed", ArgumentType::Number, true),
+        ]);
+
+        assert_eq!(
+            parse_args(&command, "").unwrap_err(),
+            "Missing argument <code>."
+        );
+        assert_eq!(
+            parse_args(&command, "1.5").unwrap_err(),
+            "<code> must be a whole number."
+        );
+        assert_eq!(
+            parse_args(&command, "1 nan").unwrap_err(),
+            "<speed> must be a number."
+        );
+        assert_eq!(
+            parse_args(&command, "1 2 3").unwrap_err(),
+            "Unexpected argument `3`."
+        );
+        assert_eq!(
+            parse_args(
+                &test_command(vec![arg("on", ArgumentType::Bool, false)]),
+                "maybe"
+            )
+            .unwrap_err(),
+            "<on> must be either on or off."
+        );
+    }
+}
@@ -19,8 +19,22 @@ pub struct Config {
     pub eval_enabled: bool,
     #[serde(default)]
     pub integrity_check_disabled: bool,
+    #[serde(default = "default_decision_timeout")]
+    pub decision_timeout: u64,
+    #[serde(default)]
+    pub decision_default: DecisionDefault,
     pub tts: Option<TtsConfig>,
     pub srs: Option<SrsConfig>,
+    pub rotation: Option<RotationConfig>,
+    pub gci: Option<GciConfig>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DecisionDefault {
+    #[default]
+    Allow,
+    Deny,
 }
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
@@ -87,6 +101,53 @@ pub struct SrsConfig {
     pub addr: Option<SocketAddr>,
 }
 
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RotationConfig {
+    #[serde(default)]
+    pub missions: Vec<RotationMissionConfig>,
+    /// Wall-clock times (UTC) formatted as `HH:MM`.
+    #[serde(default)]
+    pub restart_times: Vec<String>,
+    /// Seconds before the rotation to warn players.
+    #[serde(default)]
+    pub warnings: Vec<u32>,
+    pub tts_frequency: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RotationMissionConfig {
+    pub path: String,
+    /// In seconds.
+    pub max_duration: Option<u32>,
+    pub min_players: Option<u32>,
+    pub max_players: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GciConfig {
+    pub blue: Option<GciCoalitionConfig>,
+    pub red: Option<GciCoalitionConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GciCoalitionConfig {
+    /// The names of the AWACS and EWR units whose detected targets make up the picture.
+    #[serde(default)]
+    pub sensors: Vec<String>,
+    /// The frequency (in Hz) to transmit the calls on. The coalition's GCI is not started
+    /// without it.
+    pub frequency: Option<u64>,
+    pub callsign: Option<String>,
+    /// Seconds between picture calls.
+    pub picture_interval: Option<u32>,
+    /// The range (in nautical miles) within which threat calls are made to players.
+    pub threat_range: Option<u32>,
+}
+
 fn default_host() -> String {
     String::from("127.0.0.1")
 }
@@ -95,6 +156,10 @@ fn default_port() -> u16 {
     50051
 }
 
+fn default_decision_timeout() -> u64 {
+    50
+}
+
 impl<'lua> mlua::FromLua<'lua> for Config {
     fn from_lua(lua_value: mlua::Value<'lua>, lua: &'lua mlua::Lua) -> mlua::Result<Self> {
         use mlua::LuaSerdeExt;
@@ -0,0 +1,491 @@
+//! Conversions between DCS' map coordinates, latitude/longitude and MGRS without a roundtrip to
+//! the mission.
+//!
+//! Each DCS theatre uses a transverse Mercator projection (on the WGS84 ellipsoid) with its own
+//! central meridian and false easting/northing. DCS' `x` axis points north and its `z` axis points
+//! east, so `x` is the projection's northing and `z` its easting.
+
+/// The semi-major axis of the WGS84 ellipsoid.
+const WGS84_A: f64 = 6_378_137.0;
+/// The flattening of the WGS84 ellipsoid.
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// A transverse Mercator projection on the WGS84 ellipsoid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransverseMercator {
+    /// The central meridian in degrees.
+    pub central_meridian: f64,
+    pub false_easting: f64,
+    pub false_northing: f64,
+    pub scale_factor: f64,
+}
+
+impl TransverseMercator {
+    /// The projection used by the DCS theatre of the given name (as returned by
+    /// `WorldService.GetTheatre`).
+    pub fn for_theatre(theatre: &str) -> Option<Self> {
+        let (central_meridian, false_easting, false_northing) = match theatre {
+            "Caucasus" => (33.0, -99_516.999_999_973_2, -4_998_114.999_999_984),
+            "Nevada" => (-117.0, -193_996.809_999_645_48, -4_410_028.063_999_966),
+            "Normandy" => (-3.0, -195_526.000_000_002_04, -5_484_812.999_999_951),
+            "PersianGulf" => (57.0, 75_755.999_999_996_45, -2_894_933.000_000_037_7),
+            "TheChannel" => (3.0, 99_376.000_000_002_88, -5_636_889.000_000_01),
+            "Syria" => (39.0, 282_801.000_000_039_93, -3_879_865.999_999_993_5),
+            "MarianaIslands" => (147.0, 238_417.999_999_899_68, -1_491_840.000_000_048),
+            "Falklands" => (-57.0, 147_639.999_999_975_93, 5_815_417.000_000_032),
+            "SinaiMap" => (33.0, 169_222.999_999_992_43, -3_325_312.999_999_969_3),
+            "Kola" => (21.0, -62_702.000_000_000_87, -7_543_624.999_999_979),
+            "Afghanistan" => (63.0, -300_149.999_999_986_4, -3_759_657.000_000_049),
+            _ => return None,
+        };
+        Some(Self {
+            central_meridian,
+            false_easting,
+            false_northing,
+            scale_factor: 0.9996,
+        })
+    }
+
+    /// The UTM projection of the given zone.
+    fn utm(zone: u8, north: bool) -> Self {
+        Self {
+            central_meridian: f64::from(zone) * 6.0 - 183.0,
+            false_easting: 500_000.0,
+            false_northing: if north { 0.0 } else { 10_000_000.0 },
+            scale_factor: 0.9996,
+        }
+    }
+
+    /// Project the given latitude and longitude (in degrees) to `(northing, easting)`.
+    pub fn forward(&self, lat: f64, lon: f64) -> (f64, f64) {
+        let Series { a, alpha, e, .. } = Series::wgs84();
+
+        let phi = lat.to_radians();
+        let lambda = normalize_lon(lon - self.central_meridian).to_radians();
+
+        // conformal latitude
+        let tau = phi.tan();
+        let sigma = (e * (e * tau / (1.0 + tau * tau).sqrt()).atanh()).sinh();
+        let tau_prime = tau * (1.0 + sigma * sigma).sqrt() - sigma * (1.0 + tau * tau).sqrt();
+
+        let xi_prime = tau_prime.atan2(lambda.cos());
+        let eta_prime =
+            (lambda.sin() / (tau_prime * tau_prime + lambda.cos().powi(2)).sqrt()).asinh();
+
+        let mut xi = xi_prime;
+        let mut eta = eta_prime;
+        for (j, alpha) in alpha.iter().enumerate() {
+            let j = 2.0 * (j + 1) as f64;
+            xi += alpha * (j * xi_prime).sin() * (j * eta_prime).cosh();
+            eta += alpha * (j * xi_prime).cos() * (j * eta_prime).sinh();
+        }
+
+        (
+            self.scale_factor * a * xi + self.false_northing,
+            self.scale_factor * a * eta + self.false_easting,
+        )
+    }
+
+    /// Convert the given `northing` and `easting` back to latitude and longitude (in degrees).
+    pub fn inverse(&self, northing: f64, easting: f64) -> (f64, f64) {
+        let Series { a, beta, e, .. } = Series::wgs84();
+
+        let xi = (northing - self.false_northing) / (self.scale_factor * a);
+        let eta = (easting - self.false_easting) / (self.scale_factor * a);
+
+        let mut xi_prime = xi;
+        let mut eta_prime = eta;
+        for (j, beta) in beta.iter().enumerate() {
+            let j = 2.0 * (j + 1) as f64;
+            xi_prime -= beta * (j * xi).sin() * (j * eta).cosh();
+            eta_prime -= beta * (j * xi).cos() * (j * eta).sinh();
+        }
+
+        let sinh_eta_prime = eta_prime.sinh();
+        let sin_xi_prime = xi_prime.sin();
+        let cos_xi_prime = xi_prime.cos();
+        let tau_prime = sin_xi_prime / (sinh_eta_prime.powi(2) + cos_xi_prime.powi(2)).sqrt();
+
+        // solve for the geodetic latitude using Newton-Raphson
+        let e2 = e * e;
+        let mut tau = tau_prime;
+        for _ in 0..10 {
+            let sigma = (e * (e * tau / (1.0 + tau * tau).sqrt()).atanh()).sinh();
+            let tau_i = tau * (1.0 + sigma * sigma).sqrt() - sigma * (1.0 + tau * tau).sqrt();
+            let delta = (tau_prime - tau_i) / (1.0 + tau_i * tau_i).sqrt()
+                * (1.0 + (1.0 - e2) * tau * tau)
+                / ((1.0 - e2) * (1.0 + tau * tau).sqrt());
+            tau += delta;
+            if delta.abs() < 1e-12 {
+                break;
+            }
+        }
+
+        let lat = tau.atan().to_degrees();
+        let lon = sinh_eta_prime.atan2(cos_xi_prime).to_degrees() + self.central_meridian;
+        (lat, normalize_lon(lon))
+    }
+}
+
+/// The coefficients of the Krüger series (to the sixth order of the third flattening), which are
+/// accurate to a few millimeters within several thousand kilometers of the central meridian.
+struct Series {
+    /// The radius of the rectifying sphere multiplied by 2π is the circumference of a meridian.
+    a: f64,
+    alpha: [f64; 6],
+    beta: [f64; 6],
+    /// The eccentricity.
+    e: f64,
+}
+
+impl Series {
+    fn wgs84() -> Self {
+        let n = WGS84_F / (2.0 - WGS84_F);
+        let n2 = n * n;
+        let n3 = n2 * n;
+        let n4 = n3 * n;
+        let n5 = n4 * n;
+        let n6 = n5 * n;
+
+        Self {
+            a: WGS84_A / (1.0 + n) * (1.0 + n2 / 4.0 + n4 / 64.0 + n6 / 256.0),
+            alpha: [
+                n / 2.0 - 2.0 / 3.0 * n2 + 5.0 / 16.0 * n3 + 41.0 / 180.0 * n4 - 127.0 / 288.0 * n5
+                    + 7891.0 / 37800.0 * n6,
+                13.0 / 48.0 * n2 - 3.0 / 5.0 * n3 + 557.0 / 1440.0 * n4 + 281.0 / 630.0 * n5
+                    - 1983433.0 / 1935360.0 * n6,
+                61.0 / 240.0 * n3 - 103.0 / 140.0 * n4
+                    + 15061.0 / 26880.0 * n5
+                    + 167603.0 / 181440.0 * n6,
+                49561.0 / 161280.0 * n4 - 179.0 / 168.0 * n5 + 6601661.0 / 7257600.0 * n6,
+                34729.0 / 80640.0 * n5 - 3418889.0 / 1995840.0 * n6,
+                212378941.0 / 319334400.0 * n6,
+            ],
+            beta: [
+                n / 2.0 - 2.0 / 3.0 * n2 + 37.0 / 96.0 * n3 - 1.0 / 360.0 * n4 - 81.0 / 512.0 * n5
+                    + 96199.0 / 604800.0 * n6,
+                1.0 / 48.0 * n2 + 1.0 / 15.0 * n3 - 437.0 / 1440.0 * n4 + 46.0 / 105.0 * n5
+                    - 1118711.0 / 3870720.0 * n6,
+                17.0 / 480.0 * n3 - 37.0 / 840.0 * n4 - 209.0 / 4480.0 * n5 + 5569.0 / 90720.0 * n6,
+                4397.0 / 161280.0 * n4 - 11.0 / 504.0 * n5 - 830251.0 / 7257600.0 * n6,
+                4583.0 / 161280.0 * n5 - 108847.0 / 3991680.0 * n6,
+                20648693.0 / 638668800.0 * n6,
+            ],
+            e: (WGS84_F * (2.0 - WGS84_F)).sqrt(),
+        }
+    }
+}
+
+/// Normalize a longitude to the range -180..180 degrees.
+fn normalize_lon(lon: f64) -> f64 {
+    (lon + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// The mean radius of the earth (as defined by the IUGG) used for great-circle calculations.
+const EARTH_MEAN_RADIUS: f64 = 6_371_008.8;
+
+/// The initial true bearing (in degrees, 0 to 360) and the distance (in meters) along the great
+/// circle from the first to the second latitude and longitude (in degrees).
+pub fn great_circle(from: (f64, f64), to: (f64, f64)) -> (f64, f64) {
+    let (lat1, lon1) = (from.0.to_radians(), from.1.to_radians());
+    let (lat2, lon2) = (to.0.to_radians(), to.1.to_radians());
+    let delta_lat = lat2 - lat1;
+    let delta_lon = lon2 - lon1;
+
+    let y = delta_lon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * delta_lon.cos();
+    let bearing = y.atan2(x).to_degrees().rem_euclid(360.0);
+
+    // haversine
+    let a =
+        (delta_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (delta_lon / 2.0).sin().powi(2);
+    let distance = 2.0 * EARTH_MEAN_RADIUS * a.sqrt().atan2((1.0 - a).sqrt());
+
+    (bearing, distance)
+}
+
+/// The MGRS latitude bands, each spanning 8° (except for `X`, which spans 12°) starting at 80°S.
+const LAT_BANDS: &[u8] = b"CDEFGHJKLMNPQRSTUVWX";
+/// The column letters of the 100km squares, which repeat every three zones.
+const COLUMN_LETTERS: [&[u8]; 3] = [b"ABCDEFGH", b"JKLMNPQR", b"STUVWXYZ"];
+/// The row letters of the 100km squares.
+const ROW_LETTERS: &[u8] = b"ABCDEFGHJKLMNPQRSTUV";
+/// The offset into the row letters at the equator, indexed by `zone % 2` (even zones start at
+/// `F`).
+const ROW_OFFSETS: [usize; 2] = [5, 0];
+
+/// Convert the given latitude and longitude (in degrees) to an MGRS grid reference with the given
+/// number of digits per easting and northing (1 to 5, i.e. 10km to 1m precision), e.g.
+/// `37T GG 12345 67890`.
+pub fn to_mgrs(lat: f64, lon: f64, precision: u8) -> Result<String, MgrsError> {
+    if !(-80.0..=84.0).contains(&lat) || !lat.is_finite() {
+        return Err(MgrsError::LatitudeOutOfRange(lat));
+    }
+    if !(1..=5).contains(&precision) {
+        return Err(MgrsError::InvalidPrecision(precision));
+    }
+    let lon = normalize_lon(lon);
+
+    let band_index = (((lat + 80.0) / 8.0).floor() as usize).min(LAT_BANDS.len() - 1);
+    let band = LAT_BANDS[band_index] as char;
+    let zone = utm_zone(lat, lon);
+
+    let (northing, easting) = TransverseMercator::utm(zone, lat >= 0.0).forward(lat, lon);
+
+    let column = (easting / 100_000.0).floor() as usize;
+    let column_letters = COLUMN_LETTERS[(zone as usize - 1) % 3];
+    // eastings within a zone are always within 100km and 900km
+    let column_letter = column_letters[column.clamp(1, 8) - 1] as char;
+    let row = ((northing / 100_000.0).floor() as usize + ROW_OFFSETS[usize::from(zone % 2)]) % 20;
+    let row_letter = ROW_LETTERS[row] as char;
+
+    // truncate (instead of round) to the requested precision, as is convention for grid references
+    let divisor = 10f64.powi(5 - i32::from(precision));
+    let easting = ((easting % 100_000.0) / divisor).floor() as u32;
+    let northing = ((northing % 100_000.0) / divisor).floor() as u32;
+    let width = usize::from(precision);
+
+    Ok(format!(
+        "{zone}{band} {column_letter}{row_letter} {easting:0width$} {northing:0width$}"
+    ))
+}
+
+/// Convert the given MGRS grid reference to latitude and longitude (in degrees). Spaces are
+/// optional. The result is the south-west corner of the referenced grid square.
+pub fn from_mgrs(mgrs: &str) -> Result<(f64, f64), MgrsError> {
+    let invalid = || MgrsError::Invalid(mgrs.to_string());
+    let reference = mgrs
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_uppercase();
+
+    let zone_len = reference.chars().take_while(|c| c.is_ascii_digit()).count();
+    if !(1..=2).contains(&zone_len) || !reference.is_ascii() {
+        return Err(invalid());
+    }
+    let zone: u8 = reference[..zone_len].parse().map_err(|_| invalid())?;
+    if !(1..=60).contains(&zone) {
+        return Err(invalid());
+    }
+
+    let letters = &reference.as_bytes()[zone_len..];
+    if letters.len() < 3 {
+        return Err(invalid());
+    }
+    let band_index = LAT_BANDS
+        .iter()
+        .position(|b| *b == letters[0])
+        .ok_or_else(invalid)?;
+    let column = COLUMN_LETTERS[(zone as usize - 1) % 3]
+        .iter()
+        .position(|c| *c == letters[1])
+        .ok_or_else(invalid)?;
+    let row = ROW_LETTERS
+        .iter()
+        .position(|r| *r == letters[2])
+        .ok_or_else(invalid)?;
+    let row = (row + 20 - ROW_OFFSETS[usize::from(zone % 2)]) % 20;
+
+    let digits = &reference[zone_len + 3..];
+    if digits.len() % 2 != 0 || digits.len() > 10 || !digits.bytes().all(|d| d.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let precision = digits.len() / 2;
+    let scale = 10f64.powi(5 - precision as i32);
+    let parse = |digits: &str| -> f64 {
+        if digits.is_empty() {
+            0.0
+        } else {
+            digits.parse::<u32>().map(f64::from).unwrap_or_default() * scale
+        }
+    };
+    let easting = (column + 1) as f64 * 100_000.0 + parse(&digits[..precision]);
+    let mut northing = row as f64 * 100_000.0 + parse(&digits[precision..]);
+
+    // The row letters repeat every 2000km, so use the latitude band to determine which cycle the
+    // grid reference is in.
+    let north = band_index >= 10;
+    let utm = TransverseMercator::utm(zone, north);
+    let band_lat = band_index as f64 * 8.0 - 80.0;
+    let (band_northing, _) = utm.forward(band_lat, utm.central_meridian);
+    let band_northing = (band_northing / 100_000.0).floor() * 100_000.0;
+    while northing < band_northing {
+        northing += 2_000_000.0;
+    }
+
+    Ok(utm.inverse(northing, easting))
+}
+
+/// The UTM zone of the given position, including the exceptions around Norway and Svalbard.
+fn utm_zone(lat: f64, lon: f64) -> u8 {
+    let mut zone = (((lon + 180.0) / 6.0).floor() as u8 % 60) + 1;
+    if (56.0..64.0).contains(&lat) && (3.0..12.0).contains(&lon) {
+        zone = 32;
+    }
+    if lat >= 72.0 && (0.0..42.0).contains(&lon) {
+        zone = match lon {
+            lon if lon < 9.0 => 31,
+            lon if lon < 21.0 => 33,
+            lon if lon < 33.0 => 35,
+            _ => 37,
+        };
+    }
+    zone
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MgrsError {
+    #[error("latitude {0} is outside of the MGRS range (80°S to 84°N)")]
+    LatitudeOutOfRange(f64),
+    #[error("invalid precision {0} (expected 1 to 5 digits)")]
+    InvalidPrecision(u8),
+    #[error("invalid MGRS grid reference `{0}`")]
+    Invalid(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THEATRES: [&str; 11] = [
+        "Caucasus",
+        "Nevada",
+        "Normandy",
+        "PersianGulf",
+        "TheChannel",
+        "Syria",
+        "MarianaIslands",
+        "Falklands",
+        "SinaiMap",
+        "Kola",
+        "Afghanistan",
+    ];
+
+    fn assert_close(actual: f64, expected: f64, epsilon: f64) {
+        assert!(
+            (actual - expected).abs() < epsilon,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_theatre_roundtrip() {
+        for theatre in THEATRES {
+            let projection = TransverseMercator::for_theatre(theatre).unwrap();
+            // the map origin is always somewhere within the theatre
+            let (lat, lon) = projection.inverse(0.0, 0.0);
+            for (x, z) in [(0.0, 0.0), (250_000.0, -150_000.0), (-300_000.0, 400_000.0)] {
+                let (lat, lon) = projection.inverse(x, z);
+                let (northing, easting) = projection.forward(lat, lon);
+                assert_close(northing, x, 1e-3);
+                assert_close(easting, z, 1e-3);
+            }
+            let (x, z) = projection.forward(lat, lon);
+            assert_close(x, 0.0, 1e-3);
+            assert_close(z, 0.0, 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_unknown_theatre() {
+        assert_eq!(TransverseMercator::for_theatre("Unknown"), None);
+    }
+
+    #[test]
+    fn test_caucasus_origin() {
+        let projection = TransverseMercator::for_theatre("Caucasus").unwrap();
+        let (lat, lon) = projection.inverse(0.0, 0.0);
+        assert_close(lat, 45.129497, 1e-6);
+        assert_close(lon, 34.265515, 1e-6);
+    }
+
+    #[test]
+    fn test_utm() {
+        let (northing, easting) = TransverseMercator::utm(31, true).forward(45.0, 6.0);
+        assert_close(northing, 4_987_329.50, 0.01);
+        assert_close(easting, 736_446.03, 0.01);
+
+        let (northing, easting) = TransverseMercator::utm(37, false).forward(-45.0, 39.0);
+        assert_close(northing, 10_000_000.0 - 4_982_950.40, 0.01);
+        assert_close(easting, 500_000.0, 0.01);
+    }
+
+    #[test]
+    fn test_to_mgrs() {
+        // roughly in the middle of the 1m square
+        assert_eq!(
+            to_mgrs(42.134369, 41.569338, 5).unwrap(),
+            "37T GG 12345 67890"
+        );
+        assert_eq!(to_mgrs(42.134369, 41.569338, 2).unwrap(), "37T GG 12 67");
+        assert!(matches!(
+            to_mgrs(85.0, 0.0, 5),
+            Err(MgrsError::LatitudeOutOfRange(_))
+        ));
+        assert!(matches!(
+            to_mgrs(42.0, 41.0, 6),
+            Err(MgrsError::InvalidPrecision(6))
+        ));
+    }
+
+    #[test]
+    fn test_from_mgrs() {
+        let (lat, lon) = from_mgrs("37T GG 12345 67890").unwrap();
+        assert_close(lat, 42.134365, 1e-6);
+        assert_close(lon, 41.569332, 1e-6);
+
+        // spaces are optional
+        let (lat, lon) = from_mgrs("37tgg1234567890").unwrap();
+        assert_close(lat, 42.134365, 1e-6);
+        assert_close(lon, 41.569332, 1e-6);
+
+        assert!(matches!(
+            from_mgrs("37T GG 123"),
+            Err(MgrsError::Invalid(_))
+        ));
+        assert!(matches!(
+            from_mgrs("61T GG 12 34"),
+            Err(MgrsError::Invalid(_))
+        ));
+        assert!(matches!(
+            from_mgrs("37T GI 12 34"),
+            Err(MgrsError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_mgrs_roundtrip() {
+        for (lat, lon) in [
+            (41.6103, 41.6),
+            (36.2, -115.03),
+            (-51.7, -57.85),
+            (69.1, 33.4),
+        ] {
+            let mgrs = to_mgrs(lat, lon, 5).unwrap();
+            let (lat2, lon2) = from_mgrs(&mgrs).unwrap();
+            // the result is the south-west corner of the 1m square
+            assert_close(lat2, lat, 1e-4);
+            assert_close(lon2, lon, 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_great_circle() {
+        let (bearing, distance) = great_circle((0.0, 0.0), (0.0, 90.0));
+        assert_close(bearing, 90.0, 1e-9);
+        assert_close(
+            distance,
+            EARTH_MEAN_RADIUS * std::f64::consts::FRAC_PI_2,
+            1e-3,
+        );
+
+        let (bearing, _) = great_circle((10.0, 10.0), (0.0, 10.0));
+        assert_close(bearing, 180.0, 1e-9);
+
+        // Land's End to John o' Groats
+        let (bearing, distance) = great_circle((50.0664, -5.7147), (58.6439, -3.0700));
+        assert_close(bearing, 9.1198, 1e-3);
+        assert_close(distance, 968_900.0, 100.0);
+    }
+}
@@ -0,0 +1,60 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use rusqlite::Connection;
+
+/// An SQLite database, shared by all clones. As SQLite blocks, all operations are run on threads
+/// where blocking is acceptable.
+#[derive(Clone)]
+pub struct Database(Arc<Mutex<Connection>>);
+
+impl Database {
+    /// Open (or create) the database at the given path and create the tables of the given schema
+    /// if they don't exist yet.
+    pub fn open(path: &Path, schema: &str) -> Result<Self, Error> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let db = Connection::open(path)?;
+        db.execute_batch(schema)?;
+
+        Ok(Self(Arc::new(Mutex::new(db))))
+    }
+
+    /// Run the given database operation on a thread where blocking is acceptable.
+    pub async fn run<T, E, F>(&self, f: F) -> Result<T, E>
+    where
+        T: Send + 'static,
+        E: From<Error> + Send + 'static,
+        F: FnOnce(&Connection) -> Result<T, E> + Send + 'static,
+    {
+        let db = self.clone();
+        tokio::task::spawn_blocking(move || db.run_blocking(f))
+            .await
+            .map_err(|err| E::from(Error::Join(err)))?
+    }
+
+    /// Run the given database operation on the current thread. Only use this where blocking is
+    /// acceptable (e.g. on startup).
+    pub fn run_blocking<T, E, F>(&self, f: F) -> Result<T, E>
+    where
+        E: From<Error>,
+        F: FnOnce(&Connection) -> Result<T, E>,
+    {
+        let db = self.0.lock().map_err(|_| Error::Poisoned)?;
+        f(&db)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+    #[error(transparent)]
+    Join(#[from] tokio::task::JoinError),
+    #[error("the database is unusable after a panic")]
+    Poisoned,
+}
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use stubs::hook::v0::register_decision_handler_response::Decision;
+use stubs::hook::v0::{RegisterDecisionHandlerRequest, RegisterDecisionHandlerResponse};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::config::DecisionDefault;
+
+/// Forwards decisions the hook environment has to make synchronously to the client registered
+/// as decision handler, and waits for its answer up to a configured deadline.
+#[derive(Clone)]
+pub struct Decisions(Arc<Inner>);
+
+struct Inner {
+    timeout: Duration,
+    default: DecisionDefault,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    handler: Option<Handler>,
+    next_handler_id: u64,
+    next_decision_id: u32,
+    /// The pending decisions waiting for an answer, by decision id.
+    pending: HashMap<u32, oneshot::Sender<RegisterDecisionHandlerRequest>>,
+}
+
+struct Handler {
+    id: u64,
+    tx: mpsc::UnboundedSender<RegisterDecisionHandlerResponse>,
+}
+
+/// A decision forwarded to the registered handler, waiting for its answer.
+pub struct Pending {
+    id: u32,
+    rx: oneshot::Receiver<RegisterDecisionHandlerRequest>,
+}
+
+/// The outcome of a decision.
+#[derive(Debug)]
+pub struct Verdict {
+    pub allow: bool,
+    /// The reason to show to the player if denied.
+    pub reason: Option<String>,
+}
+
+impl Decisions {
+    pub fn new(timeout: Duration, default: DecisionDefault) -> Self {
+        Self(Arc::new(Inner {
+            timeout,
+            default,
+            state: Default::default(),
+        }))
+    }
+
+    /// Register a new decision handler. Returns the id of the handler and the receiver for the
+    /// decisions to make, or `None` if there is already a handler registered.
+    pub fn register(
+        &self,
+    ) -> Option<(
+        u64,
+        mpsc::UnboundedReceiver<RegisterDecisionHandlerResponse>,
+    )> {
+        let mut state = self.0.state.lock().unwrap();
+        if matches!(&state.handler, Some(handler) if !handler.tx.is_closed()) {
+            return None;
+        }
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        state.next_handler_id += 1;
+        let id = state.next_handler_id;
+        state.handler = Some(Handler { id, tx });
+        Some((id, rx))
+    }
+
+    pub fn unregister(&self, handler_id: u64) {
+        let mut state = self.0.state.lock().unwrap();
+        if matches!(&state.handler, Some(handler) if handler.id == handler_id) {
+            state.handler = None;
+            // dropping the senders immediately falls back to the default for all pending
+            // decisions
+            state.pending.clear();
+        }
+    }
+
+    /// Resolve a pending decision with the answer of the handler.
+    pub fn answer(&self, answer: RegisterDecisionHandlerRequest) {
+        let tx = self.0.state.lock().unwrap().pending.remove(&answer.id);
+        match tx {
+            // the decision might already have timed out in the meantime
+            Some(tx) => {
+                let _ = tx.send(answer);
+            }
+            None => log::debug!("Received answer for unknown decision {}", answer.id),
+        }
+    }
+
+    /// Forward the decision to the registered handler. Returns the configured default right away
+    /// if there is no handler, so that callers don't have to wait for anything in that case.
+    pub fn submit(&self, decision: Decision) -> Result<Pending, Verdict> {
+        let mut state = self.0.state.lock().unwrap();
+        let id = state.next_decision_id;
+        let request = RegisterDecisionHandlerResponse {
+            id,
+            decision: Some(decision),
+        };
+        let sent = match &state.handler {
+            Some(handler) => handler.tx.send(request).is_ok(),
+            None => false,
+        };
+        if !sent {
+            // either there is no handler, or its stream is closed already
+            state.handler = None;
+            return Err(self.default_verdict());
+        }
+
+        let (tx, rx) = oneshot::channel();
+        state.next_decision_id = id.wrapping_add(1);
+        state.pending.insert(id, tx);
+        Ok(Pending { id, rx })
+    }
+
+    /// Wait for the answer of the handler to a submitted decision, falling back to the
+    /// configured default once the decision timed out.
+    pub async fn wait(&self, pending: Pending) -> Verdict {
+        let Pending { id, rx } = pending;
+        match tokio::time::timeout(self.0.timeout, rx).await {
+            Ok(Ok(answer)) => Verdict {
+                allow: answer.allow,
+                reason: answer.reason,
+            },
+            _ => {
+                self.0.state.lock().unwrap().pending.remove(&id);
+                log::warn!(
+                    "Decision handler did not answer decision {} in time, falling back to {:?}",
+                    id,
+                    self.0.default
+                );
+                self.default_verdict()
+            }
+        }
+    }
+
+    fn default_verdict(&self) -> Verdict {
+        Verdict {
+            allow: matches!(self.0.default, DecisionDefault::Allow),
+            reason: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stubs::hook::v0::register_decision_handler_response::TryConnect;
+
+    use super::*;
+
+    fn decision() -> Decision {
+        Decision::TryConnect(TryConnect {
+            name: String::from("player"),
+            ..Default::default()
+        })
+    }
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_no_handler() {
+        let decisions = Decisions::new(Duration::from_secs(60), DecisionDefault::Deny);
+        let verdict = decisions.submit(decision()).err().unwrap();
+        assert!(!verdict.allow);
+
+        let decisions = Decisions::new(Duration::from_secs(60), DecisionDefault::Allow);
+        let verdict = decisions.submit(decision()).err().unwrap();
+        assert!(verdict.allow);
+
+        // a handler whose stream got closed counts as no handler
+        let (_, rx) = decisions.register().unwrap();
+        drop(rx);
+        assert!(decisions.submit(decision()).is_err());
+    }
+
+    #[test]
+    fn test_answer() {
+        let decisions = Decisions::new(Duration::from_secs(60), DecisionDefault::Allow);
+        let (_, mut rx) = decisions.register().unwrap();
+        // only one handler at a time
+        assert!(decisions.register().is_none());
+
+        let pending = decisions.submit(decision()).ok().unwrap();
+        let request = rx.try_recv().unwrap();
+        assert_eq!(request.decision, Some(decision()));
+
+        decisions.answer(RegisterDecisionHandlerRequest {
+            id: request.id,
+            allow: false,
+            reason: Some(String::from("Server is full")),
+        });
+        let verdict = block_on(decisions.wait(pending));
+        assert!(!verdict.allow);
+        assert_eq!(verdict.reason.as_deref(), Some("Server is full"));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let decisions = Decisions::new(Duration::from_millis(10), DecisionDefault::Deny);
+        let (_, _rx) = decisions.register().unwrap();
+
+        let pending = decisions.submit(decision()).ok().unwrap();
+        let verdict = block_on(decisions.wait(pending));
+        assert!(!verdict.allow);
+        assert_eq!(verdict.reason, None);
+        assert!(decisions.0.state.lock().unwrap().pending.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_answer() {
+        let decisions = Decisions::new(Duration::from_millis(10), DecisionDefault::Deny);
+        let (_, mut rx) = decisions.register().unwrap();
+
+        let pending = decisions.submit(decision()).ok().unwrap();
+        let request = rx.try_recv().unwrap();
+        decisions.answer(RegisterDecisionHandlerRequest {
+            id: request.id + 1,
+            allow: true,
+            reason: None,
+        });
+        let verdict = block_on(decisions.wait(pending));
+        assert!(!verdict.allow);
+    }
+
+    #[test]
+    fn test_unregister() {
+        let decisions = Decisions::new(Duration::from_secs(60), DecisionDefault::Deny);
+        let (handler_id, _rx) = decisions.register().unwrap();
+
+        // pending decisions fall back to the default right away
+        let pending = decisions.submit(decision()).ok().unwrap();
+        decisions.unregister(handler_id);
+        let verdict = block_on(decisions.wait(pending));
+        assert!(!verdict.allow);
+
+        assert!(decisions.register().is_some());
+    }
+}
@@ -0,0 +1,506 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use stubs::coalition::v0::coalition_service_server::CoalitionService;
+use stubs::coalition::v0::{GetBullseyeRequest, GetPlayerUnitsRequest};
+use stubs::common::v0::{contact, Coalition, InputPosition, Position, Unit};
+use stubs::controller::v0::controller_service_server::ControllerService;
+use stubs::controller::v0::GetDetectedTargetsRequest;
+use stubs::geometry::v0::geometry_service_server::GeometryService;
+use stubs::geometry::v0::{location, Aspect, GetBraaRequest, GetBraaResponse, Location};
+use stubs::tts::v0::tts_service_server::TtsService;
+use stubs::tts::v0::TransmitRequest;
+use tokio::time::sleep;
+use tonic::{Request, Status};
+
+use crate::brevity::{
+    format_altitude, format_bearing, format_range, format_track, METERS_PER_NAUTICAL_MILE,
+};
+use crate::config::{GciCoalitionConfig, GciConfig};
+use crate::coord;
+use crate::rpc::{MissionRpc, Tts};
+
+/// How often the detected targets are gathered.
+const UPDATE_INTERVAL: Duration = Duration::from_secs(10);
+const DEFAULT_CALLSIGN: &str = "Overlord";
+const DEFAULT_PICTURE_INTERVAL: u32 = 300;
+const DEFAULT_THREAT_RANGE: u32 = 30;
+/// Contacts within this distance (in meters) of each other are considered to be one formation.
+const FORMATION_RADIUS: f64 = 3.0 * METERS_PER_NAUTICAL_MILE;
+/// Formations of at least this many contacts are called out as heavy.
+const HEAVY_CONTACTS: usize = 3;
+/// The maximum number of groups described in a picture call (the closest to the bullseye).
+const MAX_PICTURE_GROUPS: usize = 5;
+/// The maximum number of calls transmitted per update, to keep the frequency usable.
+const MAX_CALLS_PER_UPDATE: usize = 3;
+/// The pause between two consecutive calls.
+const CALL_GAP: Duration = Duration::from_secs(2);
+/// How long to wait before repeating a threat call for the same player and formation.
+const THREAT_REPEAT: Duration = Duration::from_secs(60);
+
+/// Broadcasts bullseye-referenced picture and threat calls via TTS, based on the targets detected
+/// by each coalition's configured AWACS and EWR units.
+#[derive(Clone)]
+pub struct Gci(Arc<Inner>);
+
+struct Inner {
+    mission: MissionRpc,
+    tts: Arc<Tts>,
+    /// The coalitions with a GCI, together with their frequency (in Hz) and config.
+    coalitions: Vec<(Coalition, u64, GciCoalitionConfig)>,
+}
+
+/// The state of a coalition's GCI.
+#[derive(Default)]
+struct State {
+    next_picture_at: Option<Instant>,
+    last_picture: Option<String>,
+    /// When a threat call was last made, keyed by the player's unit id and the id of the
+    /// formation's lead.
+    threat_calls: HashMap<(u32, u32), Instant>,
+}
+
+/// Hostile contacts flying together.
+struct Formation {
+    /// The contacts ordered by their id. The first one is used as the formation's lead.
+    contacts: Vec<Unit>,
+    /// The average position of all contacts, but with the highest altitude.
+    position: Position,
+}
+
+impl Gci {
+    /// Returns `None` if no coalition is configured.
+    pub fn new(config: Option<GciConfig>, mission: MissionRpc, tts: Arc<Tts>) -> Option<Self> {
+        let config = config?;
+        let coalitions = [(Coalition::Blue, config.blue), (Coalition::Red, config.red)]
+            .into_iter()
+            .filter_map(|(coalition, config)| Some((coalition, config?)))
+            .filter(|(_, config)| !config.sensors.is_empty())
+            .filter_map(|(coalition, config)| match config.frequency {
+                Some(frequency) => Some((coalition, frequency, config)),
+                None => {
+                    log::error!(
+                        "Not starting GCI of {:?}: no frequency configured",
+                        coalition
+                    );
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        if coalitions.is_empty() {
+            return None;
+        }
+
+        Some(Self(Arc::new(Inner {
+            mission,
+            tts,
+            coalitions,
+        })))
+    }
+
+    pub async fn run_in_background(self, mut shutdown_signal: impl Future<Output = ()> + Unpin) {
+        let mut states = self
+            .0
+            .coalitions
+            .iter()
+            .map(|_| State::default())
+            .collect::<Vec<_>>();
+
+        loop {
+            for ((coalition, frequency, config), state) in self.0.coalitions.iter().zip(&mut states)
+            {
+                if let Err(err) = self.update(*coalition, *frequency, config, state).await {
+                    log::error!("Failed to update GCI of {:?}: {}", coalition, err);
+                }
+            }
+
+            tokio::select! {
+                _ = &mut shutdown_signal => break,
+                _ = sleep(UPDATE_INTERVAL) => {}
+            }
+        }
+    }
+
+    async fn update(
+        &self,
+        coalition: Coalition,
+        frequency: u64,
+        config: &GciCoalitionConfig,
+        state: &mut State,
+    ) -> Result<(), Status> {
+        let callsign = config.callsign.as_deref().unwrap_or(DEFAULT_CALLSIGN);
+        let contacts = match self.detected_contacts(coalition, &config.sensors).await? {
+            Some(contacts) => contacts,
+            // all sensors are dead or not spawned (yet)
+            None => return Ok(()),
+        };
+        let formations = formations(contacts);
+
+        let mut calls = Vec::new();
+
+        // threat calls take precedence over the picture
+        let threat_range = f64::from(config.threat_range.unwrap_or(DEFAULT_THREAT_RANGE))
+            * METERS_PER_NAUTICAL_MILE;
+        let now = Instant::now();
+        state
+            .threat_calls
+            .retain(|_, called_at| now.duration_since(*called_at) < THREAT_REPEAT);
+        let players = self
+            .0
+            .mission
+            .get_player_units(Request::new(GetPlayerUnitsRequest {
+                coalition: coalition.into(),
+            }))
+            .await?
+            .into_inner()
+            .units;
+        for player in players.iter().filter(|unit| unit.in_air) {
+            if calls.len() >= MAX_CALLS_PER_UPDATE {
+                break;
+            }
+            let position = player.position.clone().unwrap_or_default();
+            let threat = formations
+                .iter()
+                .filter(|formation| {
+                    !state
+                        .threat_calls
+                        .contains_key(&(player.id, formation.lead().id))
+                })
+                .map(|formation| (formation, distance(&position, &formation.position)))
+                .filter(|(_, distance)| *distance <= threat_range)
+                .min_by(|(_, a), (_, b)| a.total_cmp(b));
+            let (formation, _) = match threat {
+                Some(threat) => threat,
+                None => continue,
+            };
+
+            let braa = self
+                .braa(
+                    location::Location::UnitName(player.name.clone()),
+                    location::Location::UnitName(formation.lead().name.clone()),
+                )
+                .await?;
+            // formations flying away from the player are no threat
+            if braa.aspect == Some(Aspect::Drag.into()) {
+                continue;
+            }
+
+            state
+                .threat_calls
+                .insert((player.id, formation.lead().id), now);
+            calls.push(format!(
+                "{}, {}, threat, {}, hostile.",
+                player.callsign, callsign, braa.brevity
+            ));
+        }
+
+        let picture_interval = Duration::from_secs(u64::from(
+            config.picture_interval.unwrap_or(DEFAULT_PICTURE_INTERVAL),
+        ));
+        let picture_due = !matches!(state.next_picture_at, Some(at) if at > now);
+        if picture_due && calls.len() < MAX_CALLS_PER_UPDATE {
+            let picture = self.picture(coalition, callsign, &formations).await?;
+            state.next_picture_at = Some(now + picture_interval);
+            // don't repeat an unchanged picture
+            if state.last_picture.as_ref() != Some(&picture) {
+                state.last_picture = Some(picture.clone());
+                calls.push(picture);
+            }
+        }
+
+        for (i, call) in calls.into_iter().enumerate() {
+            if i > 0 {
+                sleep(CALL_GAP).await;
+            }
+            self.transmit(coalition, frequency, callsign, call).await;
+        }
+
+        Ok(())
+    }
+
+    /// The hostile airborne contacts detected by any of the given sensors. Returns `None` if none
+    /// of the sensors exist.
+    async fn detected_contacts(
+        &self,
+        coalition: Coalition,
+        sensors: &[String],
+    ) -> Result<Option<Vec<Unit>>, Status> {
+        // only the opposing coalition is hostile, neutral contacts are not called out
+        let hostile = match coalition {
+            Coalition::Red => Coalition::Blue,
+            _ => Coalition::Red,
+        };
+        let mut contacts = HashMap::new();
+        let mut any_sensor = false;
+        for sensor in sensors {
+            let res = self
+                .0
+                .mission
+                .get_detected_targets(Request::new(GetDetectedTargetsRequest {
+                    unit_name: sensor.clone(),
+                    include_object: Some(true),
+                    detection_type: None,
+                }))
+                .await;
+            let detected = match res {
+                Ok(res) => res.into_inner().contacts,
+                Err(status) if status.code() == tonic::Code::NotFound => continue,
+                Err(status) => return Err(status),
+            };
+            any_sensor = true;
+
+            for contact in detected {
+                if let Some(contact::Target::Unit(unit)) = contact.target {
+                    if unit.in_air && unit.coalition == i32::from(hostile) {
+                        contacts.insert(unit.id, unit);
+                    }
+                }
+            }
+        }
+
+        Ok(any_sensor.then(|| contacts.into_values().collect()))
+    }
+
+    async fn picture(
+        &self,
+        coalition: Coalition,
+        callsign: &str,
+        formations: &[Formation],
+    ) -> Result<String, Status> {
+        if formations.is_empty() {
+            return Ok(format!("{callsign}, picture clean."));
+        }
+
+        let bullseye = self
+            .0
+            .mission
+            .get_bullseye(Request::new(GetBullseyeRequest {
+                coalition: coalition.into(),
+            }))
+            .await?
+            .into_inner()
+            .position
+            .unwrap_or_default();
+
+        let mut groups = Vec::with_capacity(formations.len());
+        for formation in formations {
+            let braa = self
+                .braa(
+                    location::Location::Position(input_position(&bullseye)),
+                    location::Location::Position(input_position(&formation.position)),
+                )
+                .await?;
+            groups.push((braa, formation));
+        }
+        groups.sort_by(|(a, _), (b, _)| a.range.total_cmp(&b.range));
+
+        let describe = |(braa, formation): &(GetBraaResponse, &Formation)| {
+            let mut group = format!(
+                "bullseye {}/{}, {}",
+                format_bearing(braa.bearing_magnetic),
+                format_range(braa.range),
+                format_altitude(formation.position.alt),
+            );
+            if let Some(velocity) = &formation.lead().velocity {
+                group.push_str(", track ");
+                group.push_str(format_track(velocity.heading));
+            }
+            group.push_str(", hostile");
+            if formation.contacts.len() >= HEAVY_CONTACTS {
+                group.push_str(", heavy");
+            }
+            group
+        };
+
+        if let [group] = groups.as_slice() {
+            return Ok(format!("{callsign}, single group {}.", describe(group)));
+        }
+
+        let mut picture = format!("{callsign}, {} groups.", groups.len());
+        for group in groups.iter().take(MAX_PICTURE_GROUPS) {
+            picture.push_str(" Group ");
+            picture.push_str(&describe(group));
+            picture.push('.');
+        }
+        Ok(picture)
+    }
+
+    async fn braa(
+        &self,
+        origin: location::Location,
+        target: location::Location,
+    ) -> Result<GetBraaResponse, Status> {
+        Ok(self
+            .0
+            .mission
+            .get_braa(Request::new(GetBraaRequest {
+                origin: Some(Location {
+                    location: Some(origin),
+                }),
+                target: Some(Location {
+                    location: Some(target),
+                }),
+            }))
+            .await?
+            .into_inner())
+    }
+
+    async fn transmit(&self, coalition: Coalition, frequency: u64, callsign: &str, text: String) {
+        log::debug!("GCI call of {:?}: {}", coalition, text);
+        let result = self
+            .0
+            .tts
+            .transmit(Request::new(TransmitRequest {
+                ssml: text.clone(),
+                plaintext: Some(text),
+                frequency,
+                srs_client_name: Some(callsign.to_string()),
+                position: None,
+                coalition: coalition.into(),
+                r#async: false,
+                provider: None,
+            }))
+            .await;
+        if let Err(err) = result {
+            log::error!("Failed to transmit GCI call via TTS: {}", err);
+        }
+    }
+}
+
+impl Formation {
+    fn lead(&self) -> &Unit {
+        &self.contacts[0]
+    }
+}
+
+/// Group the given contacts into formations of contacts within [FORMATION_RADIUS] of another
+/// contact of the formation.
+fn formations(mut contacts: Vec<Unit>) -> Vec<Formation> {
+    contacts.sort_by_key(|unit| unit.id);
+    let positions = contacts
+        .iter()
+        .map(|unit| unit.position.clone().unwrap_or_default())
+        .collect::<Vec<_>>();
+
+    // the index of the formation of each contact
+    let mut assigned: Vec<Option<usize>> = vec![None; contacts.len()];
+    let mut count = 0;
+    for start in 0..contacts.len() {
+        if assigned[start].is_some() {
+            continue;
+        }
+        assigned[start] = Some(count);
+        let mut queue = vec![start];
+        while let Some(i) = queue.pop() {
+            for j in 0..contacts.len() {
+                if assigned[j].is_none()
+                    && distance(&positions[i], &positions[j]) <= FORMATION_RADIUS
+                {
+                    assigned[j] = Some(count);
+                    queue.push(j);
+                }
+            }
+        }
+        count += 1;
+    }
+
+    let mut formations = (0..count).map(|_| Vec::new()).collect::<Vec<Vec<Unit>>>();
+    for (unit, formation) in contacts.into_iter().zip(assigned) {
+        if let Some(formation) = formation {
+            formations[formation].push(unit);
+        }
+    }
+
+    formations
+        .into_iter()
+        .map(|contacts| {
+            let n = contacts.len() as f64;
+            let positions = contacts.iter().filter_map(|unit| unit.position.as_ref());
+            let position = Position {
+                lat: positions.clone().map(|p| p.lat).sum::<f64>() / n,
+                lon: positions.clone().map(|p| p.lon).sum::<f64>() / n,
+                alt: positions.clone().map(|p| p.alt).fold(f64::MIN, f64::max),
+                u: positions.clone().map(|p| p.u).sum::<f64>() / n,
+                v: positions.map(|p| p.v).sum::<f64>() / n,
+            };
+            Formation { contacts, position }
+        })
+        .collect()
+}
+
+/// The great-circle distance in meters.
+fn distance(a: &Position, b: &Position) -> f64 {
+    coord::great_circle((a.lat, a.lon), (b.lat, b.lon)).1
+}
+
+fn input_position(position: &Position) -> InputPosition {
+    InputPosition {
+        lat: position.lat,
+        lon: position.lon,
+        alt: position.alt,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A degree of latitude is 60 nautical miles.
+    const NM: f64 = 1.0 / 60.0;
+
+    fn contact(id: u32, lat: f64, alt: f64) -> Unit {
+        Unit {
+            id,
+            position: Some(Position {
+                lat,
+                lon: 41.0,
+                alt,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn ids(formation: &Formation) -> Vec<u32> {
+        formation.contacts.iter().map(|unit| unit.id).collect()
+    }
+
+    #[test]
+    fn test_formations() {
+        let formations = formations(vec![
+            contact(4, 43.0, 3000.0),
+            contact(3, 42.0 + 4.0 * NM, 5000.0),
+            contact(1, 42.0, 5000.0),
+            contact(2, 42.0 + 2.0 * NM, 6000.0),
+        ]);
+        assert_eq!(formations.len(), 2);
+
+        // contacts within 3nm of any other contact of the formation belong to it
+        assert_eq!(ids(&formations[0]), vec![1, 2, 3]);
+        assert_eq!(formations[0].lead().id, 1);
+        assert!((formations[0].position.lat - (42.0 + 2.0 * NM)).abs() < 1e-9);
+        assert_eq!(formations[0].position.alt, 6000.0);
+
+        assert_eq!(ids(&formations[1]), vec![4]);
+        assert_eq!(formations[1].position.lat, 43.0);
+        assert_eq!(formations[1].position.alt, 3000.0);
+    }
+
+    #[test]
+    fn test_formations_split() {
+        let formations = formations(vec![
+            contact(1, 42.0, 5000.0),
+            contact(2, 42.0 + 3.5 * NM, 5000.0),
+        ]);
+        assert_eq!(formations.len(), 2);
+        assert_eq!(ids(&formations[0]), vec![1]);
+        assert_eq!(ids(&formations[1]), vec![2]);
+    }
+
+    #[test]
+    fn test_formations_empty() {
+        assert!(formations(Vec::new()).is_empty());
+    }
+}
@@ -85,6 +85,55 @@ pub fn event(lua: &Lua, event: Value) -> LuaResult<()> {
     }
 }
 
+pub fn chat_command(
+    lua: &Lua,
+    arg: (f64, u32, String, String, i32),
+) -> LuaResult<(bool, Option<String>)> {
+    if let Some(ref lib) = *LIBRARY.read().unwrap() {
+        let f: Symbol<
+            fn(
+                lua: &Lua,
+                arg: (f64, u32, String, String, i32),
+            ) -> LuaResult<(bool, Option<String>)>,
+        > = unsafe {
+            lib.get(b"chat_command")
+                .map_err(|err| mlua::Error::ExternalError(Arc::new(err)))?
+        };
+        f(lua, arg).map_err(take_error_ownership)
+    } else {
+        Ok((false, None))
+    }
+}
+
+pub fn check_access(
+    lua: &Lua,
+    arg: (f64, String, String, String),
+) -> LuaResult<(bool, Option<String>)> {
+    if let Some(ref lib) = *LIBRARY.read().unwrap() {
+        let f: Symbol<
+            fn(lua: &Lua, arg: (f64, String, String, String)) -> LuaResult<(bool, Option<String>)>,
+        > = unsafe {
+            lib.get(b"check_access")
+                .map_err(|err| mlua::Error::ExternalError(Arc::new(err)))?
+        };
+        f(lua, arg).map_err(take_error_ownership)
+    } else {
+        Ok((true, None))
+    }
+}
+
+pub fn decide(lua: &Lua, decision: Value) -> LuaResult<(bool, Option<String>)> {
+    if let Some(ref lib) = *LIBRARY.read().unwrap() {
+        let f: Symbol<fn(lua: &Lua, decision: Value) -> LuaResult<(bool, Option<String>)>> = unsafe {
+            lib.get(b"decide")
+                .map_err(|err| mlua::Error::ExternalError(Arc::new(err)))?
+        };
+        f(lua, decision).map_err(take_error_ownership)
+    } else {
+        Ok((true, None))
+    }
+}
+
 pub fn simulation_frame(lua: &Lua, time: f64) -> LuaResult<()> {
     if let Some(ref lib) = *LIBRARY.read().unwrap() {
         let f: Symbol<fn(lua: &Lua, time: f64) -> LuaResult<()>> = unsafe {
@@ -0,0 +1,416 @@
+use std::collections::HashMap;
+use std::future::Future;
+
+use dcs_module_ipc::IPC;
+use futures_util::StreamExt;
+use stubs::common::v0::{initiator, target, Initiator, Target, Unit, Weapon};
+use stubs::mission::v0::stream_events_response::{
+    AttributedKillEvent, CrashEvent, DeadEvent, EjectionEvent, Event, HitEvent, KillEvent,
+    PilotDeadEvent, ShotEvent,
+};
+use stubs::mission::v0::StreamEventsResponse;
+
+/// Shots and hits older than this (in seconds) are not considered when attributing a kill.
+const HIT_WINDOW: f64 = 300.0;
+
+/// Hits that occurred within this time (in seconds) before the death of a unit are attributed with
+/// the highest confidence.
+const RECENT_HIT: f64 = 30.0;
+
+/// How long (in seconds) to remember that a kill has already been attributed for a unit, to not
+/// attribute it twice (e.g. once for its `Crash` and once for its `Dead` event).
+const ATTRIBUTED_RETENTION: f64 = 3600.0;
+
+/// Correlates the mission's events and emits an [AttributedKillEvent] for every unit that got
+/// killed by another unit.
+pub async fn run_in_background(
+    ipc: IPC<StreamEventsResponse>,
+    mut shutdown_signal: impl Future<Output = ()> + Unpin,
+) {
+    let mut events = ipc.events().await;
+    let mut correlator = Correlator::default();
+
+    loop {
+        // wait for either the shutdown signal or the next event, whatever happens first
+        let StreamEventsResponse { time, event } = tokio::select! {
+            _ = &mut shutdown_signal => {
+                break
+            }
+            Some(event) = events.next() => event,
+            else => break,
+        };
+
+        if let Some(kill) = event.and_then(|event| correlator.handle(time, event)) {
+            ipc.event(StreamEventsResponse {
+                time,
+                event: Some(Event::AttributedKill(kill)),
+            })
+            .await;
+        }
+    }
+}
+
+/// The state used to attribute kills.
+#[derive(Default)]
+struct Correlator {
+    /// The unit that fired a weapon, by weapon id.
+    shots: HashMap<u32, Shot>,
+    /// The last hit of a unit, by the id of the unit that got hit.
+    hits: HashMap<u32, Hit>,
+    /// The time a kill got attributed, by the id of the unit that got killed.
+    attributed: HashMap<u32, f64>,
+    last_pruned: f64,
+}
+
+struct Shot {
+    time: f64,
+    shooter: Unit,
+}
+
+struct Hit {
+    time: f64,
+    shooter: Option<Unit>,
+    weapon_name: Option<String>,
+}
+
+impl Correlator {
+    /// Update the state with the given event and return a kill if the event concluded one.
+    fn handle(&mut self, time: f64, event: Event) -> Option<AttributedKillEvent> {
+        self.prune(time);
+
+        match event {
+            Event::Shot(ShotEvent {
+                initiator,
+                weapon: Some(weapon),
+            }) => {
+                if let Some(shooter) = initiator_unit(initiator) {
+                    self.shots.insert(weapon.id, Shot { time, shooter });
+                }
+                None
+            }
+
+            Event::Hit(HitEvent {
+                initiator,
+                weapon,
+                target,
+                weapon_name,
+            }) => {
+                let victim = target_unit(target)?;
+                // units hitting themselves (e.g. with the debris of their own weapon) don't count
+                let shooter = self
+                    .shooter(initiator, weapon.as_ref())
+                    .filter(|shooter| shooter.id != victim.id);
+                // Don't let a hit without a known shooter (e.g. a collision after being damaged)
+                // replace a hit with a known shooter.
+                if shooter.is_none() && self.hits.contains_key(&victim.id) {
+                    return None;
+                }
+
+                self.hits.insert(
+                    victim.id,
+                    Hit {
+                        time,
+                        shooter,
+                        weapon_name: weapon.and_then(|weapon| weapon.r#type).or(weapon_name),
+                    },
+                );
+                None
+            }
+
+            Event::Kill(KillEvent {
+                initiator,
+                weapon,
+                target,
+                weapon_name,
+            }) => {
+                let victim = target_unit(target)?;
+                match self
+                    .shooter(initiator, weapon.as_ref())
+                    .filter(|killer| killer.id != victim.id)
+                {
+                    Some(killer) => {
+                        let weapon_name = weapon.and_then(|weapon| weapon.r#type).or(weapon_name);
+                        self.attribute(time, victim, killer, weapon_name, 1.0)
+                    }
+                    None => self.attribute_to_last_hit(time, victim),
+                }
+            }
+
+            Event::Dead(DeadEvent { initiator })
+            | Event::Crash(CrashEvent { initiator })
+            | Event::PilotDead(PilotDeadEvent { initiator }) => {
+                let victim = initiator_unit(initiator)?;
+                self.attribute_to_last_hit(time, victim)
+            }
+
+            // An ejection after being hit is considered a kill, otherwise wait for the abandoned
+            // aircraft to crash.
+            Event::Ejection(EjectionEvent { initiator, .. }) => {
+                let victim = initiator_unit(initiator)?;
+                if self.hits.contains_key(&victim.id) {
+                    self.attribute_to_last_hit(time, victim)
+                } else {
+                    None
+                }
+            }
+
+            _ => None,
+        }
+    }
+
+    /// Determine the shooter either from the initiator of an event, or from the shot of the given
+    /// weapon.
+    fn shooter(&self, initiator: Option<Initiator>, weapon: Option<&Weapon>) -> Option<Unit> {
+        initiator_unit(initiator).or_else(|| {
+            weapon
+                .and_then(|weapon| self.shots.get(&weapon.id))
+                .map(|shot| shot.shooter.clone())
+        })
+    }
+
+    /// Attribute the kill of the victim to whoever hit it last. Deaths without a recent hit by a
+    /// known shooter (e.g. plain crashes or units destroyed by a script) aren't attributed at all.
+    fn attribute_to_last_hit(&mut self, time: f64, victim: Unit) -> Option<AttributedKillEvent> {
+        let hit = self.hits.get(&victim.id)?;
+        if time - hit.time > HIT_WINDOW {
+            return None;
+        }
+
+        let killer = hit.shooter.clone()?;
+        let weapon_name = hit.weapon_name.clone();
+        let confidence = hit_confidence(time - hit.time);
+        self.attribute(time, victim, killer, weapon_name, confidence)
+    }
+
+    fn attribute(
+        &mut self,
+        time: f64,
+        victim: Unit,
+        killer: Unit,
+        weapon_name: Option<String>,
+        confidence: f64,
+    ) -> Option<AttributedKillEvent> {
+        // only attribute one kill per unit
+        if self.attributed.insert(victim.id, time).is_some() {
+            return None;
+        }
+        self.hits.remove(&victim.id);
+
+        Some(AttributedKillEvent {
+            killer_player_name: killer.player_name.clone(),
+            victim: Some(victim),
+            killer: Some(killer),
+            weapon_name,
+            confidence,
+        })
+    }
+
+    /// Forget about shots, hits and kills that are too old to still be relevant.
+    fn prune(&mut self, time: f64) {
+        // no need to do that for every single event
+        if time - self.last_pruned < 10.0 {
+            return;
+        }
+        self.last_pruned = time;
+
+        self.shots.retain(|_, shot| time - shot.time <= HIT_WINDOW);
+        self.hits.retain(|_, hit| time - hit.time <= HIT_WINDOW);
+        self.attributed
+            .retain(|_, attributed| time - *attributed <= ATTRIBUTED_RETENTION);
+    }
+}
+
+/// The confidence of attributing a kill to a hit that occurred `elapsed` seconds before the death
+/// of the unit. Decreases from 0.9 for recent hits to 0.5 for hits at the end of the window.
+fn hit_confidence(elapsed: f64) -> f64 {
+    if elapsed <= RECENT_HIT {
+        0.9
+    } else {
+        0.9 - 0.4 * (elapsed - RECENT_HIT) / (HIT_WINDOW - RECENT_HIT)
+    }
+}
+
+fn initiator_unit(initiator: Option<Initiator>) -> Option<Unit> {
+    match initiator?.initiator? {
+        initiator::Initiator::Unit(unit) => Some(unit),
+        _ => None,
+    }
+}
+
+fn target_unit(target: Option<Target>) -> Option<Unit> {
+    match target?.target? {
+        target::Target::Unit(unit) => Some(unit),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(id: u32) -> Unit {
+        Unit {
+            id,
+            ..Default::default()
+        }
+    }
+
+    fn initiator(id: u32) -> Option<Initiator> {
+        Some(Initiator {
+            initiator: Some(initiator::Initiator::Unit(unit(id))),
+        })
+    }
+
+    fn target(id: u32) -> Option<Target> {
+        Some(Target {
+            target: Some(target::Target::Unit(unit(id))),
+        })
+    }
+
+    fn weapon(id: u32) -> Weapon {
+        Weapon {
+            id,
+            r#type: Some("AIM_120C".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn hit(initiator_id: Option<u32>, target_id: u32, weapon_id: u32) -> Event {
+        Event::Hit(HitEvent {
+            initiator: initiator_id.and_then(initiator),
+            weapon: Some(weapon(weapon_id)),
+            target: target(target_id),
+            weapon_name: None,
+        })
+    }
+
+    fn dead(id: u32) -> Event {
+        Event::Dead(DeadEvent {
+            initiator: initiator(id),
+        })
+    }
+
+    fn killer_id(kill: &AttributedKillEvent) -> u32 {
+        kill.killer.as_ref().unwrap().id
+    }
+
+    #[test]
+    fn test_hit_confidence() {
+        assert_eq!(hit_confidence(0.0), 0.9);
+        assert_eq!(hit_confidence(RECENT_HIT), 0.9);
+        assert!((hit_confidence((RECENT_HIT + HIT_WINDOW) / 2.0) - 0.7).abs() < 1e-9);
+        assert!((hit_confidence(HIT_WINDOW) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_kill_event() {
+        let mut correlator = Correlator::default();
+        let kill = correlator
+            .handle(
+                10.0,
+                Event::Kill(KillEvent {
+                    initiator: initiator(1),
+                    weapon: Some(weapon(100)),
+                    target: target(2),
+                    weapon_name: None,
+                }),
+            )
+            .unwrap();
+        assert_eq!(killer_id(&kill), 1);
+        assert_eq!(kill.victim.as_ref().unwrap().id, 2);
+        assert_eq!(kill.weapon_name.as_deref(), Some("AIM_120C"));
+        assert_eq!(kill.confidence, 1.0);
+
+        // only attributed once
+        assert!(correlator.handle(11.0, dead(2)).is_none());
+    }
+
+    #[test]
+    fn test_attribute_to_last_hit() {
+        let mut correlator = Correlator::default();
+        assert!(correlator.handle(10.0, hit(Some(1), 3, 100)).is_none());
+        assert!(correlator.handle(20.0, hit(Some(2), 3, 101)).is_none());
+
+        let kill = correlator.handle(80.0, dead(3)).unwrap();
+        assert_eq!(killer_id(&kill), 2);
+        assert_eq!(kill.confidence, hit_confidence(60.0));
+    }
+
+    #[test]
+    fn test_shooter_from_shot() {
+        let mut correlator = Correlator::default();
+        correlator.handle(
+            5.0,
+            Event::Shot(ShotEvent {
+                initiator: initiator(1),
+                weapon: Some(weapon(100)),
+            }),
+        );
+        // the hit is reported without an initiator (e.g. because the shooter already died)
+        correlator.handle(10.0, hit(None, 2, 100));
+
+        let kill = correlator.handle(12.0, dead(2)).unwrap();
+        assert_eq!(killer_id(&kill), 1);
+    }
+
+    #[test]
+    fn test_unknown_shooter_keeps_last_hit() {
+        let mut correlator = Correlator::default();
+        correlator.handle(10.0, hit(Some(1), 2, 100));
+        correlator.handle(15.0, hit(None, 2, 101));
+
+        let kill = correlator.handle(20.0, dead(2)).unwrap();
+        assert_eq!(killer_id(&kill), 1);
+    }
+
+    #[test]
+    fn test_no_kill_without_hit() {
+        let mut correlator = Correlator::default();
+        // crashed or destroyed by a script
+        assert!(correlator.handle(10.0, dead(1)).is_none());
+        // only hit by something unknown (e.g. a collision)
+        correlator.handle(20.0, hit(None, 2, 100));
+        assert!(correlator.handle(25.0, dead(2)).is_none());
+        // ejected without being hit
+        assert!(correlator
+            .handle(
+                30.0,
+                Event::Ejection(EjectionEvent {
+                    initiator: initiator(3),
+                    ..Default::default()
+                })
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn test_hit_outside_window() {
+        let mut correlator = Correlator::default();
+        correlator.handle(10.0, hit(Some(1), 2, 100));
+        assert!(correlator
+            .handle(10.0 + HIT_WINDOW + 1.0, dead(2))
+            .is_none());
+    }
+
+    #[test]
+    fn test_ignore_self_hits() {
+        let mut correlator = Correlator::default();
+        correlator.handle(10.0, hit(Some(1), 2, 100));
+        correlator.handle(15.0, hit(Some(2), 2, 101));
+        let kill = correlator.handle(20.0, dead(2)).unwrap();
+        assert_eq!(killer_id(&kill), 1);
+
+        // a self-kill is not attributed
+        assert!(correlator
+            .handle(
+                30.0,
+                Event::Kill(KillEvent {
+                    initiator: initiator(3),
+                    weapon: Some(weapon(102)),
+                    target: target(3),
+                    weapon_name: None,
+                })
+            )
+            .is_none());
+    }
+}
@@ -1,16 +1,30 @@
 #![allow(dead_code)]
 #![recursion_limit = "256"]
 
+mod access;
+mod atis;
+mod brevity;
+mod chat;
 mod config;
+mod coord;
+mod db;
+mod decisions;
 mod fps;
+mod gci;
 #[cfg(feature = "hot-reload")]
 mod hot_reload;
 mod integrity;
+mod kills;
+mod player_registry;
+mod player_stats;
+mod rotation;
 pub mod rpc;
 mod server;
 mod shutdown;
+mod sorties;
 mod stats;
 mod stream;
+mod templates;
 mod weapon;
 
 use std::path::PathBuf;
@@ -239,6 +253,59 @@ pub fn event(lua: &Lua, event: Value) -> LuaResult<()> {
     Ok(())
 }
 
+#[no_mangle]
+pub fn chat_command(
+    _lua: &Lua,
+    (time, player_id, ucid, message, coalition): (f64, u32, String, String, i32),
+) -> LuaResult<(bool, Option<String>)> {
+    let start = Instant::now();
+
+    if let Some(server) = &*SERVER.read().unwrap() {
+        let _guard = server.stats().track_block_time(start);
+        return Ok(server.chat_command(time, player_id, &ucid, &message, coalition));
+    }
+
+    Ok((false, None))
+}
+
+#[no_mangle]
+pub fn check_access(
+    _lua: &Lua,
+    (time, addr, name, ucid): (f64, String, String, String),
+) -> LuaResult<(bool, Option<String>)> {
+    let start = Instant::now();
+
+    if let Some(server) = &*SERVER.read().unwrap() {
+        let _guard = server.stats().track_block_time(start);
+        if let Some(reason) = server.check_access(time, addr, name, ucid) {
+            return Ok((false, Some(reason)));
+        }
+    }
+
+    Ok((true, None))
+}
+
+#[no_mangle]
+pub fn decide(lua: &Lua, decision: Value) -> LuaResult<(bool, Option<String>)> {
+    let start = Instant::now();
+
+    let decision = match lua.from_value(decision) {
+        Ok(decision) => decision,
+        Err(err) => {
+            log::error!("failed to deserialize decision: {}", err);
+            return Ok((true, None));
+        }
+    };
+
+    if let Some(server) = &*SERVER.read().unwrap() {
+        let _guard = server.stats().track_block_time(start);
+        let verdict = server.decide(decision);
+        return Ok((verdict.allow, verdict.reason));
+    }
+
+    Ok((true, None))
+}
+
 // This method is called on each simulation frame, so make sure to do as few as possible (avoid
 // even getting a lock on [SERVER]).
 #[no_mangle]
@@ -295,6 +362,15 @@ pub fn dcs_grpc_hot_reload(lua: &Lua) -> LuaResult<LuaTable> {
     exports.set("stop", lua.create_function(hot_reload::stop)?)?;
     exports.set("next", lua.create_function(hot_reload::next)?)?;
     exports.set("event", lua.create_function(hot_reload::event)?)?;
+    exports.set(
+        "chatCommand",
+        lua.create_function(hot_reload::chat_command)?,
+    )?;
+    exports.set(
+        "checkAccess",
+        lua.create_function(hot_reload::check_access)?,
+    )?;
+    exports.set("decide", lua.create_function(hot_reload::decide)?)?;
     exports.set(
         "simulationFrame",
         lua.create_function(hot_reload::simulation_frame)?,
@@ -315,6 +391,9 @@ pub fn dcs_grpc(lua: &Lua) -> LuaResult<LuaTable> {
     exports.set("stop", lua.create_function(stop)?)?;
     exports.set("next", lua.create_function(next)?)?;
     exports.set("event", lua.create_function(event)?)?;
+    exports.set("chatCommand", lua.create_function(chat_command)?)?;
+    exports.set("checkAccess", lua.create_function(check_access)?)?;
+    exports.set("decide", lua.create_function(decide)?)?;
     exports.set("simulationFrame", lua.create_function(simulation_frame)?)?;
     exports.set("tts", lua.create_function(tts)?)?;
     exports.set("logError", lua.create_function(log_error)?)?;
@@ -0,0 +1,649 @@
+use std::future::Future;
+use std::path::Path;
+
+use dcs_module_ipc::IPC;
+use futures_util::StreamExt;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use stubs::mission::v0::stream_events_response::{
+    BanEnforcedEvent, ConnectEvent, DisconnectEvent, DisconnectReason, Event, PlayerChangeSlotEvent,
+};
+use stubs::mission::v0::StreamEventsResponse;
+use stubs::net::v0::get_players_response::GetPlayerInfo;
+use stubs::net::v0::{GetPlayersRequest, GetPlayersResponse};
+use stubs::player::v0::{Alias, Player, Session, SlotChange};
+
+use crate::access::{ip_address, now};
+use crate::db::Database;
+
+/// The player id of the server itself, which is always listed as a connected player.
+pub(crate) const SERVER_PLAYER_ID: u32 = 1;
+
+/// Records the connections and slot changes of all players in an SQLite database.
+#[derive(Clone)]
+pub struct PlayerRegistry {
+    db: Database,
+}
+
+impl PlayerRegistry {
+    /// Open (or create) the database at the given path.
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        let db = Database::open(
+            path,
+            "CREATE TABLE IF NOT EXISTS aliases (
+                ucid TEXT NOT NULL,
+                name TEXT NOT NULL,
+                first_seen INTEGER NOT NULL,
+                last_seen INTEGER NOT NULL,
+                PRIMARY KEY (ucid, name)
+            );
+            CREATE TABLE IF NOT EXISTS sessions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                ucid TEXT NOT NULL,
+                name TEXT NOT NULL,
+                ip_address TEXT NOT NULL,
+                player_id INTEGER NOT NULL,
+                connected_at INTEGER NOT NULL,
+                disconnected_at INTEGER,
+                disconnect_reason INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE INDEX IF NOT EXISTS sessions_ucid ON sessions (ucid);
+            CREATE TABLE IF NOT EXISTS slot_changes (
+                session_id INTEGER NOT NULL,
+                time INTEGER NOT NULL,
+                coalition INTEGER NOT NULL,
+                slot_id TEXT NOT NULL,
+                group_name TEXT NOT NULL,
+                unit_type TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS slot_changes_session_id ON slot_changes (session_id);",
+        )?;
+
+        Ok(Self { db })
+    }
+
+    /// Get a player, the player's latest sessions and all IP addresses the player used.
+    pub async fn history(
+        &self,
+        ucid: String,
+        limit: u32,
+    ) -> Result<Option<(Player, Vec<Session>, Vec<String>)>, Error> {
+        self.with_db(move |db| {
+            let player = match player(db, &ucid)? {
+                Some(player) => player,
+                None => return Ok(None),
+            };
+            let sessions = sessions(
+                db,
+                "WHERE ucid = ?1 ORDER BY id DESC LIMIT ?2",
+                params![ucid, limit],
+            )?;
+            let ip_addresses = db
+                .prepare(
+                    "SELECT ip_address FROM sessions WHERE ucid = ?1
+                    GROUP BY ip_address ORDER BY MAX(connected_at) DESC",
+                )?
+                .query_map(params![ucid], |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Some((player, sessions, ip_addresses)))
+        })
+        .await
+    }
+
+    /// Search players by any of their names.
+    pub async fn search(&self, name: String, limit: u32) -> Result<Vec<Player>, Error> {
+        self.with_db(move |db| {
+            let pattern = format!(
+                "%{}%",
+                name.replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_")
+            );
+            let ucids = db
+                .prepare(
+                    "SELECT ucid FROM aliases GROUP BY ucid
+                    HAVING SUM(name LIKE ?1 ESCAPE '\\') > 0
+                    ORDER BY MAX(last_seen) DESC LIMIT ?2",
+                )?
+                .query_map(params![pattern, limit], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut players = Vec::with_capacity(ucids.len());
+            for ucid in ucids {
+                if let Some(player) = player(db, &ucid)? {
+                    players.push(player);
+                }
+            }
+            Ok(players)
+        })
+        .await
+    }
+
+    /// Get all sessions that started or ended since the given time (unixtime).
+    pub async fn connection_log(&self, since: u64, limit: u32) -> Result<Vec<Session>, Error> {
+        self.with_db(move |db| {
+            sessions(
+                db,
+                "WHERE connected_at >= ?1 OR disconnected_at >= ?1 ORDER BY id DESC LIMIT ?2",
+                params![since, limit],
+            )
+        })
+        .await
+    }
+
+    pub async fn run_in_background(
+        self,
+        ipc: IPC<StreamEventsResponse>,
+        mut shutdown_signal: impl Future<Output = ()> + Unpin,
+    ) {
+        let mut events = ipc.events().await;
+
+        // Sessions might have been left open by a previous run (e.g. if DCS crashed), and players
+        // that are already connected (e.g. after a mission change) don't connect again.
+        if let Err(err) = self.resume_sessions(&ipc).await {
+            log::error!("Failed to resume player sessions: {}", err);
+        }
+
+        loop {
+            // wait for either the shutdown signal or the next event, whatever happens first
+            let event = tokio::select! {
+                _ = &mut shutdown_signal => {
+                    break
+                }
+                Some(StreamEventsResponse { event, .. }) = events.next() => event,
+                else => break,
+            };
+
+            let result = match event {
+                Some(Event::Connect(event)) => self.with_db(|db| connect(db, event)).await,
+                Some(Event::Disconnect(event)) => self.with_db(|db| disconnect(db, event)).await,
+                Some(Event::PlayerChangeSlot(event)) => {
+                    self.with_db(|db| change_slot(db, event)).await
+                }
+                Some(Event::BanEnforced(event)) => self.with_db(|db| rejected(db, event)).await,
+                _ => continue,
+            };
+
+            if let Err(err) = result {
+                log::error!("Failed to record player connection: {}", err);
+            }
+        }
+    }
+
+    /// Close all open sessions of players that are not connected anymore, and open sessions for
+    /// connected players that don't have one yet.
+    async fn resume_sessions(&self, ipc: &IPC<StreamEventsResponse>) -> Result<(), Error> {
+        let players: GetPlayersResponse = ipc
+            .request("getPlayers", Some(GetPlayersRequest {}))
+            .await?;
+        self.with_db(move |db| resume(db, players.players)).await
+    }
+
+    /// Run the given database operation on a thread where blocking is acceptable.
+    async fn with_db<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> Result<T, Error> + Send + 'static,
+    {
+        self.db.run(f).await
+    }
+}
+
+fn resume(db: &Connection, players: Vec<GetPlayerInfo>) -> Result<(), Error> {
+    let now = now();
+    let open_sessions = db
+        .prepare("SELECT id, player_id, ucid FROM sessions WHERE disconnected_at IS NULL")?
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, u32>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut resumed = Vec::new();
+    for (session_id, player_id, ucid) in open_sessions {
+        let connected = players
+            .iter()
+            .any(|player| player.id == player_id && player.ucid == ucid);
+        if connected && !resumed.contains(&player_id) {
+            resumed.push(player_id);
+        } else {
+            db.execute(
+                "UPDATE sessions SET disconnected_at = ?2 WHERE id = ?1",
+                params![session_id, now],
+            )?;
+        }
+    }
+
+    for player in players {
+        // the server itself never connects
+        if player.id == SERVER_PLAYER_ID || resumed.contains(&player.id) {
+            continue;
+        }
+        connect(
+            db,
+            ConnectEvent {
+                addr: player.remote_address,
+                name: player.name,
+                ucid: player.ucid,
+                id: player.id,
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+fn connect(db: &Connection, event: ConnectEvent) -> Result<(), Error> {
+    let now = now();
+
+    // Players connecting while the sessions are resumed are already part of the resumed players,
+    // so don't open a second session once their connect event is handled afterwards.
+    let connected = db
+        .query_row(
+            "SELECT 1 FROM sessions
+            WHERE player_id = ?1 AND ucid = ?2 AND disconnected_at IS NULL",
+            params![event.id, event.ucid],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    if !connected {
+        db.execute(
+            "INSERT INTO sessions (ucid, name, ip_address, player_id, connected_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                event.ucid,
+                event.name,
+                ip_address(&event.addr),
+                event.id,
+                now
+            ],
+        )?;
+    }
+    db.execute(
+        "INSERT INTO aliases (ucid, name, first_seen, last_seen) VALUES (?1, ?2, ?3, ?3)
+        ON CONFLICT (ucid, name) DO UPDATE SET last_seen = ?3",
+        params![event.ucid, event.name, now],
+    )?;
+
+    Ok(())
+}
+
+fn disconnect(db: &Connection, event: DisconnectEvent) -> Result<(), Error> {
+    if let Some(session_id) = open_session(db, event.id)? {
+        db.execute(
+            "UPDATE sessions SET disconnected_at = ?2, disconnect_reason = ?3 WHERE id = ?1",
+            params![session_id, now(), event.reason],
+        )?;
+    }
+    Ok(())
+}
+
+fn change_slot(db: &Connection, event: PlayerChangeSlotEvent) -> Result<(), Error> {
+    if let Some(session_id) = open_session(db, event.player_id)? {
+        db.execute(
+            "INSERT INTO slot_changes (session_id, time, coalition, slot_id, group_name, unit_type)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                session_id,
+                now(),
+                event.coalition,
+                event.slot_id,
+                event.group_name,
+                event.unit_type
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Record the connection attempt of a player that got rejected due to a ban or the whitelist as
+/// an already closed session. The player's id is not known in this case, so it is recorded as
+/// `0`.
+fn rejected(db: &Connection, event: BanEnforcedEvent) -> Result<(), Error> {
+    let now = now();
+    db.execute(
+        "INSERT INTO sessions
+        (ucid, name, ip_address, player_id, connected_at, disconnected_at, disconnect_reason)
+        VALUES (?1, ?2, ?3, 0, ?4, ?4, ?5)",
+        params![
+            event.ucid,
+            event.name,
+            ip_address(&event.addr),
+            now,
+            DisconnectReason::Banned as i32
+        ],
+    )?;
+    db.execute(
+        "INSERT INTO aliases (ucid, name, first_seen, last_seen) VALUES (?1, ?2, ?3, ?3)
+        ON CONFLICT (ucid, name) DO UPDATE SET last_seen = ?3",
+        params![event.ucid, event.name, now],
+    )?;
+    Ok(())
+}
+
+/// The id of the open session of the player with the given id. Player ids are only unique within
+/// a DCS session, so sessions left open by a previous DCS session are ignored by preferring the
+/// latest one.
+fn open_session(db: &Connection, player_id: u32) -> Result<Option<i64>, Error> {
+    Ok(db
+        .query_row(
+            "SELECT id FROM sessions WHERE player_id = ?1 AND disconnected_at IS NULL
+            ORDER BY id DESC LIMIT 1",
+            params![player_id],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+fn player(db: &Connection, ucid: &str) -> Result<Option<Player>, Error> {
+    let aliases = db
+        .prepare(
+            "SELECT name, first_seen, last_seen FROM aliases WHERE ucid = ?1
+            ORDER BY last_seen DESC",
+        )?
+        .query_map(params![ucid], |row| {
+            Ok(Alias {
+                name: row.get(0)?,
+                first_seen: row.get(1)?,
+                last_seen: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let latest = match aliases.first() {
+        Some(latest) => latest,
+        None => return Ok(None),
+    };
+    Ok(Some(Player {
+        ucid: ucid.to_string(),
+        name: latest.name.clone(),
+        first_seen: aliases
+            .iter()
+            .map(|a| a.first_seen)
+            .min()
+            .unwrap_or_default(),
+        last_seen: latest.last_seen,
+        aliases,
+    }))
+}
+
+/// Query sessions (including their slot changes) using the given `WHERE` and `ORDER` clauses.
+fn sessions(
+    db: &Connection,
+    clauses: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<Session>, Error> {
+    let mut sessions = db
+        .prepare(&format!(
+            "SELECT id, ucid, name, ip_address, player_id, connected_at, disconnected_at,
+                disconnect_reason
+            FROM sessions {clauses}"
+        ))?
+        .query_map(params, session_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stmt = db.prepare(
+        "SELECT time, coalition, slot_id, group_name, unit_type FROM slot_changes
+        WHERE session_id = ?1 ORDER BY time, rowid",
+    )?;
+    for session in &mut sessions {
+        session.slot_changes = stmt
+            .query_map(params![session.id], |row| {
+                Ok(SlotChange {
+                    time: row.get(0)?,
+                    coalition: row.get(1)?,
+                    slot_id: row.get(2)?,
+                    group_name: row.get(3)?,
+                    unit_type: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+    }
+
+    Ok(sessions)
+}
+
+fn session_from_row(row: &Row<'_>) -> rusqlite::Result<Session> {
+    Ok(Session {
+        id: row.get(0)?,
+        ucid: row.get(1)?,
+        name: row.get(2)?,
+        ip_address: row.get(3)?,
+        player_id: row.get(4)?,
+        connected_at: row.get(5)?,
+        disconnected_at: row.get(6)?,
+        disconnect_reason: row.get(7)?,
+        slot_changes: Vec::new(),
+    })
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Db(#[from] crate::db::Error),
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+    #[error(transparent)]
+    Ipc(#[from] dcs_module_ipc::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use stubs::common::v0::Coalition;
+
+    use super::*;
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn open() -> PlayerRegistry {
+        PlayerRegistry::open(Path::new(":memory:")).unwrap()
+    }
+
+    fn run(registry: &PlayerRegistry, f: impl FnOnce(&Connection) -> Result<(), Error>) {
+        registry.db.run_blocking(f).unwrap();
+    }
+
+    fn connect_event(id: u32, ucid: &str) -> ConnectEvent {
+        ConnectEvent {
+            addr: String::from("127.0.0.1:10308"),
+            name: format!("Player {}", ucid),
+            ucid: ucid.to_string(),
+            id,
+        }
+    }
+
+    fn player_info(id: u32, ucid: &str) -> GetPlayerInfo {
+        GetPlayerInfo {
+            id,
+            name: format!("Player {}", ucid),
+            remote_address: String::from("127.0.0.1:10308"),
+            ucid: ucid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn sessions_of(registry: &PlayerRegistry, ucid: &str) -> Vec<Session> {
+        block_on(registry.history(ucid.to_string(), 10))
+            .unwrap()
+            .map(|(_, sessions, _)| sessions)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_connect_disconnect() {
+        let registry = open();
+        run(&registry, |db| connect(db, connect_event(2, "a")));
+
+        let (player, sessions, ip_addresses) = block_on(registry.history(String::from("a"), 10))
+            .unwrap()
+            .unwrap();
+        assert_eq!(player.name, "Player a");
+        assert_eq!(player.aliases.len(), 1);
+        assert_eq!(ip_addresses, vec!["127.0.0.1"]);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].player_id, 2);
+        assert_eq!(sessions[0].ip_address, "127.0.0.1");
+        assert_eq!(sessions[0].disconnected_at, None);
+
+        // disconnects of unknown players are ignored
+        run(&registry, |db| {
+            disconnect(
+                db,
+                DisconnectEvent {
+                    id: 3,
+                    reason: DisconnectReason::Timeout.into(),
+                },
+            )
+        });
+        assert_eq!(sessions_of(&registry, "a")[0].disconnected_at, None);
+
+        run(&registry, |db| {
+            disconnect(
+                db,
+                DisconnectEvent {
+                    id: 2,
+                    reason: DisconnectReason::Timeout.into(),
+                },
+            )
+        });
+        let sessions = sessions_of(&registry, "a");
+        assert_eq!(sessions.len(), 1);
+        assert!(sessions[0].disconnected_at.is_some());
+        assert_eq!(sessions[0].disconnect_reason(), DisconnectReason::Timeout);
+
+        // reconnecting opens a new session
+        run(&registry, |db| connect(db, connect_event(4, "a")));
+        let sessions = sessions_of(&registry, "a");
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].player_id, 4);
+        assert_eq!(sessions[0].disconnected_at, None);
+    }
+
+    #[test]
+    fn test_connect_after_resume() {
+        let registry = open();
+        run(&registry, |db| resume(db, vec![player_info(2, "a")]));
+        // the connect event of a player that connected while the sessions got resumed
+        run(&registry, |db| connect(db, connect_event(2, "a")));
+        assert_eq!(sessions_of(&registry, "a").len(), 1);
+    }
+
+    #[test]
+    fn test_resume_sessions() {
+        let registry = open();
+        run(&registry, |db| {
+            connect(db, connect_event(2, "a"))?;
+            connect(db, connect_event(3, "b"))?;
+            connect(db, connect_event(4, "c"))?;
+            // a session left open by a previous run that used the same player id
+            connect(db, connect_event(5, "e"))?;
+            connect(db, connect_event(6, "e"))?;
+            db.execute("UPDATE sessions SET player_id = 6 WHERE ucid = 'e'", [])?;
+            Ok(())
+        });
+
+        run(&registry, |db| {
+            resume(
+                db,
+                vec![
+                    player_info(SERVER_PLAYER_ID, "server"),
+                    // still connected
+                    player_info(2, "a"),
+                    // the player id got reused by another player
+                    player_info(4, "d"),
+                    player_info(6, "e"),
+                    // connected while the server was not running
+                    player_info(7, "f"),
+                ],
+            )
+        });
+
+        let a = sessions_of(&registry, "a");
+        assert_eq!(a.len(), 1);
+        assert_eq!(a[0].disconnected_at, None);
+
+        let b = sessions_of(&registry, "b");
+        assert_eq!(b.len(), 1);
+        assert!(b[0].disconnected_at.is_some());
+
+        let c = sessions_of(&registry, "c");
+        assert_eq!(c.len(), 1);
+        assert!(c[0].disconnected_at.is_some());
+
+        let d = sessions_of(&registry, "d");
+        assert_eq!(d.len(), 1);
+        assert_eq!(d[0].player_id, 4);
+        assert_eq!(d[0].disconnected_at, None);
+
+        // only one of the duplicate sessions is resumed
+        let e = sessions_of(&registry, "e");
+        assert_eq!(e.len(), 2);
+        assert_eq!(e.iter().filter(|s| s.disconnected_at.is_none()).count(), 1);
+
+        let f = sessions_of(&registry, "f");
+        assert_eq!(f.len(), 1);
+        assert_eq!(f[0].player_id, 7);
+        assert_eq!(f[0].disconnected_at, None);
+
+        assert!(sessions_of(&registry, "server").is_empty());
+    }
+
+    #[test]
+    fn test_slot_change_history() {
+        let registry = open();
+        let change_slot_event = |slot_id: &str, unit_type: &str| PlayerChangeSlotEvent {
+            player_id: 2,
+            coalition: Coalition::Blue.into(),
+            slot_id: slot_id.to_string(),
+            group_name: format!("Group {}", slot_id),
+            unit_type: unit_type.to_string(),
+        };
+        run(&registry, |db| {
+            connect(db, connect_event(2, "a"))?;
+            change_slot(db, change_slot_event("1", "F-16C_50"))?;
+            change_slot(db, change_slot_event("2", "FA-18C_hornet"))?;
+            disconnect(
+                db,
+                DisconnectEvent {
+                    id: 2,
+                    reason: DisconnectReason::ThatsOkay.into(),
+                },
+            )?;
+            // slot changes after the disconnect are not recorded
+            change_slot(db, change_slot_event("3", "A-10C_2"))?;
+            Ok(())
+        });
+
+        let sessions = sessions_of(&registry, "a");
+        assert_eq!(sessions.len(), 1);
+        assert!(sessions[0].disconnected_at.is_some());
+        assert_eq!(sessions[0].disconnect_reason(), DisconnectReason::ThatsOkay);
+        let slot_changes = sessions[0]
+            .slot_changes
+            .iter()
+            .map(|s| {
+                (
+                    s.slot_id.as_str(),
+                    s.group_name.as_str(),
+                    s.unit_type.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            slot_changes,
+            vec![
+                ("1", "Group 1", "F-16C_50"),
+                ("2", "Group 2", "FA-18C_hornet"),
+            ]
+        );
+        assert_eq!(sessions[0].slot_changes[0].coalition(), Coalition::Blue);
+    }
+}
@@ -0,0 +1,896 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::Path;
+
+use dcs_module_ipc::IPC;
+use futures_util::StreamExt;
+use rusqlite::{params, Connection, OptionalExtension};
+use stubs::common::v0::{initiator, GroupCategory, Initiator, Unit};
+use stubs::mission::v0::stream_events_response::{
+    AttributedKillEvent, ConnectEvent, DisconnectEvent, Event, LandEvent, LandingQualityMarkEvent,
+    PlayerChangeSlotEvent,
+};
+use stubs::mission::v0::StreamEventsResponse;
+use stubs::net::v0::{GetPlayersRequest, GetPlayersResponse};
+use stubs::stats::v0::get_leaderboard_response::Entry;
+use stubs::stats::v0::{
+    AirframeFlightTime, LeaderboardMetric, PlayerStats as Stats, Sortie, SortieOutcome, StatsPeriod,
+};
+use time::{Duration, OffsetDateTime};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::db::Database;
+use crate::sorties::Sorties;
+
+/// Persists the statistics of all players in an SQLite database, so that they survive mission
+/// rotations and server restarts.
+#[derive(Clone)]
+pub struct PlayerStats {
+    db: Database,
+}
+
+/// The counters stored per player and day.
+#[derive(Debug, Clone, Copy)]
+enum Counter {
+    AirKills,
+    HelicopterKills,
+    GroundKills,
+    ShipKills,
+    Deaths,
+    Landings,
+    Traps,
+    Teamkills,
+}
+
+impl PlayerStats {
+    /// Open (or create) the database at the given path.
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        let db = Database::open(
+            path,
+            "CREATE TABLE IF NOT EXISTS players (
+                ucid TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS daily_stats (
+                ucid TEXT NOT NULL,
+                day TEXT NOT NULL,
+                air_kills INTEGER NOT NULL DEFAULT 0,
+                helicopter_kills INTEGER NOT NULL DEFAULT 0,
+                ground_kills INTEGER NOT NULL DEFAULT 0,
+                ship_kills INTEGER NOT NULL DEFAULT 0,
+                deaths INTEGER NOT NULL DEFAULT 0,
+                landings INTEGER NOT NULL DEFAULT 0,
+                traps INTEGER NOT NULL DEFAULT 0,
+                teamkills INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (ucid, day)
+            );
+            CREATE TABLE IF NOT EXISTS daily_flight_time (
+                ucid TEXT NOT NULL,
+                day TEXT NOT NULL,
+                airframe TEXT NOT NULL,
+                seconds REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY (ucid, day, airframe)
+            );",
+        )?;
+
+        Ok(Self { db })
+    }
+
+    /// Get the stats of the player with the given UCID, or `None` if the player is not known.
+    pub async fn get(&self, ucid: String, period: StatsPeriod) -> Result<Option<Stats>, Error> {
+        let since = first_day(period);
+        self.with_db(move |db| {
+            let name = db
+                .query_row(
+                    "SELECT name FROM players WHERE ucid = ?1",
+                    params![ucid],
+                    |row| row.get::<_, String>(0),
+                )
+                .optional()?;
+            let name = match name {
+                Some(name) => name,
+                None => return Ok(None),
+            };
+
+            let mut stats = db.query_row(
+                "SELECT
+                    COALESCE(SUM(air_kills), 0), COALESCE(SUM(helicopter_kills), 0),
+                    COALESCE(SUM(ground_kills), 0), COALESCE(SUM(ship_kills), 0),
+                    COALESCE(SUM(deaths), 0), COALESCE(SUM(landings), 0),
+                    COALESCE(SUM(traps), 0), COALESCE(SUM(teamkills), 0)
+                FROM daily_stats WHERE ucid = ?1 AND day >= ?2",
+                params![ucid, since],
+                |row| {
+                    Ok(Stats {
+                        air_kills: row.get(0)?,
+                        helicopter_kills: row.get(1)?,
+                        ground_kills: row.get(2)?,
+                        ship_kills: row.get(3)?,
+                        deaths: row.get(4)?,
+                        landings: row.get(5)?,
+                        traps: row.get(6)?,
+                        teamkills: row.get(7)?,
+                        ..Default::default()
+                    })
+                },
+            )?;
+
+            let mut stmt = db.prepare(
+                "SELECT airframe, SUM(seconds) AS total FROM daily_flight_time
+                WHERE ucid = ?1 AND day >= ?2
+                GROUP BY airframe ORDER BY total DESC",
+            )?;
+            stats.flight_time_by_airframe = stmt
+                .query_map(params![ucid, since], |row| {
+                    Ok(AirframeFlightTime {
+                        airframe: row.get(0)?,
+                        flight_time: row.get(1)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            stats.flight_time = stats
+                .flight_time_by_airframe
+                .iter()
+                .map(|a| a.flight_time)
+                .sum();
+            stats.ucid = ucid;
+            stats.name = name;
+
+            Ok(Some(stats))
+        })
+        .await
+    }
+
+    /// Get up to `limit` players ranked by the given metric.
+    pub async fn leaderboard(
+        &self,
+        metric: LeaderboardMetric,
+        period: StatsPeriod,
+        limit: u32,
+    ) -> Result<Vec<Entry>, Error> {
+        let (table, value) = match metric {
+            LeaderboardMetric::Unspecified => return Err(Error::UnspecifiedMetric),
+            LeaderboardMetric::Kills => (
+                "daily_stats",
+                "air_kills + helicopter_kills + ground_kills + ship_kills",
+            ),
+            LeaderboardMetric::AirKills => ("daily_stats", Counter::AirKills.column()),
+            LeaderboardMetric::HelicopterKills => {
+                ("daily_stats", Counter::HelicopterKills.column())
+            }
+            LeaderboardMetric::GroundKills => ("daily_stats", Counter::GroundKills.column()),
+            LeaderboardMetric::ShipKills => ("daily_stats", Counter::ShipKills.column()),
+            LeaderboardMetric::Deaths => ("daily_stats", Counter::Deaths.column()),
+            LeaderboardMetric::Landings => ("daily_stats", Counter::Landings.column()),
+            LeaderboardMetric::Traps => ("daily_stats", Counter::Traps.column()),
+            LeaderboardMetric::Teamkills => ("daily_stats", Counter::Teamkills.column()),
+            LeaderboardMetric::FlightTime => ("daily_flight_time", "seconds"),
+        };
+        let since = first_day(period);
+
+        self.with_db(move |db| {
+            let mut stmt = db.prepare(&format!(
+                "SELECT s.ucid, p.name, CAST(SUM({value}) AS REAL) AS value
+                FROM {table} s JOIN players p ON p.ucid = s.ucid
+                WHERE s.day >= ?1
+                GROUP BY s.ucid HAVING value > 0
+                ORDER BY value DESC, p.name ASC
+                LIMIT ?2"
+            ))?;
+            let entries = stmt
+                .query_map(params![since, limit], |row| {
+                    Ok(Entry {
+                        rank: 0,
+                        ucid: row.get(0)?,
+                        name: row.get(1)?,
+                        value: row.get(2)?,
+                    })
+                })?
+                .enumerate()
+                .map(|(i, entry)| {
+                    entry.map(|entry| Entry {
+                        rank: i as u32 + 1,
+                        ..entry
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(entries)
+        })
+        .await
+    }
+
+    /// Record the stats from the mission's events and the sorties of the players.
+    pub async fn run_in_background(
+        self,
+        ipc: IPC<StreamEventsResponse>,
+        sorties: Sorties,
+        mut shutdown_signal: impl Future<Output = ()> + Unpin,
+    ) {
+        let mut events = ipc.events().await;
+        let mut updates = sorties.subscribe();
+        let mut recorder = Recorder {
+            stats: self,
+            ipc,
+            players: HashMap::new(),
+            completed: HashMap::new(),
+        };
+        let mut last_time = 0.0;
+
+        loop {
+            // wait for either the shutdown signal, the next event or the next sortie update,
+            // whatever happens first
+            let result = tokio::select! {
+                _ = &mut shutdown_signal => {
+                    // don't lose the flight time of the sorties that are still in progress
+                    for sortie in sorties.end_all(last_time).await {
+                        if let Err(err) = recorder.handle_sortie(sortie).await {
+                            log::error!("Failed to record player stats: {}", err);
+                        }
+                    }
+                    break
+                }
+                Some(StreamEventsResponse { time, event }) = events.next() => match event {
+                    Some(event) => {
+                        last_time = time;
+                        recorder.handle_event(event).await
+                    }
+                    None => continue,
+                },
+                sortie = updates.recv() => match sortie {
+                    Ok(sortie) => recorder.handle_sortie(sortie).await,
+                    Err(RecvError::Lagged(n)) => {
+                        log::warn!("Player stats missed {} sortie updates", n);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                else => break,
+            };
+
+            if let Err(err) = result {
+                log::error!("Failed to record player stats: {}", err);
+            }
+        }
+    }
+
+    /// Increment the given counter of the player for the current day.
+    async fn increment(&self, ucid: String, counter: Counter) -> Result<(), Error> {
+        let day = today();
+        self.with_db(move |db| {
+            let column = counter.column();
+            db.execute(
+                &format!(
+                    "INSERT INTO daily_stats (ucid, day, {column}) VALUES (?1, ?2, 1)
+                    ON CONFLICT (ucid, day) DO UPDATE SET {column} = {column} + 1"
+                ),
+                params![ucid, day],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn add_flight_time(
+        &self,
+        ucid: String,
+        airframe: String,
+        seconds: f64,
+    ) -> Result<(), Error> {
+        let day = today();
+        self.with_db(move |db| {
+            db.execute(
+                "INSERT INTO daily_flight_time (ucid, day, airframe, seconds) VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT (ucid, day, airframe) DO UPDATE SET seconds = seconds + ?4",
+                params![ucid, day, airframe, seconds],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn set_name(&self, ucid: String, name: String) -> Result<(), Error> {
+        self.with_db(move |db| {
+            db.execute(
+                "INSERT INTO players (ucid, name) VALUES (?1, ?2)
+                ON CONFLICT (ucid) DO UPDATE SET name = ?2",
+                params![ucid, name],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Run the given database operation on a thread where blocking is acceptable.
+    async fn with_db<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> Result<T, Error> + Send + 'static,
+    {
+        self.db.run(f).await
+    }
+}
+
+/// The state required to map events and sorties to the stats of a player.
+struct Recorder {
+    stats: PlayerStats,
+    ipc: IPC<StreamEventsResponse>,
+    /// All known players, by their id in the current server session.
+    players: HashMap<u32, KnownPlayer>,
+    /// The recorded outcome of all completed sorties, by sortie id.
+    completed: HashMap<u32, SortieOutcome>,
+}
+
+struct KnownPlayer {
+    name: String,
+    ucid: String,
+    /// Disconnected players are kept until the next refresh, as their last sortie updates might
+    /// only arrive after they disconnected.
+    connected: bool,
+}
+
+impl Recorder {
+    async fn handle_event(&mut self, event: Event) -> Result<(), Error> {
+        match event {
+            Event::Connect(ConnectEvent { name, ucid, id, .. }) => {
+                self.stats.set_name(ucid.clone(), name.clone()).await?;
+                self.players.insert(
+                    id,
+                    KnownPlayer {
+                        name,
+                        ucid,
+                        connected: true,
+                    },
+                );
+            }
+
+            Event::Disconnect(DisconnectEvent { id, .. }) => {
+                if let Some(player) = self.players.get_mut(&id) {
+                    player.connected = false;
+                }
+            }
+
+            // players can only change their name while not in a slot, so refresh the names once
+            // they pick one
+            Event::PlayerChangeSlot(PlayerChangeSlotEvent { .. }) => {
+                self.refresh_players().await?;
+            }
+
+            Event::AttributedKill(AttributedKillEvent {
+                victim: Some(victim),
+                killer: Some(killer),
+                ..
+            }) => {
+                let ucid = match self.player_ucid(&killer).await? {
+                    Some(ucid) => ucid,
+                    None => return Ok(()),
+                };
+                let counter = if victim.coalition == killer.coalition {
+                    Counter::Teamkills
+                } else {
+                    match victim.group.as_ref().map(|group| group.category()) {
+                        Some(GroupCategory::Airplane) => Counter::AirKills,
+                        Some(GroupCategory::Helicopter) => Counter::HelicopterKills,
+                        Some(GroupCategory::Ground) | Some(GroupCategory::Train) => {
+                            Counter::GroundKills
+                        }
+                        Some(GroupCategory::Ship) => Counter::ShipKills,
+                        Some(GroupCategory::Unspecified) | None => return Ok(()),
+                    }
+                };
+                self.stats.increment(ucid, counter).await?;
+            }
+
+            Event::Land(LandEvent { initiator, .. }) => {
+                if let Some(ucid) = self.initiator_ucid(initiator).await? {
+                    self.stats.increment(ucid, Counter::Landings).await?;
+                }
+            }
+
+            // DCS only grades landings on carriers
+            Event::LandingQualityMark(LandingQualityMarkEvent { initiator, .. }) => {
+                if let Some(ucid) = self.initiator_ucid(initiator).await? {
+                    self.stats.increment(ucid, Counter::Traps).await?;
+                }
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    async fn handle_sortie(&mut self, sortie: Sortie) -> Result<(), Error> {
+        // only record each sortie once it completed
+        let outcome = sortie.outcome();
+        if outcome == SortieOutcome::InProgress {
+            return Ok(());
+        }
+
+        // The outcome of a completed sortie might still be corrected to `Killed` later on (e.g.
+        // after crashing or ejecting), in which case only a death that wasn't recorded yet is.
+        let previous = self.completed.insert(sortie.id, outcome);
+        let first = previous.is_none();
+        let death = is_death(outcome) && !previous.map(is_death).unwrap_or(false);
+        if !first && !death {
+            return Ok(());
+        }
+
+        let ucid = match self.ucid(&sortie.player_name).await? {
+            Some(ucid) => ucid,
+            None => return Ok(()),
+        };
+
+        if first && sortie.flight_time > 0.0 {
+            self.stats
+                .add_flight_time(ucid.clone(), sortie.airframe.clone(), sortie.flight_time)
+                .await?;
+        }
+
+        if death {
+            self.stats.increment(ucid, Counter::Deaths).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn initiator_ucid(
+        &mut self,
+        initiator: Option<Initiator>,
+    ) -> Result<Option<String>, Error> {
+        match initiator.and_then(|initiator| initiator.initiator) {
+            Some(initiator::Initiator::Unit(unit)) => self.player_ucid(&unit).await,
+            _ => Ok(None),
+        }
+    }
+
+    async fn player_ucid(&mut self, unit: &Unit) -> Result<Option<String>, Error> {
+        match &unit.player_name {
+            Some(name) if !name.is_empty() => self.ucid(name).await,
+            _ => Ok(None),
+        }
+    }
+
+    /// Resolve the UCID of the player with the given name. Players that connected before the
+    /// server got started (e.g. after a reload of the mission) or that changed their name are
+    /// looked up on demand.
+    async fn ucid(&mut self, name: &str) -> Result<Option<String>, Error> {
+        if let Some(ucid) = self.cached_ucid(name) {
+            return Ok(Some(ucid));
+        }
+
+        self.refresh_players().await?;
+        Ok(self.cached_ucid(name))
+    }
+
+    /// The UCID of the player with the given name, preferring connected players over ones that
+    /// disconnected since the last refresh.
+    fn cached_ucid(&self, name: &str) -> Option<String> {
+        self.players
+            .values()
+            .filter(|player| player.name == name)
+            .max_by_key(|player| player.connected)
+            .map(|player| player.ucid.clone())
+    }
+
+    /// Replace all known players with the currently connected ones.
+    async fn refresh_players(&mut self) -> Result<(), Error> {
+        let players: GetPlayersResponse = self
+            .ipc
+            .request("getPlayers", Some(GetPlayersRequest {}))
+            .await?;
+
+        let mut refreshed = HashMap::with_capacity(players.players.len());
+        for player in players.players {
+            let unchanged = matches!(
+                self.players.get(&player.id),
+                Some(known) if known.name == player.name && known.ucid == player.ucid
+            );
+            if !unchanged {
+                self.stats
+                    .set_name(player.ucid.clone(), player.name.clone())
+                    .await?;
+            }
+            refreshed.insert(
+                player.id,
+                KnownPlayer {
+                    name: player.name,
+                    ucid: player.ucid,
+                    connected: true,
+                },
+            );
+        }
+        self.players = refreshed;
+
+        Ok(())
+    }
+}
+
+impl Counter {
+    fn column(&self) -> &'static str {
+        match self {
+            Counter::AirKills => "air_kills",
+            Counter::HelicopterKills => "helicopter_kills",
+            Counter::GroundKills => "ground_kills",
+            Counter::ShipKills => "ship_kills",
+            Counter::Deaths => "deaths",
+            Counter::Landings => "landings",
+            Counter::Traps => "traps",
+            Counter::Teamkills => "teamkills",
+        }
+    }
+}
+
+fn is_death(outcome: SortieOutcome) -> bool {
+    matches!(outcome, SortieOutcome::Crashed | SortieOutcome::Killed)
+}
+
+fn today() -> String {
+    OffsetDateTime::now_utc().date().to_string()
+}
+
+/// The first day (inclusive) of the given period, formatted as `YYYY-MM-DD`, which allows to
+/// compare days as strings.
+fn first_day(period: StatsPeriod) -> String {
+    let days = match period {
+        StatsPeriod::Unspecified | StatsPeriod::AllTime => return String::new(),
+        StatsPeriod::Day => 0,
+        StatsPeriod::Week => 6,
+        StatsPeriod::Month => 29,
+    };
+    (OffsetDateTime::now_utc().date() - Duration::days(days)).to_string()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Db(#[from] crate::db::Error),
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+    #[error(transparent)]
+    Ipc(#[from] dcs_module_ipc::Error),
+    #[error("a leaderboard metric is required")]
+    UnspecifiedMetric,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn open() -> PlayerStats {
+        PlayerStats::open(Path::new(":memory:")).unwrap()
+    }
+
+    fn day(days_ago: i64) -> String {
+        (OffsetDateTime::now_utc().date() - Duration::days(days_ago)).to_string()
+    }
+
+    fn insert_player(stats: &PlayerStats, ucid: &str, name: &str) {
+        stats
+            .db
+            .run_blocking(|db| -> Result<_, Error> {
+                db.execute(
+                    "INSERT INTO players (ucid, name) VALUES (?1, ?2)",
+                    params![ucid, name],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    fn insert_stat(stats: &PlayerStats, ucid: &str, days_ago: i64, column: &str, value: u32) {
+        let day = day(days_ago);
+        stats
+            .db
+            .run_blocking(|db| -> Result<_, Error> {
+                db.execute(
+                    &format!(
+                        "INSERT INTO daily_stats (ucid, day, {column}) VALUES (?1, ?2, ?3)
+                        ON CONFLICT (ucid, day) DO UPDATE SET {column} = {column} + ?3"
+                    ),
+                    params![ucid, day, value],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    fn insert_flight_time(
+        stats: &PlayerStats,
+        ucid: &str,
+        days_ago: i64,
+        airframe: &str,
+        seconds: f64,
+    ) {
+        let day = day(days_ago);
+        stats
+            .db
+            .run_blocking(|db| -> Result<_, Error> {
+                db.execute(
+                    "INSERT INTO daily_flight_time (ucid, day, airframe, seconds)
+                    VALUES (?1, ?2, ?3, ?4)",
+                    params![ucid, day, airframe, seconds],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    fn recorder(stats: PlayerStats, players: &[(u32, &str, &str, bool)]) -> Recorder {
+        Recorder {
+            stats,
+            ipc: IPC::default(),
+            players: players
+                .iter()
+                .map(|(id, name, ucid, connected)| {
+                    (
+                        *id,
+                        KnownPlayer {
+                            name: name.to_string(),
+                            ucid: ucid.to_string(),
+                            connected: *connected,
+                        },
+                    )
+                })
+                .collect(),
+            completed: HashMap::new(),
+        }
+    }
+
+    fn sortie(id: u32, outcome: SortieOutcome, flight_time: f64) -> Sortie {
+        Sortie {
+            id,
+            player_name: String::from("player"),
+            airframe: String::from("F-16C_50"),
+            flight_time,
+            outcome: outcome.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_get_by_period() {
+        let stats = open();
+        insert_player(&stats, "a", "Player A");
+        for (days_ago, kills) in [(0, 1), (3, 2), (10, 4), (40, 8)] {
+            insert_stat(&stats, "a", days_ago, "air_kills", kills);
+            insert_stat(&stats, "a", days_ago, "deaths", 1);
+            insert_flight_time(&stats, "a", days_ago, "F-16C_50", 60.0);
+        }
+        insert_flight_time(&stats, "a", 3, "FA-18C_hornet", 180.0);
+        // stats of other players must not be included
+        insert_player(&stats, "b", "Player B");
+        insert_stat(&stats, "b", 0, "air_kills", 16);
+
+        for (period, air_kills, deaths, flight_time) in [
+            (StatsPeriod::Day, 1, 1, 60.0),
+            (StatsPeriod::Week, 3, 2, 300.0),
+            (StatsPeriod::Month, 7, 3, 360.0),
+            (StatsPeriod::AllTime, 15, 4, 420.0),
+        ] {
+            let result = block_on(stats.get(String::from("a"), period))
+                .unwrap()
+                .unwrap();
+            assert_eq!(result.ucid, "a");
+            assert_eq!(result.name, "Player A");
+            assert_eq!(result.air_kills, air_kills, "{:?}", period);
+            assert_eq!(result.deaths, deaths, "{:?}", period);
+            assert_eq!(result.flight_time, flight_time, "{:?}", period);
+        }
+
+        let result = block_on(stats.get(String::from("a"), StatsPeriod::Week))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result.flight_time_by_airframe,
+            vec![
+                AirframeFlightTime {
+                    airframe: String::from("FA-18C_hornet"),
+                    flight_time: 180.0,
+                },
+                AirframeFlightTime {
+                    airframe: String::from("F-16C_50"),
+                    flight_time: 120.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_unknown_player() {
+        let stats = open();
+        assert!(block_on(stats.get(String::from("a"), StatsPeriod::AllTime))
+            .unwrap()
+            .is_none());
+
+        insert_player(&stats, "a", "Player A");
+        let result = block_on(stats.get(String::from("a"), StatsPeriod::AllTime))
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.air_kills, 0);
+        assert_eq!(result.flight_time, 0.0);
+        assert!(result.flight_time_by_airframe.is_empty());
+    }
+
+    #[test]
+    fn test_leaderboard_metrics() {
+        for (metric, column) in [
+            (LeaderboardMetric::AirKills, "air_kills"),
+            (LeaderboardMetric::HelicopterKills, "helicopter_kills"),
+            (LeaderboardMetric::GroundKills, "ground_kills"),
+            (LeaderboardMetric::ShipKills, "ship_kills"),
+            (LeaderboardMetric::Deaths, "deaths"),
+            (LeaderboardMetric::Landings, "landings"),
+            (LeaderboardMetric::Traps, "traps"),
+            (LeaderboardMetric::Teamkills, "teamkills"),
+        ] {
+            let stats = open();
+            for (ucid, name) in [("a", "A"), ("b", "B"), ("c", "C"), ("d", "D"), ("e", "E")] {
+                insert_player(&stats, ucid, name);
+            }
+            insert_stat(&stats, "a", 0, column, 1);
+            insert_stat(&stats, "b", 0, column, 3);
+            insert_stat(&stats, "b", 1, column, 2);
+            insert_stat(&stats, "c", 0, column, 4);
+            // ties are ordered by name
+            insert_stat(&stats, "d", 0, column, 1);
+            // players without any value are left out
+            insert_stat(&stats, "e", 0, "air_kills", 0);
+            insert_stat(&stats, "e", 0, "landings", 0);
+
+            let entries = block_on(stats.leaderboard(metric, StatsPeriod::AllTime, 10)).unwrap();
+            let entries = entries
+                .into_iter()
+                .map(|e| (e.rank, e.ucid, e.value))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                entries,
+                vec![
+                    (1, String::from("b"), 5.0),
+                    (2, String::from("c"), 4.0),
+                    (3, String::from("a"), 1.0),
+                    (4, String::from("d"), 1.0),
+                ],
+                "{:?}",
+                metric
+            );
+
+            let entries = block_on(stats.leaderboard(metric, StatsPeriod::Day, 2)).unwrap();
+            let ucids = entries.into_iter().map(|e| e.ucid).collect::<Vec<_>>();
+            assert_eq!(ucids, vec!["c", "b"], "{:?}", metric);
+        }
+    }
+
+    #[test]
+    fn test_leaderboard_kills() {
+        let stats = open();
+        for (ucid, name) in [("a", "A"), ("b", "B")] {
+            insert_player(&stats, ucid, name);
+        }
+        insert_stat(&stats, "a", 0, "air_kills", 1);
+        insert_stat(&stats, "a", 0, "helicopter_kills", 1);
+        insert_stat(&stats, "a", 0, "ground_kills", 1);
+        insert_stat(&stats, "a", 0, "ship_kills", 1);
+        insert_stat(&stats, "b", 0, "ground_kills", 3);
+        // neither deaths nor teamkills count as kills
+        insert_stat(&stats, "b", 0, "deaths", 5);
+        insert_stat(&stats, "b", 0, "teamkills", 5);
+
+        let entries =
+            block_on(stats.leaderboard(LeaderboardMetric::Kills, StatsPeriod::AllTime, 10))
+                .unwrap();
+        let entries = entries
+            .into_iter()
+            .map(|e| (e.rank, e.ucid, e.name, e.value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            vec![
+                (1, String::from("a"), String::from("A"), 4.0),
+                (2, String::from("b"), String::from("B"), 3.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_leaderboard_flight_time() {
+        let stats = open();
+        for (ucid, name) in [("a", "A"), ("b", "B")] {
+            insert_player(&stats, ucid, name);
+        }
+        insert_flight_time(&stats, "a", 0, "F-16C_50", 100.0);
+        insert_flight_time(&stats, "a", 0, "FA-18C_hornet", 100.0);
+        insert_flight_time(&stats, "b", 0, "F-16C_50", 150.0);
+        insert_flight_time(&stats, "b", 10, "F-16C_50", 100.0);
+
+        let entries =
+            block_on(stats.leaderboard(LeaderboardMetric::FlightTime, StatsPeriod::AllTime, 10))
+                .unwrap();
+        let entries = entries
+            .into_iter()
+            .map(|e| (e.ucid, e.value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            vec![(String::from("b"), 250.0), (String::from("a"), 200.0)]
+        );
+
+        let entries =
+            block_on(stats.leaderboard(LeaderboardMetric::FlightTime, StatsPeriod::Week, 10))
+                .unwrap();
+        let entries = entries
+            .into_iter()
+            .map(|e| (e.ucid, e.value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            vec![(String::from("a"), 200.0), (String::from("b"), 150.0)]
+        );
+    }
+
+    #[test]
+    fn test_leaderboard_unspecified_metric() {
+        let stats = open();
+        assert!(matches!(
+            block_on(stats.leaderboard(LeaderboardMetric::Unspecified, StatsPeriod::AllTime, 10)),
+            Err(Error::UnspecifiedMetric)
+        ));
+    }
+
+    #[test]
+    fn test_deaths_once_per_sortie() {
+        let stats = open();
+        insert_player(&stats, "a", "player");
+        let mut recorder = recorder(stats.clone(), &[(2, "player", "a", true)]);
+        let get = || {
+            block_on(stats.get(String::from("a"), StatsPeriod::AllTime))
+                .unwrap()
+                .unwrap()
+        };
+
+        // sorties in progress are not recorded
+        block_on(recorder.handle_sortie(sortie(1, SortieOutcome::InProgress, 60.0))).unwrap();
+        assert_eq!(get().flight_time, 0.0);
+
+        block_on(recorder.handle_sortie(sortie(1, SortieOutcome::Crashed, 60.0))).unwrap();
+        assert_eq!((get().deaths, get().flight_time), (1, 60.0));
+
+        // repeated updates and the correction of a crash to a kill are not counted again
+        block_on(recorder.handle_sortie(sortie(1, SortieOutcome::Crashed, 60.0))).unwrap();
+        block_on(recorder.handle_sortie(sortie(1, SortieOutcome::Killed, 60.0))).unwrap();
+        assert_eq!((get().deaths, get().flight_time), (1, 60.0));
+
+        // an ejection only becomes a death once corrected to a kill
+        block_on(recorder.handle_sortie(sortie(2, SortieOutcome::Ejected, 30.0))).unwrap();
+        assert_eq!((get().deaths, get().flight_time), (1, 90.0));
+        block_on(recorder.handle_sortie(sortie(2, SortieOutcome::Killed, 30.0))).unwrap();
+        assert_eq!((get().deaths, get().flight_time), (2, 90.0));
+        block_on(recorder.handle_sortie(sortie(2, SortieOutcome::Killed, 30.0))).unwrap();
+        assert_eq!((get().deaths, get().flight_time), (2, 90.0));
+
+        block_on(recorder.handle_sortie(sortie(3, SortieOutcome::Landed, 120.0))).unwrap();
+        assert_eq!((get().deaths, get().flight_time), (2, 210.0));
+    }
+
+    #[test]
+    fn test_cached_ucid_prefers_connected() {
+        let recorder = recorder(
+            open(),
+            &[
+                (2, "player", "old", false),
+                (3, "player", "new", true),
+                (4, "other", "other", false),
+            ],
+        );
+        assert_eq!(recorder.cached_ucid("player").as_deref(), Some("new"));
+        // disconnected players are still resolved until the next refresh
+        assert_eq!(recorder.cached_ucid("other").as_deref(), Some("other"));
+        assert_eq!(recorder.cached_ucid("unknown"), None);
+    }
+}
@@ -0,0 +1,492 @@
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use dcs_module_ipc::IPC;
+use stubs::common::v0::Coalition;
+use stubs::hook::v0::{
+    GetMissionFilenameRequest, GetMissionFilenameResponse, LoadMissionRequest, LoadMissionResponse,
+};
+use stubs::mission::v0::StreamEventsResponse;
+use stubs::net::v0::{GetPlayersRequest, GetPlayersResponse};
+use stubs::rotation::v0::{Rotation, RotationMission};
+use stubs::trigger::v0::{OutTextRequest, OutTextResponse};
+use stubs::tts::v0::tts_service_server::TtsService;
+use stubs::tts::v0::TransmitRequest;
+use time::OffsetDateTime;
+use tokio::time::sleep;
+
+use crate::config::RotationConfig;
+use crate::player_registry::SERVER_PLAYER_ID;
+use crate::rpc::Tts;
+
+const DEFAULT_WARNINGS: [u32; 3] = [600, 300, 60];
+
+/// Loads the next mission of the configured rotation once the running mission exceeded its
+/// maximum duration or a restart time is reached, and warns players beforehand.
+#[derive(Clone)]
+pub struct MissionRotation(Arc<Inner>);
+
+struct Inner {
+    /// Where changes to the rotation are persisted.
+    path: PathBuf,
+    ipc_mission: IPC<StreamEventsResponse>,
+    ipc_hook: IPC<()>,
+    tts: Arc<Tts>,
+    /// When the running mission got loaded. The server is restarted for every mission, so this
+    /// is simply the time the rotation got created.
+    loaded_at: OffsetDateTime,
+    state: Mutex<State>,
+    /// Serializes changes to the rotation, so that the persisted rotation always matches the one
+    /// in use.
+    saving: tokio::sync::Mutex<()>,
+}
+
+struct State {
+    rotation: Rotation,
+    /// The file name of the running mission, once known.
+    mission_filename: Option<String>,
+    /// The index of the running mission in the rotation.
+    current_index: Option<usize>,
+}
+
+impl MissionRotation {
+    /// Create the rotation from the one persisted at `path`, or from the config if nothing was
+    /// persisted yet.
+    pub fn new(
+        path: PathBuf,
+        config: Option<RotationConfig>,
+        ipc_mission: IPC<StreamEventsResponse>,
+        ipc_hook: IPC<()>,
+        tts: Arc<Tts>,
+    ) -> Self {
+        let rotation = match load(&path) {
+            Ok(Some(rotation)) => {
+                if config.is_some() {
+                    log::info!(
+                        "Using the mission rotation saved at {} instead of the configured one",
+                        path.display()
+                    );
+                }
+                rotation
+            }
+            Ok(None) => config.map(from_config).unwrap_or_default(),
+            Err(err) => {
+                log::error!(
+                    "Failed to read mission rotation from {}: {}",
+                    path.display(),
+                    err
+                );
+                config.map(from_config).unwrap_or_default()
+            }
+        };
+        let rotation = match validate(&rotation) {
+            Ok(()) => rotation,
+            Err(err) => {
+                log::error!("Ignoring invalid mission rotation: {}", err);
+                Rotation::default()
+            }
+        };
+
+        Self(Arc::new(Inner {
+            path,
+            ipc_mission,
+            ipc_hook,
+            tts,
+            loaded_at: OffsetDateTime::now_utc(),
+            state: Mutex::new(State {
+                rotation,
+                mission_filename: None,
+                current_index: None,
+            }),
+            saving: Default::default(),
+        }))
+    }
+
+    /// The rotation, the index of the running mission in it and when the next mission is loaded
+    /// (in unixtime).
+    pub fn get(&self) -> (Rotation, Option<u32>, Option<u64>) {
+        let state = self.0.state.lock().unwrap();
+        (
+            state.rotation.clone(),
+            state.current_index.map(|i| i as u32),
+            self.next_rotation_at(&state)
+                .map(|at| at.unix_timestamp().max(0) as u64),
+        )
+    }
+
+    /// Replace the rotation and persist it.
+    pub async fn set(&self, rotation: Rotation) -> Result<(), Error> {
+        validate(&rotation)?;
+
+        let _saving = self.0.saving.lock().await;
+        let path = self.0.path.clone();
+        let data = serde_json::to_vec_pretty(&rotation)?;
+        tokio::task::spawn_blocking(move || {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(&path, data)
+        })
+        .await??;
+
+        let mut state = self.0.state.lock().unwrap();
+        state.current_index = state
+            .mission_filename
+            .as_deref()
+            .and_then(|filename| position(&rotation, filename));
+        state.rotation = rotation;
+        Ok(())
+    }
+
+    /// Load the next mission of the rotation. Returns the path of the mission being loaded.
+    pub async fn skip_to_next(&self) -> Result<String, Error> {
+        let players: GetPlayersResponse = self
+            .0
+            .ipc_mission
+            .request("getPlayers", Some(GetPlayersRequest {}))
+            .await?;
+        let player_count = players
+            .players
+            .iter()
+            .filter(|p| p.id != SERVER_PLAYER_ID)
+            .count() as u32;
+
+        let path = {
+            let state = self.0.state.lock().unwrap();
+            next_mission(&state, player_count)
+        };
+        // without any missions in the rotation, restart times reload the running mission
+        let path = match path {
+            Some(path) => path,
+            None => self.mission_filename().await?,
+        };
+
+        log::info!("Loading next mission of the rotation: {}", path);
+        let _: LoadMissionResponse = self
+            .0
+            .ipc_hook
+            .request(
+                "loadMission",
+                Some(LoadMissionRequest {
+                    file_name: path.clone(),
+                }),
+            )
+            .await?;
+
+        Ok(path)
+    }
+
+    pub async fn run_in_background(self, mut shutdown_signal: impl Future<Output = ()> + Unpin) {
+        match self.mission_filename().await {
+            Ok(filename) => {
+                let mut state = self.0.state.lock().unwrap();
+                state.current_index = position(&state.rotation, &filename);
+                state.mission_filename = Some(filename);
+            }
+            Err(err) => log::error!("Failed to get the running mission's filename: {}", err),
+        }
+
+        let mut previous_remaining: Option<i64> = None;
+        loop {
+            let (remaining, warnings, tts_frequency) = {
+                let state = self.0.state.lock().unwrap();
+                let remaining = self
+                    .next_rotation_at(&state)
+                    .map(|at| (at - OffsetDateTime::now_utc()).whole_seconds());
+                let warnings = if state.rotation.warnings.is_empty() {
+                    DEFAULT_WARNINGS.to_vec()
+                } else {
+                    state.rotation.warnings.clone()
+                };
+                (remaining, warnings, state.rotation.tts_frequency)
+            };
+
+            let mut delay = Duration::from_secs(1);
+            match remaining {
+                Some(remaining) if remaining <= 0 => match self.skip_to_next().await {
+                    // DCS is about to unload the mission (and thus stop the server), so don't try
+                    // again unless the loading fails and the mission keeps running
+                    Ok(_) => delay = Duration::from_secs(60),
+                    Err(err) => {
+                        log::error!("Failed to load the next mission of the rotation: {}", err);
+                        delay = Duration::from_secs(60);
+                    }
+                },
+                Some(remaining) => {
+                    // only warn when crossing a warning threshold, and only once per tick if
+                    // multiple got crossed at once (e.g. due to a changed rotation)
+                    let crossed = warnings.iter().any(|w| {
+                        let w = i64::from(*w);
+                        remaining <= w && matches!(previous_remaining, Some(p) if p > w)
+                    });
+                    if crossed {
+                        self.warn(remaining, tts_frequency).await;
+                    }
+                }
+                None => {}
+            }
+            previous_remaining = remaining;
+
+            tokio::select! {
+                _ = &mut shutdown_signal => break,
+                _ = sleep(delay) => {}
+            }
+        }
+    }
+
+    /// When the next mission is loaded, which is either after the running mission's maximum
+    /// duration, or at the next restart time, whatever comes first.
+    fn next_rotation_at(&self, state: &State) -> Option<OffsetDateTime> {
+        let loaded_at = self.0.loaded_at;
+        let max_duration = state
+            .current_index
+            .and_then(|i| state.rotation.missions.get(i))
+            .and_then(|mission| mission.max_duration)
+            .map(|secs| loaded_at + time::Duration::seconds(i64::from(secs)));
+        // ignore restart times right after the mission got loaded, as that is most likely the
+        // restart time that caused the mission to be loaded in the first place
+        let restart = next_restart_time(
+            &state.rotation.restart_times,
+            loaded_at + time::Duration::minutes(1),
+        );
+
+        match (max_duration, restart) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    async fn mission_filename(&self) -> Result<String, Error> {
+        let res: GetMissionFilenameResponse = self
+            .0
+            .ipc_hook
+            .request("getMissionFilename", Some(GetMissionFilenameRequest {}))
+            .await?;
+        Ok(res.name)
+    }
+
+    /// Warn all players via text and (if configured) via TTS about the upcoming rotation.
+    async fn warn(&self, remaining: i64, tts_frequency: Option<u64>) {
+        let text = format!(
+            "The next mission will be loaded in {}.",
+            format_remaining(remaining)
+        );
+        log::info!("{}", text);
+
+        let result: Result<OutTextResponse, _> = self
+            .0
+            .ipc_mission
+            .request(
+                "outText",
+                Some(OutTextRequest {
+                    text: text.clone(),
+                    display_time: 15,
+                    clear_view: false,
+                }),
+            )
+            .await;
+        if let Err(err) = result {
+            log::error!("Failed to show mission rotation warning: {}", err);
+        }
+
+        if let Some(frequency) = tts_frequency {
+            let tts = self.0.tts.clone();
+            tokio::spawn(async move {
+                let result = tts
+                    .transmit(tonic::Request::new(TransmitRequest {
+                        ssml: text.clone(),
+                        plaintext: Some(text),
+                        frequency,
+                        srs_client_name: Some(String::from("Mission Rotation")),
+                        position: None,
+                        coalition: Coalition::All.into(),
+                        r#async: false,
+                        provider: None,
+                    }))
+                    .await;
+                if let Err(err) = result {
+                    log::error!("Failed to announce mission rotation via TTS: {}", err);
+                }
+            });
+        }
+    }
+}
+
+fn load(path: &Path) -> Result<Option<Rotation>, Error> {
+    match std::fs::read(path) {
+        Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn from_config(config: RotationConfig) -> Rotation {
+    Rotation {
+        missions: config
+            .missions
+            .into_iter()
+            .map(|mission| RotationMission {
+                path: mission.path,
+                max_duration: mission.max_duration,
+                min_players: mission.min_players,
+                max_players: mission.max_players,
+            })
+            .collect(),
+        restart_times: config.restart_times,
+        warnings: config.warnings,
+        tts_frequency: config.tts_frequency,
+    }
+}
+
+fn validate(rotation: &Rotation) -> Result<(), Error> {
+    if let Some(i) = rotation.missions.iter().position(|m| m.path.is_empty()) {
+        return Err(Error::MissingPath(i));
+    }
+    if let Some(time) = rotation
+        .restart_times
+        .iter()
+        .find(|time| parse_time(time).is_none())
+    {
+        return Err(Error::InvalidRestartTime(time.clone()));
+    }
+    Ok(())
+}
+
+/// The index of the mission with the given file name in the rotation.
+fn position(rotation: &Rotation, filename: &str) -> Option<usize> {
+    let filename = normalize_path(filename);
+    rotation
+        .missions
+        .iter()
+        .position(|mission| normalize_path(&mission.path) == filename)
+}
+
+/// DCS reports paths with backslashes, but forward slashes are accepted in the rotation, too.
+fn normalize_path(path: &str) -> String {
+    path.replace('/', "\\").to_lowercase()
+}
+
+/// The path of the mission following the running one which allows the given player count.
+/// Falls back to the directly following mission if no mission allows the player count.
+fn next_mission(state: &State, player_count: u32) -> Option<String> {
+    let missions = &state.rotation.missions;
+    if missions.is_empty() {
+        return None;
+    }
+
+    let start = state.current_index.map(|i| i + 1).unwrap_or(0);
+    let candidates = (0..missions.len()).map(|i| &missions[(start + i) % missions.len()]);
+    let mission = candidates
+        .clone()
+        .find(|mission| {
+            mission.min_players.unwrap_or(0) <= player_count
+                && mission.max_players.unwrap_or(u32::MAX) >= player_count
+        })
+        .or_else(|| candidates.clone().next())?;
+    Some(mission.path.clone())
+}
+
+/// Parse a wall-clock time formatted as `HH:MM`.
+fn parse_time(time: &str) -> Option<time::Time> {
+    let (hour, minute) = time.trim().split_once(':')?;
+    time::Time::from_hms(hour.parse().ok()?, minute.parse().ok()?, 0).ok()
+}
+
+/// The first of the given restart times (UTC) after `after`.
+fn next_restart_time(restart_times: &[String], after: OffsetDateTime) -> Option<OffsetDateTime> {
+    let after = after.to_offset(time::UtcOffset::UTC);
+    restart_times
+        .iter()
+        .filter_map(|time| parse_time(time))
+        .map(|time| {
+            let at = after.replace_time(time);
+            if at <= after {
+                at + time::Duration::days(1)
+            } else {
+                at
+            }
+        })
+        .min()
+}
+
+/// Format the remaining seconds as e.g. `5 minutes` or `30 seconds`.
+fn format_remaining(secs: i64) -> String {
+    if secs < 60 {
+        return match secs.max(1) {
+            1 => String::from("1 second"),
+            secs => format!("{secs} seconds"),
+        };
+    }
+
+    // round to full minutes, as the warnings are triggered within the second after crossing the
+    // threshold
+    match (secs + 30) / 60 {
+        1 => String::from("1 minute"),
+        minutes => format!("{minutes} minutes"),
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Ipc(#[from] dcs_module_ipc::Error),
+    #[error(transparent)]
+    Join(#[from] tokio::task::JoinError),
+    #[error("mission #{0} of the rotation is missing a path")]
+    MissingPath(usize),
+    #[error("invalid restart time `{0}` (expected `HH:MM`)")]
+    InvalidRestartTime(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{Date, Month, Time, UtcOffset};
+
+    use super::*;
+
+    fn times(times: &[&str]) -> Vec<String> {
+        times.iter().map(|time| time.to_string()).collect()
+    }
+
+    /// The given day of May 2022 and hour (UTC).
+    fn at(day: u8, hour: u8) -> OffsetDateTime {
+        Date::from_calendar_date(2022, Month::May, day)
+            .unwrap()
+            .with_time(Time::from_hms(hour, 0, 0).unwrap())
+            .assume_utc()
+    }
+
+    #[test]
+    fn test_next_restart_time() {
+        let restart_times = times(&["18:00", "06:00", "invalid"]);
+        assert_eq!(next_restart_time(&restart_times, at(1, 5)), Some(at(1, 6)));
+        assert_eq!(
+            next_restart_time(&restart_times, at(1, 12)),
+            Some(at(1, 18))
+        );
+        // restart times that already passed today are scheduled for tomorrow
+        assert_eq!(next_restart_time(&restart_times, at(1, 18)), Some(at(2, 6)));
+        // restart times are in UTC, regardless of the offset of `after`
+        let after = at(1, 20).to_offset(UtcOffset::from_hms(8, 0, 0).unwrap());
+        assert_eq!(next_restart_time(&restart_times, after), Some(at(2, 6)));
+        assert_eq!(next_restart_time(&[], at(1, 12)), None);
+    }
+
+    #[test]
+    fn test_format_remaining() {
+        assert_eq!(format_remaining(-5), "1 second");
+        assert_eq!(format_remaining(1), "1 second");
+        assert_eq!(format_remaining(30), "30 seconds");
+        assert_eq!(format_remaining(59), "59 seconds");
+        assert_eq!(format_remaining(60), "1 minute");
+        assert_eq!(format_remaining(89), "1 minute");
+        assert_eq!(format_remaining(90), "2 minutes");
+        assert_eq!(format_remaining(599), "10 minutes");
+    }
+}
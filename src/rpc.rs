@@ -6,22 +6,42 @@ use stubs::mission::v0::StreamEventsResponse;
 use tokio::sync::RwLock;
 use tonic::{Request, Status};
 
+pub use self::access::AccessRpc;
+pub use self::atis::AtisRpc;
+pub use self::chat::ChatRpc;
+pub use self::player::PlayerRpc;
+pub use self::player_stats::PlayerStatsRpc;
+pub use self::rotation::RotationRpc;
+pub use self::template::TemplateRpc;
 pub use self::tts::Tts;
+use crate::decisions::Decisions;
 use crate::shutdown::ShutdownHandle;
 use crate::stats::Stats;
 
+mod access;
+mod airbase;
+mod atis;
 mod atmosphere;
+mod chat;
 mod coalition;
 mod controller;
+mod coordinate;
 mod custom;
+mod geometry;
 mod group;
 mod hook;
+mod land;
 mod mission;
 mod net;
+mod player;
+mod player_stats;
+mod rotation;
+mod template;
 mod timer;
 mod trigger;
 mod tts;
 mod unit;
+mod warehouse;
 mod weapon;
 mod world;
 
@@ -37,12 +57,14 @@ pub struct MissionRpc {
 #[derive(Default)]
 struct Cache {
     scenario_start_time: Option<time::OffsetDateTime>,
+    theatre: Option<String>,
 }
 
 #[derive(Clone)]
 pub struct HookRpc {
     ipc: IPC<()>,
     stats: Stats,
+    decisions: Decisions,
     eval_enabled: bool,
     shutdown_signal: ShutdownHandle,
 }
@@ -84,10 +106,16 @@ impl MissionRpc {
 }
 
 impl HookRpc {
-    pub fn new(ipc: IPC<()>, stats: Stats, shutdown_signal: ShutdownHandle) -> Self {
+    pub fn new(
+        ipc: IPC<()>,
+        stats: Stats,
+        decisions: Decisions,
+        shutdown_signal: ShutdownHandle,
+    ) -> Self {
         HookRpc {
             ipc,
             stats,
+            decisions,
             eval_enabled: false,
             shutdown_signal,
         }
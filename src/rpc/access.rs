@@ -0,0 +1,166 @@
+use stubs::access;
+use stubs::access::v0::access_service_server::AccessService;
+use stubs::access::v0::{Ban, WhitelistEntry};
+use tonic::{Request, Response, Status};
+
+use crate::access::{now, Access, Error};
+
+pub struct AccessRpc {
+    access: Option<Access>,
+}
+
+impl AccessRpc {
+    pub fn new(access: Option<Access>) -> Self {
+        Self { access }
+    }
+
+    fn access(&self) -> Result<&Access, Status> {
+        self.access
+            .as_ref()
+            .ok_or_else(|| Status::unavailable("the access database could not be opened"))
+    }
+}
+
+#[tonic::async_trait]
+impl AccessService for AccessRpc {
+    async fn add_ban(
+        &self,
+        request: Request<access::v0::AddBanRequest>,
+    ) -> Result<Response<access::v0::AddBanResponse>, Status> {
+        let request = request.into_inner();
+        let created_at = now();
+        let expires_at = request
+            .duration
+            .map(|duration| {
+                created_at
+                    .checked_add(duration)
+                    .filter(|expires_at| i64::try_from(*expires_at).is_ok())
+                    .ok_or_else(|| Status::invalid_argument("duration is out of range"))
+            })
+            .transpose()?;
+        let ban = self
+            .access()?
+            .add_ban(Ban {
+                id: 0,
+                ucid: request.ucid.filter(|ucid| !ucid.is_empty()),
+                ip_address: request.ip_address.filter(|ip| !ip.is_empty()),
+                player_name: request.player_name,
+                reason: request.reason,
+                issuer: request.issuer,
+                created_at,
+                expires_at,
+            })
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(access::v0::AddBanResponse { ban: Some(ban) }))
+    }
+
+    async fn list_bans(
+        &self,
+        request: Request<access::v0::ListBansRequest>,
+    ) -> Result<Response<access::v0::ListBansResponse>, Status> {
+        let include_expired = request.into_inner().include_expired.unwrap_or(false);
+        let bans = self.access()?.bans(include_expired);
+        Ok(Response::new(access::v0::ListBansResponse { bans }))
+    }
+
+    async fn update_ban(
+        &self,
+        request: Request<access::v0::UpdateBanRequest>,
+    ) -> Result<Response<access::v0::UpdateBanResponse>, Status> {
+        let request = request.into_inner();
+        let id = request.id;
+        // SQLite only stores signed 64-bit integers
+        if matches!(request.expires_at, Some(expires_at) if i64::try_from(expires_at).is_err()) {
+            return Err(Status::invalid_argument("expires_at is out of range"));
+        }
+        match self
+            .access()?
+            .update_ban(id, request.reason, request.issuer, request.expires_at)
+            .await
+            .map_err(to_status)?
+        {
+            Some(ban) => Ok(Response::new(access::v0::UpdateBanResponse {
+                ban: Some(ban),
+            })),
+            None => Err(Status::not_found(format!("ban with id `{id}` not found"))),
+        }
+    }
+
+    async fn remove_ban(
+        &self,
+        request: Request<access::v0::RemoveBanRequest>,
+    ) -> Result<Response<access::v0::RemoveBanResponse>, Status> {
+        let id = request.into_inner().id;
+        if !self.access()?.remove_ban(id).await.map_err(to_status)? {
+            return Err(Status::not_found(format!("ban with id `{id}` not found")));
+        }
+        Ok(Response::new(access::v0::RemoveBanResponse {}))
+    }
+
+    async fn add_to_whitelist(
+        &self,
+        request: Request<access::v0::AddToWhitelistRequest>,
+    ) -> Result<Response<access::v0::AddToWhitelistResponse>, Status> {
+        let request = request.into_inner();
+        if request.ucid.is_empty() {
+            return Err(Status::invalid_argument("ucid is required"));
+        }
+        self.access()?
+            .add_to_whitelist(WhitelistEntry {
+                ucid: request.ucid,
+                note: request.note,
+                created_at: now(),
+            })
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(access::v0::AddToWhitelistResponse {}))
+    }
+
+    async fn remove_from_whitelist(
+        &self,
+        request: Request<access::v0::RemoveFromWhitelistRequest>,
+    ) -> Result<Response<access::v0::RemoveFromWhitelistResponse>, Status> {
+        let ucid = request.into_inner().ucid;
+        if !self
+            .access()?
+            .remove_from_whitelist(ucid.clone())
+            .await
+            .map_err(to_status)?
+        {
+            return Err(Status::not_found(format!(
+                "player with ucid `{ucid}` is not whitelisted"
+            )));
+        }
+        Ok(Response::new(access::v0::RemoveFromWhitelistResponse {}))
+    }
+
+    async fn get_whitelist(
+        &self,
+        _: Request<access::v0::GetWhitelistRequest>,
+    ) -> Result<Response<access::v0::GetWhitelistResponse>, Status> {
+        let (enabled, entries) = self.access()?.whitelist();
+        Ok(Response::new(access::v0::GetWhitelistResponse {
+            enabled,
+            entries,
+        }))
+    }
+
+    async fn set_whitelist_enabled(
+        &self,
+        request: Request<access::v0::SetWhitelistEnabledRequest>,
+    ) -> Result<Response<access::v0::SetWhitelistEnabledResponse>, Status> {
+        self.access()?
+            .set_whitelist_enabled(request.into_inner().enabled)
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(access::v0::SetWhitelistEnabledResponse {}))
+    }
+}
+
+fn to_status(err: Error) -> Status {
+    match err {
+        Error::MissingBanTarget => Status::invalid_argument(err.to_string()),
+        err => Status::internal(err.to_string()),
+    }
+}
@@ -0,0 +1,56 @@
+use stubs::airbase::v0::airbase_service_server::AirbaseService;
+use stubs::*;
+use tonic::{Request, Response, Status};
+
+use super::MissionRpc;
+
+#[tonic::async_trait]
+impl AirbaseService for MissionRpc {
+    async fn get_runways(
+        &self,
+        request: Request<airbase::v0::GetRunwaysRequest>,
+    ) -> Result<Response<airbase::v0::GetRunwaysResponse>, Status> {
+        let res = self.request("getRunways", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn get_parking(
+        &self,
+        request: Request<airbase::v0::GetParkingRequest>,
+    ) -> Result<Response<airbase::v0::GetParkingResponse>, Status> {
+        let res = self.request("getParking", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn set_coalition(
+        &self,
+        request: Request<airbase::v0::SetCoalitionRequest>,
+    ) -> Result<Response<airbase::v0::SetCoalitionResponse>, Status> {
+        let res = self.request("setAirbaseCoalition", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn auto_capture(
+        &self,
+        request: Request<airbase::v0::AutoCaptureRequest>,
+    ) -> Result<Response<airbase::v0::AutoCaptureResponse>, Status> {
+        let res = self.request("airbaseAutoCapture", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn set_radio_silent_mode(
+        &self,
+        request: Request<airbase::v0::SetRadioSilentModeRequest>,
+    ) -> Result<Response<airbase::v0::SetRadioSilentModeResponse>, Status> {
+        let res = self.request("setRadioSilentMode", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn get_radio_silent_mode(
+        &self,
+        request: Request<airbase::v0::GetRadioSilentModeRequest>,
+    ) -> Result<Response<airbase::v0::GetRadioSilentModeResponse>, Status> {
+        let res = self.request("getRadioSilentMode", request).await?;
+        Ok(Response::new(res))
+    }
+}
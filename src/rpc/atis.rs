@@ -0,0 +1,53 @@
+use stubs::atis;
+use stubs::atis::v0::atis_service_server::AtisService;
+use tonic::{Request, Response, Status};
+
+use crate::atis::AtisBroadcasts;
+
+pub struct AtisRpc {
+    broadcasts: AtisBroadcasts,
+}
+
+impl AtisRpc {
+    pub fn new(broadcasts: AtisBroadcasts) -> Self {
+        Self { broadcasts }
+    }
+}
+
+#[tonic::async_trait]
+impl AtisService for AtisRpc {
+    async fn start_atis(
+        &self,
+        request: Request<atis::v0::StartAtisRequest>,
+    ) -> Result<Response<atis::v0::StartAtisResponse>, Status> {
+        let atis::v0::StartAtisRequest {
+            airbase,
+            frequency,
+            coalition,
+        } = request.into_inner();
+        self.broadcasts.start(airbase, frequency, coalition).await?;
+        Ok(Response::new(atis::v0::StartAtisResponse {}))
+    }
+
+    async fn stop_atis(
+        &self,
+        request: Request<atis::v0::StopAtisRequest>,
+    ) -> Result<Response<atis::v0::StopAtisResponse>, Status> {
+        let airbase = request.into_inner().airbase;
+        if !self.broadcasts.stop(&airbase) {
+            return Err(Status::not_found(format!(
+                "no ATIS is broadcast for airbase `{airbase}`"
+            )));
+        }
+        Ok(Response::new(atis::v0::StopAtisResponse {}))
+    }
+
+    async fn list_atis(
+        &self,
+        _: Request<atis::v0::ListAtisRequest>,
+    ) -> Result<Response<atis::v0::ListAtisResponse>, Status> {
+        Ok(Response::new(atis::v0::ListAtisResponse {
+            broadcasts: self.broadcasts.list(),
+        }))
+    }
+}
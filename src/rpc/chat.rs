@@ -0,0 +1,67 @@
+use stubs::chat;
+use stubs::chat::v0::chat_service_server::ChatService;
+use tonic::{Request, Response, Status};
+
+use crate::chat::ChatCommands;
+
+pub struct ChatRpc {
+    commands: ChatCommands,
+}
+
+impl ChatRpc {
+    pub fn new(commands: ChatCommands) -> Self {
+        Self { commands }
+    }
+}
+
+#[tonic::async_trait]
+impl ChatService for ChatRpc {
+    async fn register_command(
+        &self,
+        request: Request<chat::v0::RegisterCommandRequest>,
+    ) -> Result<Response<chat::v0::RegisterCommandResponse>, Status> {
+        let command = request
+            .into_inner()
+            .command
+            .ok_or_else(|| Status::invalid_argument("command is required"))?;
+        self.commands
+            .register(command)
+            .map_err(Status::invalid_argument)?;
+        Ok(Response::new(chat::v0::RegisterCommandResponse {}))
+    }
+
+    async fn unregister_command(
+        &self,
+        request: Request<chat::v0::UnregisterCommandRequest>,
+    ) -> Result<Response<chat::v0::UnregisterCommandResponse>, Status> {
+        let name = request.into_inner().name;
+        if !self.commands.unregister(&name) {
+            return Err(Status::not_found(format!(
+                "chat command `{name}` is not registered"
+            )));
+        }
+        Ok(Response::new(chat::v0::UnregisterCommandResponse {}))
+    }
+
+    async fn list_commands(
+        &self,
+        _: Request<chat::v0::ListCommandsRequest>,
+    ) -> Result<Response<chat::v0::ListCommandsResponse>, Status> {
+        Ok(Response::new(chat::v0::ListCommandsResponse {
+            commands: self.commands.list(),
+        }))
+    }
+
+    async fn set_permission_level(
+        &self,
+        request: Request<chat::v0::SetPermissionLevelRequest>,
+    ) -> Result<Response<chat::v0::SetPermissionLevelResponse>, Status> {
+        let request = request.into_inner();
+        if request.ucid.is_empty() {
+            return Err(Status::invalid_argument("ucid is required"));
+        }
+        self.commands
+            .set_permission_level(request.ucid, request.permission_level);
+        Ok(Response::new(chat::v0::SetPermissionLevelResponse {}))
+    }
+}
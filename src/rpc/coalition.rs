@@ -1,7 +1,9 @@
+use stubs::coalition::v0::add_group_request::Template;
 use stubs::coalition::v0::coalition_service_server::CoalitionService;
 use stubs::*;
 use tonic::{Request, Response, Status};
 
+use super::group::validate_route;
 use super::MissionRpc;
 
 #[tonic::async_trait]
@@ -10,6 +12,20 @@ impl CoalitionService for MissionRpc {
         &self,
         request: Request<coalition::v0::AddGroupRequest>,
     ) -> Result<Response<coalition::v0::AddGroupResponse>, Status> {
+        match &request.get_ref().template {
+            Some(Template::PlaneTemplate(template)) => validate_route(&template.route),
+            Some(Template::HelicopterTemplate(template)) => validate_route(&template.route),
+            // ground units and ships spawn at their position, so they don't need a route
+            Some(Template::GroundTemplate(template)) if !template.route.is_empty() => {
+                validate_route(&template.route)
+            }
+            Some(Template::ShipTemplate(template)) if !template.route.is_empty() => {
+                validate_route(&template.route)
+            }
+            _ => Ok(()),
+        }
+        .map_err(|err| Status::invalid_argument(format!("route: {err}")))?;
+
         let res = self.request("addGroup", request).await?;
         Ok(Response::new(res))
     }
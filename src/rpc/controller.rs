@@ -20,4 +20,52 @@ impl ControllerService for MissionRpc {
         let res = self.request("getDetectedTargets", request).await?;
         Ok(Response::new(res))
     }
+
+    async fn set_task(
+        &self,
+        request: Request<controller::v0::SetTaskRequest>,
+    ) -> Result<Response<controller::v0::SetTaskResponse>, Status> {
+        let res = self.request("setTask", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn push_task(
+        &self,
+        request: Request<controller::v0::PushTaskRequest>,
+    ) -> Result<Response<controller::v0::PushTaskResponse>, Status> {
+        let res = self.request("pushTask", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn pop_task(
+        &self,
+        request: Request<controller::v0::PopTaskRequest>,
+    ) -> Result<Response<controller::v0::PopTaskResponse>, Status> {
+        let res = self.request("popTask", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn reset_task(
+        &self,
+        request: Request<controller::v0::ResetTaskRequest>,
+    ) -> Result<Response<controller::v0::ResetTaskResponse>, Status> {
+        let res = self.request("resetTask", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn set_option(
+        &self,
+        request: Request<controller::v0::SetOptionRequest>,
+    ) -> Result<Response<controller::v0::SetOptionResponse>, Status> {
+        let res = self.request("setOption", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn set_command(
+        &self,
+        request: Request<controller::v0::SetCommandRequest>,
+    ) -> Result<Response<controller::v0::SetCommandResponse>, Status> {
+        let res = self.request("setCommand", request).await?;
+        Ok(Response::new(res))
+    }
 }
@@ -0,0 +1,118 @@
+use stubs::common::v0::{InputPosition, Position};
+use stubs::coordinate::v0::coordinate_service_server::CoordinateService;
+use stubs::*;
+use tonic::{Request, Response, Status};
+
+use super::MissionRpc;
+use crate::coord::{self, TransverseMercator};
+
+#[tonic::async_trait]
+impl CoordinateService for MissionRpc {
+    async fn to_lat_lon(
+        &self,
+        request: Request<coordinate::v0::ToLatLonRequest>,
+    ) -> Result<Response<coordinate::v0::ToLatLonResponse>, Status> {
+        let coordinate::v0::ToLatLonRequest { u, v, alt, theatre } = request.into_inner();
+        let projection = self.get_projection(theatre).await?;
+        let (lat, lon) = projection.inverse(v, u);
+        Ok(Response::new(coordinate::v0::ToLatLonResponse {
+            position: Some(Position {
+                lat,
+                lon,
+                alt,
+                u,
+                v,
+            }),
+        }))
+    }
+
+    async fn to_dcs(
+        &self,
+        request: Request<coordinate::v0::ToDcsRequest>,
+    ) -> Result<Response<coordinate::v0::ToDcsResponse>, Status> {
+        let coordinate::v0::ToDcsRequest { position, theatre } = request.into_inner();
+        let InputPosition { lat, lon, alt } =
+            position.ok_or_else(|| Status::invalid_argument("missing position"))?;
+        let projection = self.get_projection(theatre).await?;
+        let (v, u) = projection.forward(lat, lon);
+        Ok(Response::new(coordinate::v0::ToDcsResponse {
+            position: Some(Position {
+                lat,
+                lon,
+                alt,
+                u,
+                v,
+            }),
+        }))
+    }
+
+    async fn to_mgrs(
+        &self,
+        request: Request<coordinate::v0::ToMgrsRequest>,
+    ) -> Result<Response<coordinate::v0::ToMgrsResponse>, Status> {
+        let coordinate::v0::ToMgrsRequest {
+            position,
+            precision,
+        } = request.into_inner();
+        let InputPosition { lat, lon, .. } =
+            position.ok_or_else(|| Status::invalid_argument("missing position"))?;
+        let precision = u8::try_from(precision.unwrap_or(5)).unwrap_or(u8::MAX);
+        let mgrs = coord::to_mgrs(lat, lon, precision)
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
+        Ok(Response::new(coordinate::v0::ToMgrsResponse { mgrs }))
+    }
+
+    async fn from_mgrs(
+        &self,
+        request: Request<coordinate::v0::FromMgrsRequest>,
+    ) -> Result<Response<coordinate::v0::FromMgrsResponse>, Status> {
+        let coordinate::v0::FromMgrsRequest { mgrs, theatre } = request.into_inner();
+        let (lat, lon) =
+            coord::from_mgrs(&mgrs).map_err(|err| Status::invalid_argument(err.to_string()))?;
+        let projection = self.get_projection(theatre).await?;
+        let (v, u) = projection.forward(lat, lon);
+        Ok(Response::new(coordinate::v0::FromMgrsResponse {
+            position: Some(Position {
+                lat,
+                lon,
+                alt: 0.0,
+                u,
+                v,
+            }),
+        }))
+    }
+}
+
+impl MissionRpc {
+    /// The projection of the given theatre, or of the mission's theatre if none is given.
+    pub(super) async fn get_projection(
+        &self,
+        theatre: Option<String>,
+    ) -> Result<TransverseMercator, Status> {
+        let theatre = match theatre {
+            Some(theatre) => theatre,
+            None => self.get_theatre().await?,
+        };
+        TransverseMercator::for_theatre(&theatre)
+            .ok_or_else(|| Status::failed_precondition(format!("unsupported theatre `{theatre}`")))
+    }
+
+    /// The theatre of the running mission. It is cached, as it cannot change without the
+    /// server being restarted.
+    pub(super) async fn get_theatre(&self) -> Result<String, Status> {
+        let cache = self.cache.read().await;
+        if let Some(theatre) = &cache.theatre {
+            return Ok(theatre.clone());
+        }
+        std::mem::drop(cache);
+
+        let world::v0::GetTheatreResponse { theatre } = self
+            .request("getTheatre", Request::new(world::v0::GetTheatreRequest {}))
+            .await?;
+
+        let mut cache = self.cache.write().await;
+        cache.theatre = Some(theatre.clone());
+
+        Ok(theatre)
+    }
+}
@@ -57,25 +57,38 @@ impl CustomService for MissionRpc {
         request: Request<custom::v0::GetMagneticDeclinationRequest>,
     ) -> Result<Response<custom::v0::GetMagneticDeclinationResponse>, Status> {
         let position = request.into_inner();
+        let declination = self
+            .magnetic_declination(position.lat, position.lon, position.alt)
+            .await?;
 
+        // reduce precision to two decimal places
+        let declination = ((declination * 100.0).round() / 100.0).neg();
+
+        Ok(Response::new(custom::v0::GetMagneticDeclinationResponse {
+            declination,
+        }))
+    }
+}
+
+impl MissionRpc {
+    /// The magnetic declination (positive when easterly) in degrees at the given position.
+    pub(super) async fn magnetic_declination(
+        &self,
+        lat: f64,
+        lon: f64,
+        alt: f64,
+    ) -> Result<f64, Status> {
         // As only the date is relevant, and a difference of some days don't really matter, it is
         // fine to just use the scenario's start time, especially since it is cached and thus
         // prevents unnecessary roundtrips to the MSE.
         let date = self.get_scenario_start_time().await?.date();
-        let declination = igrf::declination(position.lat, position.lon, position.alt as u32, date)
+        igrf::declination(lat, lon, alt as u32, date)
             .map(|f| f.d)
             .or_else(|err| match err {
                 igrf::Error::DateOutOfRange(f) => Ok(f.d),
                 err => Err(Status::internal(format!(
                     "failed to estimate magnetic declination: {err}"
                 ))),
-            })?;
-
-        // reduce precision to two decimal places
-        let declination = ((declination * 100.0).round() / 100.0).neg();
-
-        Ok(Response::new(custom::v0::GetMagneticDeclinationResponse {
-            declination,
-        }))
+            })
     }
 }
@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+
+use stubs::coalition::v0::coalition_service_server::CoalitionService;
+use stubs::common::v0::Vector;
+use stubs::geometry::v0::geometry_service_server::GeometryService;
+use stubs::geometry::v0::location::Location;
+use stubs::geometry::v0::Aspect;
+use stubs::unit::v0::unit_service_server::UnitService;
+use stubs::*;
+use tonic::{Request, Response, Status};
+
+use super::MissionRpc;
+use crate::brevity::{
+    format_altitude, format_bearing, format_range, METERS_PER_FOOT, METERS_PER_NAUTICAL_MILE,
+};
+use crate::coord::{self, TransverseMercator};
+
+/// Targets slower than this (in m/s) are considered to be stationary and thus don't have an
+/// aspect.
+const MIN_ASPECT_SPEED: f64 = 1.0;
+
+#[tonic::async_trait]
+impl GeometryService for MissionRpc {
+    async fn get_braa(
+        &self,
+        request: Request<geometry::v0::GetBraaRequest>,
+    ) -> Result<Response<geometry::v0::GetBraaResponse>, Status> {
+        let geometry::v0::GetBraaRequest { origin, target } = request.into_inner();
+        let origin = origin
+            .and_then(|l| l.location)
+            .ok_or_else(|| Status::invalid_argument("missing origin"))?;
+        let target = target
+            .and_then(|l| l.location)
+            .ok_or_else(|| Status::invalid_argument("missing target"))?;
+        let [origin, target] = self.resolve_locations([origin, target]).await?;
+
+        let (bearing_true, range) =
+            coord::great_circle((origin.lat, origin.lon), (target.lat, target.lon));
+        let bearing_magnetic = self.magnetic_bearing(bearing_true, &origin).await?;
+
+        let aspect_angle = match (&target.velocity, target.map) {
+            (Some(velocity), Some(target_map))
+                if velocity.x.hypot(velocity.z) >= MIN_ASPECT_SPEED =>
+            {
+                // The aspect is computed in map coordinates, so it is omitted if the origin
+                // cannot be projected onto the map of the mission's theatre.
+                let origin_map = match origin.map {
+                    Some(map) => Some(map),
+                    None => TransverseMercator::for_theatre(&self.get_theatre().await?)
+                        .map(|projection| projection.forward(origin.lat, origin.lon)),
+                };
+                origin_map.map(|origin_map| aspect_angle(origin_map, target_map, velocity))
+            }
+            _ => None,
+        };
+        let aspect = aspect_angle.map(aspect);
+
+        let mut brevity = format_call("BRAA", bearing_magnetic, range, target.alt);
+        if let Some(aspect) = aspect {
+            brevity.push_str(match aspect {
+                Aspect::Hot => ", hot",
+                Aspect::Flank => ", flank",
+                Aspect::Beam => ", beam",
+                Aspect::Drag => ", drag",
+                Aspect::Unspecified => "",
+            });
+        }
+
+        Ok(Response::new(geometry::v0::GetBraaResponse {
+            bearing_true,
+            bearing_magnetic,
+            range,
+            range_nm: range / METERS_PER_NAUTICAL_MILE,
+            altitude: target.alt,
+            altitude_ft: target.alt / METERS_PER_FOOT,
+            aspect_angle,
+            aspect: aspect.map(i32::from),
+            brevity,
+        }))
+    }
+
+    async fn get_bullseye_relative(
+        &self,
+        request: Request<geometry::v0::GetBullseyeRelativeRequest>,
+    ) -> Result<Response<geometry::v0::GetBullseyeRelativeResponse>, Status> {
+        let geometry::v0::GetBullseyeRelativeRequest { coalition, target } = request.into_inner();
+        let target = target
+            .and_then(|l| l.location)
+            .ok_or_else(|| Status::invalid_argument("missing target"))?;
+        let [target] = self.resolve_locations([target]).await?;
+
+        let bullseye = self
+            .get_bullseye(Request::new(coalition::v0::GetBullseyeRequest {
+                coalition,
+            }))
+            .await?
+            .into_inner()
+            .position
+            .ok_or_else(|| Status::internal("bullseye without position"))?;
+        let bullseye = Fix {
+            lat: bullseye.lat,
+            lon: bullseye.lon,
+            alt: bullseye.alt,
+            map: Some((bullseye.v, bullseye.u)),
+            velocity: None,
+        };
+
+        let (bearing_true, range) =
+            coord::great_circle((bullseye.lat, bullseye.lon), (target.lat, target.lon));
+        let bearing_magnetic = self.magnetic_bearing(bearing_true, &bullseye).await?;
+
+        Ok(Response::new(geometry::v0::GetBullseyeRelativeResponse {
+            bearing_true,
+            bearing_magnetic,
+            range,
+            range_nm: range / METERS_PER_NAUTICAL_MILE,
+            altitude: target.alt,
+            altitude_ft: target.alt / METERS_PER_FOOT,
+            brevity: format_call("BULLSEYE", bearing_magnetic, range, target.alt),
+        }))
+    }
+
+    async fn get_closure_rate(
+        &self,
+        request: Request<geometry::v0::GetClosureRateRequest>,
+    ) -> Result<Response<geometry::v0::GetClosureRateResponse>, Status> {
+        let geometry::v0::GetClosureRateRequest {
+            unit_name,
+            target_unit_name,
+        } = request.into_inner();
+        let [unit, target] = self
+            .resolve_locations([
+                Location::UnitName(unit_name),
+                Location::UnitName(target_unit_name),
+            ])
+            .await?;
+
+        let closure_rate = closure_rate(&unit, &target);
+
+        Ok(Response::new(geometry::v0::GetClosureRateResponse {
+            closure_rate,
+            closure_rate_kts: closure_rate * 3600.0 / METERS_PER_NAUTICAL_MILE,
+        }))
+    }
+}
+
+/// A [Location] resolved to a position.
+struct Fix {
+    lat: f64,
+    lon: f64,
+    alt: f64,
+    /// `(x, z)` in DCS' map coordinates, known only for units.
+    map: Option<(f64, f64)>,
+    /// The velocity in DCS' map coordinates, known only for units.
+    velocity: Option<Vector>,
+}
+
+impl MissionRpc {
+    /// Resolve the given locations, retrieving the positions of all units with a single request.
+    async fn resolve_locations<const N: usize>(
+        &self,
+        locations: [Location; N],
+    ) -> Result<[Fix; N], Status> {
+        let names = locations
+            .iter()
+            .filter_map(|location| match location {
+                Location::UnitName(name) => Some(name.clone()),
+                Location::Position(_) => None,
+            })
+            .collect::<Vec<_>>();
+        let transforms = if names.is_empty() {
+            HashMap::new()
+        } else {
+            self.get_transforms(Request::new(unit::v0::GetTransformsRequest {
+                names,
+                include_vitals: None,
+            }))
+            .await?
+            .into_inner()
+            .transforms
+        };
+
+        let mut fixes = Vec::with_capacity(N);
+        for location in locations {
+            fixes.push(match location {
+                Location::Position(position) => Fix {
+                    lat: position.lat,
+                    lon: position.lon,
+                    alt: position.alt,
+                    map: None,
+                    velocity: None,
+                },
+                Location::UnitName(name) => {
+                    let transform = transforms
+                        .get(&name)
+                        .cloned()
+                        .ok_or_else(|| Status::not_found(format!("unit `{name}` not found")))?;
+                    let position = transform.position.unwrap_or_default();
+                    Fix {
+                        lat: position.lat,
+                        lon: position.lon,
+                        alt: position.alt,
+                        map: Some((position.v, position.u)),
+                        velocity: transform.velocity.and_then(|v| v.velocity),
+                    }
+                }
+            });
+        }
+
+        fixes
+            .try_into()
+            .map_err(|_| Status::internal("failed to resolve locations"))
+    }
+
+    /// Convert the true bearing measured at the given position to a magnetic bearing.
+    async fn magnetic_bearing(&self, bearing_true: f64, at: &Fix) -> Result<f64, Status> {
+        let declination = self.magnetic_declination(at.lat, at.lon, at.alt).await?;
+        Ok((bearing_true - declination).rem_euclid(360.0))
+    }
+}
+
+/// The angle (in degrees, 0 to 180) between the target's track and the line of sight from the
+/// target to the origin, both given as `(x, z)` in DCS' map coordinates.
+fn aspect_angle(origin: (f64, f64), target: (f64, f64), velocity: &Vector) -> f64 {
+    let track = velocity.z.atan2(velocity.x).to_degrees();
+    let line_of_sight = (origin.1 - target.1)
+        .atan2(origin.0 - target.0)
+        .to_degrees();
+    let angle = (track - line_of_sight).rem_euclid(360.0);
+    if angle > 180.0 {
+        360.0 - angle
+    } else {
+        angle
+    }
+}
+
+fn aspect(aspect_angle: f64) -> Aspect {
+    match aspect_angle {
+        a if a <= 30.0 => Aspect::Hot,
+        a if a <= 70.0 => Aspect::Flank,
+        a if a <= 110.0 => Aspect::Beam,
+        _ => Aspect::Drag,
+    }
+}
+
+/// The rate (in m/s) at which the distance between both units decreases (negative if it
+/// increases).
+fn closure_rate(unit: &Fix, target: &Fix) -> f64 {
+    // Both are units, so both have map coordinates and a velocity.
+    let (unit_v, unit_u) = unit.map.unwrap_or_default();
+    let (target_v, target_u) = target.map.unwrap_or_default();
+    let unit_velocity = unit.velocity.clone().unwrap_or_default();
+    let target_velocity = target.velocity.clone().unwrap_or_default();
+
+    // the line of sight and relative velocity in DCS' coordinate system (x north, y up, z east)
+    let line_of_sight = (target_v - unit_v, target.alt - unit.alt, target_u - unit_u);
+    let relative_velocity = (
+        target_velocity.x - unit_velocity.x,
+        target_velocity.y - unit_velocity.y,
+        target_velocity.z - unit_velocity.z,
+    );
+    let distance =
+        (line_of_sight.0.powi(2) + line_of_sight.1.powi(2) + line_of_sight.2.powi(2)).sqrt();
+    if distance > 0.0 {
+        -(line_of_sight.0 * relative_velocity.0
+            + line_of_sight.1 * relative_velocity.1
+            + line_of_sight.2 * relative_velocity.2)
+            / distance
+    } else {
+        0.0
+    }
+}
+
+/// Format a call like `BRAA 090/25, 20 thousand`.
+fn format_call(kind: &str, bearing: f64, range: f64, alt: f64) -> String {
+    format!(
+        "{kind} {}/{}, {}",
+        format_bearing(bearing),
+        format_range(range),
+        format_altitude(alt)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector(x: f64, z: f64) -> Vector {
+        Vector { x, y: 0.0, z }
+    }
+
+    fn unit(x: f64, z: f64, alt: f64, velocity: Vector) -> Fix {
+        Fix {
+            lat: 0.0,
+            lon: 0.0,
+            alt,
+            map: Some((x, z)),
+            velocity: Some(velocity),
+        }
+    }
+
+    #[test]
+    fn test_aspect_angle() {
+        // target 10km north of the origin
+        let origin = (0.0, 0.0);
+        let target = (10_000.0, 0.0);
+        // flying south, towards the origin
+        assert_eq!(aspect_angle(origin, target, &vector(-200.0, 0.0)), 0.0);
+        // flying north, away from the origin
+        assert_eq!(aspect_angle(origin, target, &vector(200.0, 0.0)), 180.0);
+        // flying east or west
+        assert_eq!(aspect_angle(origin, target, &vector(0.0, 200.0)), 90.0);
+        assert_eq!(aspect_angle(origin, target, &vector(0.0, -200.0)), 90.0);
+        // flying south-west
+        let angle = aspect_angle(origin, target, &vector(-200.0, -200.0));
+        assert!((angle - 45.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_aspect() {
+        assert_eq!(aspect(0.0), Aspect::Hot);
+        assert_eq!(aspect(30.0), Aspect::Hot);
+        assert_eq!(aspect(45.0), Aspect::Flank);
+        assert_eq!(aspect(90.0), Aspect::Beam);
+        assert_eq!(aspect(110.0), Aspect::Beam);
+        assert_eq!(aspect(150.0), Aspect::Drag);
+    }
+
+    #[test]
+    fn test_closure_rate() {
+        // head-on, both at 200 m/s
+        let a = unit(0.0, 0.0, 5000.0, vector(200.0, 0.0));
+        let b = unit(20_000.0, 0.0, 5000.0, vector(-200.0, 0.0));
+        assert_eq!(closure_rate(&a, &b), 400.0);
+        assert_eq!(closure_rate(&b, &a), 400.0);
+
+        // tail chase, opening
+        let b = unit(20_000.0, 0.0, 5000.0, vector(250.0, 0.0));
+        assert_eq!(closure_rate(&a, &b), -50.0);
+
+        // flying side by side
+        let b = unit(0.0, 1_000.0, 5000.0, vector(200.0, 0.0));
+        assert_eq!(closure_rate(&a, &b), 0.0);
+
+        // same position
+        assert_eq!(closure_rate(&a, &a), 0.0);
+    }
+
+    #[test]
+    fn test_format_call() {
+        assert_eq!(
+            format_call("BRAA", 89.6, 25.0 * METERS_PER_NAUTICAL_MILE, 6096.0),
+            "BRAA 090/25, 20 thousand"
+        );
+        assert_eq!(
+            format_call("BULLSEYE", 359.7, 4_000.0, 150.0),
+            "BULLSEYE 360/2, 5 hundred"
+        );
+    }
+}
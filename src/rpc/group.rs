@@ -1,3 +1,4 @@
+use stubs::common::v0::{AltitudeType, Waypoint, WaypointAction};
 use stubs::group::v0::group_service_server::GroupService;
 use stubs::*;
 use tonic::{Request, Response, Status};
@@ -29,4 +30,178 @@ impl GroupService for MissionRpc {
         let res = self.request("groupDestroy", request).await?;
         Ok(Response::new(res))
     }
+
+    async fn set_route(
+        &self,
+        request: Request<group::v0::SetRouteRequest>,
+    ) -> Result<Response<group::v0::SetRouteResponse>, Status> {
+        validate_route(&request.get_ref().waypoints).map_err(Status::invalid_argument)?;
+        let res = self.request("setRoute", request).await?;
+        Ok(Response::new(res))
+    }
+}
+
+pub(super) fn validate_route(waypoints: &[Waypoint]) -> Result<(), String> {
+    if waypoints.is_empty() {
+        return Err("route requires at least one waypoint".to_string());
+    }
+
+    let mut previous_eta = None;
+    for (i, waypoint) in waypoints.iter().enumerate() {
+        validate_waypoint(i, waypoint, previous_eta)
+            .map_err(|err| format!("waypoints[{i}]: {err}"))?;
+        previous_eta = waypoint.eta.or(previous_eta);
+    }
+
+    Ok(())
+}
+
+fn validate_waypoint(
+    i: usize,
+    waypoint: &Waypoint,
+    previous_eta: Option<f64>,
+) -> Result<(), String> {
+    let position = waypoint
+        .position
+        .as_ref()
+        .ok_or_else(|| "position is required".to_string())?;
+    if !(-90.0..=90.0).contains(&position.lat) || !(-180.0..=180.0).contains(&position.lon) {
+        return Err(format!(
+            "position {}, {} is out of range",
+            position.lat, position.lon
+        ));
+    }
+    if !position.alt.is_finite() {
+        return Err("altitude must be a finite number".to_string());
+    }
+    if AltitudeType::from_i32(waypoint.altitude_type).is_none() {
+        return Err(format!("unknown altitude type {}", waypoint.altitude_type));
+    }
+    if !waypoint.speed.is_finite() || waypoint.speed < 0.0 {
+        return Err(format!(
+            "speed must be a non-negative number, got {}",
+            waypoint.speed
+        ));
+    }
+
+    if let Some(eta) = waypoint.eta {
+        if !eta.is_finite() || eta < 0.0 {
+            return Err(format!("eta must be a non-negative number, got {eta}"));
+        }
+        if matches!(previous_eta, Some(previous) if eta < previous) {
+            return Err("eta must not be before the eta of a previous waypoint".to_string());
+        }
+    }
+
+    let action = WaypointAction::from_i32(waypoint.action)
+        .ok_or_else(|| format!("unknown action {}", waypoint.action))?;
+    match action {
+        WaypointAction::TakeoffRunway
+        | WaypointAction::TakeoffParking
+        | WaypointAction::TakeoffParkingHot
+        | WaypointAction::TakeoffGround
+        | WaypointAction::TakeoffGroundHot
+            if i > 0 =>
+        {
+            return Err("only the first waypoint can be a takeoff".to_string());
+        }
+        WaypointAction::TakeoffRunway
+        | WaypointAction::TakeoffParking
+        | WaypointAction::TakeoffParkingHot
+            if waypoint.airdrome_id.is_none() && waypoint.helipad_id.is_none() =>
+        {
+            return Err("takeoff requires either an airdrome_id or a helipad_id".to_string());
+        }
+        _ => {}
+    }
+
+    if let Some(j) = waypoint.tasks.iter().position(|task| task.task.is_none()) {
+        return Err(format!("tasks[{j}]: task is required"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use stubs::common::v0::{InputPosition, Task};
+
+    use super::*;
+
+    fn waypoint(action: WaypointAction, eta: Option<f64>) -> Waypoint {
+        Waypoint {
+            position: Some(InputPosition {
+                lat: 42.0,
+                lon: 41.0,
+                alt: 3000.0,
+            }),
+            speed: 200.0,
+            action: action.into(),
+            eta,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_route() {
+        let mut takeoff = waypoint(WaypointAction::TakeoffParking, None);
+        takeoff.airdrome_id = Some(1);
+        assert_eq!(
+            validate_route(&[
+                takeoff,
+                waypoint(WaypointAction::TurningPoint, Some(600.0)),
+                waypoint(WaypointAction::FlyOverPoint, None),
+                waypoint(WaypointAction::Landing, Some(600.0)),
+            ]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_validate_route_errors() {
+        let err = |waypoints: &[Waypoint]| validate_route(waypoints).unwrap_err();
+
+        assert_eq!(err(&[]), "route requires at least one waypoint");
+
+        let mut wp = waypoint(WaypointAction::TurningPoint, None);
+        wp.position = None;
+        assert_eq!(err(&[wp]), "waypoints[0]: position is required");
+
+        let mut wp = waypoint(WaypointAction::TurningPoint, None);
+        wp.position.as_mut().unwrap().lat = 91.0;
+        assert_eq!(err(&[wp]), "waypoints[0]: position 91, 41 is out of range");
+
+        let mut wp = waypoint(WaypointAction::TurningPoint, None);
+        wp.speed = -1.0;
+        assert_eq!(
+            err(&[wp]),
+            "waypoints[0]: speed must be a non-negative number, got -1"
+        );
+
+        assert_eq!(
+            err(&[
+                waypoint(WaypointAction::TurningPoint, Some(600.0)),
+                waypoint(WaypointAction::TurningPoint, None),
+                waypoint(WaypointAction::TurningPoint, Some(300.0)),
+            ]),
+            "waypoints[2]: eta must not be before the eta of a previous waypoint"
+        );
+
+        assert_eq!(
+            err(&[waypoint(WaypointAction::TakeoffRunway, None)]),
+            "waypoints[0]: takeoff requires either an airdrome_id or a helipad_id"
+        );
+
+        assert_eq!(
+            err(&[
+                waypoint(WaypointAction::TurningPoint, None),
+                waypoint(WaypointAction::TakeoffGround, None),
+            ]),
+            "waypoints[1]: only the first waypoint can be a takeoff"
+        );
+
+        let mut wp = waypoint(WaypointAction::TurningPoint, None);
+        wp.tasks.push(Task::default());
+        assert_eq!(err(&[wp]), "waypoints[0]: tasks[0]: task is required");
+    }
 }
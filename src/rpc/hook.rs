@@ -1,11 +1,25 @@
+use std::pin::Pin;
+
+use futures_util::{Stream, StreamExt};
 use stubs::hook::v0::hook_service_server::HookService;
 use stubs::*;
-use tonic::{Request, Response, Status};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tonic::{Request, Response, Status, Streaming};
 
 use super::HookRpc;
+use crate::shutdown::AbortableStream;
 
 #[tonic::async_trait]
 impl HookService for HookRpc {
+    type RegisterDecisionHandlerStream = Pin<
+        Box<
+            dyn Stream<Item = Result<hook::v0::RegisterDecisionHandlerResponse, tonic::Status>>
+                + Send
+                + Sync
+                + 'static,
+        >,
+    >;
+
     async fn get_mission_name(
         &self,
         request: Request<hook::v0::GetMissionNameRequest>,
@@ -161,4 +175,36 @@ impl HookService for HookRpc {
         let res = self.request("getBallisticsCount", request).await?;
         Ok(Response::new(res))
     }
+
+    async fn register_decision_handler(
+        &self,
+        request: Request<Streaming<hook::v0::RegisterDecisionHandlerRequest>>,
+    ) -> Result<Response<Self::RegisterDecisionHandlerStream>, Status> {
+        let (handler_id, rx) = self
+            .decisions
+            .register()
+            .ok_or_else(|| Status::already_exists("a decision handler is already registered"))?;
+
+        let mut answers = request.into_inner();
+        let decisions = self.decisions.clone();
+        tokio::spawn(async move {
+            while let Some(answer) = answers.next().await {
+                match answer {
+                    Ok(answer) => decisions.answer(answer),
+                    Err(err) => {
+                        log::debug!("Decision handler stream failed: {}", err);
+                        break;
+                    }
+                }
+            }
+            // the client closed its side of the stream (or disconnected)
+            decisions.unregister(handler_id);
+        });
+
+        let stream = AbortableStream::new(
+            self.shutdown_signal.signal(),
+            UnboundedReceiverStream::new(rx).map(Ok),
+        );
+        Ok(Response::new(Box::pin(stream)))
+    }
 }
@@ -0,0 +1,88 @@
+use stubs::land::v0::land_service_server::LandService;
+use stubs::*;
+use tonic::{Request, Response, Status};
+
+use super::MissionRpc;
+
+#[tonic::async_trait]
+impl LandService for MissionRpc {
+    async fn get_height(
+        &self,
+        request: Request<land::v0::GetHeightRequest>,
+    ) -> Result<Response<land::v0::GetHeightResponse>, Status> {
+        let res = self.request("getHeight", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn get_height_batch(
+        &self,
+        request: Request<land::v0::GetHeightBatchRequest>,
+    ) -> Result<Response<land::v0::GetHeightBatchResponse>, Status> {
+        let res = self.request("getHeightBatch", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn get_surface_type(
+        &self,
+        request: Request<land::v0::GetSurfaceTypeRequest>,
+    ) -> Result<Response<land::v0::GetSurfaceTypeResponse>, Status> {
+        let res = self.request("getSurfaceType", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn get_surface_type_batch(
+        &self,
+        request: Request<land::v0::GetSurfaceTypeBatchRequest>,
+    ) -> Result<Response<land::v0::GetSurfaceTypeBatchResponse>, Status> {
+        let res = self.request("getSurfaceTypeBatch", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn is_visible(
+        &self,
+        request: Request<land::v0::IsVisibleRequest>,
+    ) -> Result<Response<land::v0::IsVisibleResponse>, Status> {
+        let res = self.request("isVisible", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn is_visible_batch(
+        &self,
+        request: Request<land::v0::IsVisibleBatchRequest>,
+    ) -> Result<Response<land::v0::IsVisibleBatchResponse>, Status> {
+        let res = self.request("isVisibleBatch", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn get_ip(
+        &self,
+        request: Request<land::v0::GetIpRequest>,
+    ) -> Result<Response<land::v0::GetIpResponse>, Status> {
+        let res = self.request("getIP", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn get_ip_batch(
+        &self,
+        request: Request<land::v0::GetIpBatchRequest>,
+    ) -> Result<Response<land::v0::GetIpBatchResponse>, Status> {
+        let res = self.request("getIPBatch", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn profile_between(
+        &self,
+        request: Request<land::v0::ProfileBetweenRequest>,
+    ) -> Result<Response<land::v0::ProfileBetweenResponse>, Status> {
+        let res = self.request("profileBetween", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn profile_between_batch(
+        &self,
+        request: Request<land::v0::ProfileBetweenBatchRequest>,
+    ) -> Result<Response<land::v0::ProfileBetweenBatchResponse>, Status> {
+        let res = self.request("profileBetweenBatch", request).await?;
+        Ok(Response::new(res))
+    }
+}
@@ -0,0 +1,77 @@
+use stubs::player;
+use stubs::player::v0::player_service_server::PlayerService;
+use tonic::{Request, Response, Status};
+
+use crate::player_registry::PlayerRegistry;
+
+pub struct PlayerRpc {
+    registry: Option<PlayerRegistry>,
+}
+
+impl PlayerRpc {
+    pub fn new(registry: Option<PlayerRegistry>) -> Self {
+        Self { registry }
+    }
+
+    fn registry(&self) -> Result<&PlayerRegistry, Status> {
+        self.registry
+            .as_ref()
+            .ok_or_else(|| Status::unavailable("the player registry database could not be opened"))
+    }
+}
+
+#[tonic::async_trait]
+impl PlayerService for PlayerRpc {
+    async fn get_player_history(
+        &self,
+        request: Request<player::v0::GetPlayerHistoryRequest>,
+    ) -> Result<Response<player::v0::GetPlayerHistoryResponse>, Status> {
+        let request = request.into_inner();
+        let ucid = request.ucid;
+        match self
+            .registry()?
+            .history(ucid.clone(), request.limit.unwrap_or(50))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+        {
+            Some((player, sessions, ip_addresses)) => {
+                Ok(Response::new(player::v0::GetPlayerHistoryResponse {
+                    player: Some(player),
+                    sessions,
+                    ip_addresses,
+                }))
+            }
+            None => Err(Status::not_found(format!(
+                "player with ucid `{ucid}` not found"
+            ))),
+        }
+    }
+
+    async fn search_players(
+        &self,
+        request: Request<player::v0::SearchPlayersRequest>,
+    ) -> Result<Response<player::v0::SearchPlayersResponse>, Status> {
+        let request = request.into_inner();
+        let players = self
+            .registry()?
+            .search(request.name, request.limit.unwrap_or(50))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        Ok(Response::new(player::v0::SearchPlayersResponse { players }))
+    }
+
+    async fn get_connection_log(
+        &self,
+        request: Request<player::v0::GetConnectionLogRequest>,
+    ) -> Result<Response<player::v0::GetConnectionLogResponse>, Status> {
+        let request = request.into_inner();
+        let sessions = self
+            .registry()?
+            .connection_log(request.since, request.limit.unwrap_or(100))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        Ok(Response::new(player::v0::GetConnectionLogResponse {
+            sessions,
+        }))
+    }
+}
@@ -0,0 +1,144 @@
+use std::pin::Pin;
+
+use futures_util::{Stream, StreamExt};
+use stubs::stats;
+use stubs::stats::v0::stats_service_server::StatsService;
+use stubs::stats::v0::{LeaderboardMetric, SortieOutcome, StatsPeriod};
+use tokio_stream::wrappers::BroadcastStream;
+use tonic::{Request, Response, Status};
+
+use crate::player_stats::{self, PlayerStats};
+use crate::shutdown::{AbortableStream, ShutdownHandle};
+use crate::sorties::Sorties;
+
+pub struct PlayerStatsRpc {
+    sorties: Sorties,
+    player_stats: Option<PlayerStats>,
+    shutdown_signal: ShutdownHandle,
+}
+
+impl PlayerStatsRpc {
+    pub fn new(
+        sorties: Sorties,
+        player_stats: Option<PlayerStats>,
+        shutdown_signal: ShutdownHandle,
+    ) -> Self {
+        Self {
+            sorties,
+            player_stats,
+            shutdown_signal,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl StatsService for PlayerStatsRpc {
+    type StreamSortiesStream = Pin<
+        Box<
+            dyn Stream<Item = Result<stats::v0::StreamSortiesResponse, tonic::Status>>
+                + Send
+                + Sync
+                + 'static,
+        >,
+    >;
+
+    async fn list_sorties(
+        &self,
+        request: Request<stats::v0::ListSortiesRequest>,
+    ) -> Result<Response<stats::v0::ListSortiesResponse>, Status> {
+        let request = request.into_inner();
+        let sorties = self.sorties.list(request.player_name.as_deref()).await;
+        Ok(Response::new(stats::v0::ListSortiesResponse { sorties }))
+    }
+
+    async fn get_sortie(
+        &self,
+        request: Request<stats::v0::GetSortieRequest>,
+    ) -> Result<Response<stats::v0::GetSortieResponse>, Status> {
+        let id = request.into_inner().id;
+        match self.sorties.get(id).await {
+            Some(sortie) => Ok(Response::new(stats::v0::GetSortieResponse {
+                sortie: Some(sortie),
+            })),
+            None => Err(Status::not_found(format!(
+                "sortie with id `{id}` not found"
+            ))),
+        }
+    }
+
+    async fn stream_sorties(
+        &self,
+        request: Request<stats::v0::StreamSortiesRequest>,
+    ) -> Result<Response<Self::StreamSortiesStream>, Status> {
+        let completed_only = request.into_inner().completed_only.unwrap_or(false);
+        let updates = BroadcastStream::new(self.sorties.subscribe()).filter_map(move |sortie| {
+            // Updates missed due to a slow receiver are skipped. As every update contains the
+            // whole sortie, the next update of the same sortie catches up on them.
+            let sortie = sortie
+                .ok()
+                .filter(|s| !completed_only || s.outcome() != SortieOutcome::InProgress)
+                .map(|sortie| {
+                    Ok(stats::v0::StreamSortiesResponse {
+                        sortie: Some(sortie),
+                    })
+                });
+            std::future::ready(sortie)
+        });
+
+        let stream = AbortableStream::new(self.shutdown_signal.signal(), updates);
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn get_player_stats(
+        &self,
+        request: Request<stats::v0::GetPlayerStatsRequest>,
+    ) -> Result<Response<stats::v0::GetPlayerStatsResponse>, Status> {
+        let request = request.into_inner();
+        let period = StatsPeriod::from_i32(request.period).unwrap_or(StatsPeriod::Unspecified);
+        let ucid = request.ucid;
+        match self
+            .player_stats()?
+            .get(ucid.clone(), period)
+            .await
+            .map_err(to_status)?
+        {
+            Some(stats) => Ok(Response::new(stats::v0::GetPlayerStatsResponse {
+                stats: Some(stats),
+            })),
+            None => Err(Status::not_found(format!(
+                "player with ucid `{ucid}` not found"
+            ))),
+        }
+    }
+
+    async fn get_leaderboard(
+        &self,
+        request: Request<stats::v0::GetLeaderboardRequest>,
+    ) -> Result<Response<stats::v0::GetLeaderboardResponse>, Status> {
+        let request = request.into_inner();
+        let metric =
+            LeaderboardMetric::from_i32(request.metric).unwrap_or(LeaderboardMetric::Unspecified);
+        let period = StatsPeriod::from_i32(request.period).unwrap_or(StatsPeriod::Unspecified);
+        let entries = self
+            .player_stats()?
+            .leaderboard(metric, period, request.limit.unwrap_or(10))
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(stats::v0::GetLeaderboardResponse { entries }))
+    }
+}
+
+impl PlayerStatsRpc {
+    fn player_stats(&self) -> Result<&PlayerStats, Status> {
+        self.player_stats
+            .as_ref()
+            .ok_or_else(|| Status::unavailable("the player stats database could not be opened"))
+    }
+}
+
+fn to_status(err: player_stats::Error) -> Status {
+    match err {
+        player_stats::Error::UnspecifiedMetric => Status::invalid_argument(err.to_string()),
+        err => Status::internal(err.to_string()),
+    }
+}
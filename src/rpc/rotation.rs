@@ -0,0 +1,59 @@
+use stubs::rotation;
+use stubs::rotation::v0::rotation_service_server::RotationService;
+use tonic::{Request, Response, Status};
+
+use crate::rotation::{Error, MissionRotation};
+
+pub struct RotationRpc {
+    rotation: MissionRotation,
+}
+
+impl RotationRpc {
+    pub fn new(rotation: MissionRotation) -> Self {
+        Self { rotation }
+    }
+}
+
+#[tonic::async_trait]
+impl RotationService for RotationRpc {
+    async fn get_rotation(
+        &self,
+        _: Request<rotation::v0::GetRotationRequest>,
+    ) -> Result<Response<rotation::v0::GetRotationResponse>, Status> {
+        let (rotation, current_index, next_rotation_at) = self.rotation.get();
+        Ok(Response::new(rotation::v0::GetRotationResponse {
+            rotation: Some(rotation),
+            current_index,
+            next_rotation_at,
+        }))
+    }
+
+    async fn set_rotation(
+        &self,
+        request: Request<rotation::v0::SetRotationRequest>,
+    ) -> Result<Response<rotation::v0::SetRotationResponse>, Status> {
+        let rotation = request
+            .into_inner()
+            .rotation
+            .ok_or_else(|| Status::invalid_argument("rotation is required"))?;
+        self.rotation.set(rotation).await.map_err(to_status)?;
+        Ok(Response::new(rotation::v0::SetRotationResponse {}))
+    }
+
+    async fn skip_to_next(
+        &self,
+        _: Request<rotation::v0::SkipToNextRequest>,
+    ) -> Result<Response<rotation::v0::SkipToNextResponse>, Status> {
+        let path = self.rotation.skip_to_next().await.map_err(to_status)?;
+        Ok(Response::new(rotation::v0::SkipToNextResponse { path }))
+    }
+}
+
+fn to_status(err: Error) -> Status {
+    match err {
+        Error::MissingPath(_) | Error::InvalidRestartTime(_) => {
+            Status::invalid_argument(err.to_string())
+        }
+        err => Status::internal(err.to_string()),
+    }
+}
@@ -0,0 +1,245 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use stubs::coalition::v0::add_group_request::Template;
+use stubs::coalition::v0::AddGroupRequest;
+use stubs::template;
+use stubs::template::v0::spawn_from_template_request::Placement;
+use stubs::template::v0::template_service_server::TemplateService;
+use tonic::{Request, Response, Status};
+
+use super::MissionRpc;
+use crate::templates::{self, GroupTemplates};
+
+pub struct TemplateRpc {
+    mission: MissionRpc,
+    templates: GroupTemplates,
+}
+
+impl TemplateRpc {
+    pub fn new(mission: MissionRpc, templates: GroupTemplates) -> Self {
+        Self { mission, templates }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpawnGroupTemplateRequest {
+    group: AddGroupRequest,
+    placement: Option<Placement>,
+}
+
+#[tonic::async_trait]
+impl TemplateService for TemplateRpc {
+    async fn capture_group_template(
+        &self,
+        request: Request<template::v0::CaptureGroupTemplateRequest>,
+    ) -> Result<Response<template::v0::CaptureGroupTemplateResponse>, Status> {
+        let template::v0::CaptureGroupTemplateRequest {
+            group_name,
+            template_name,
+        } = request.into_inner();
+        let name = template_name.unwrap_or_else(|| group_name.clone());
+        if name.is_empty() {
+            return Err(Status::invalid_argument("template name must not be empty"));
+        }
+
+        let group: AddGroupRequest = self
+            .mission
+            .request(
+                "captureGroupTemplate",
+                Request::new(template::v0::CaptureGroupTemplateRequest {
+                    group_name,
+                    template_name: None,
+                }),
+            )
+            .await?;
+
+        let template = template::v0::GroupTemplate {
+            name,
+            captured_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            group: Some(group),
+        };
+        self.templates
+            .insert(template.clone())
+            .await
+            .map_err(to_status)?;
+
+        Ok(Response::new(template::v0::CaptureGroupTemplateResponse {
+            template: Some(template),
+        }))
+    }
+
+    async fn spawn_from_template(
+        &self,
+        request: Request<template::v0::SpawnFromTemplateRequest>,
+    ) -> Result<Response<template::v0::SpawnFromTemplateResponse>, Status> {
+        let request = request.into_inner();
+        if request.new_name.is_empty() {
+            return Err(Status::invalid_argument("new name must not be empty"));
+        }
+
+        let template = self
+            .templates
+            .get(&request.template_name)
+            .await
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "template `{}` does not exist",
+                    request.template_name
+                ))
+            })?;
+        let mut group = template
+            .group
+            .ok_or_else(|| Status::internal("template is missing its group"))?;
+        rename(&mut group, &request.new_name);
+
+        let res = self
+            .mission
+            .request(
+                "spawnGroupTemplate",
+                Request::new(SpawnGroupTemplateRequest {
+                    group,
+                    placement: request.placement,
+                }),
+            )
+            .await?;
+        Ok(Response::new(res))
+    }
+
+    async fn list_group_templates(
+        &self,
+        _: Request<template::v0::ListGroupTemplatesRequest>,
+    ) -> Result<Response<template::v0::ListGroupTemplatesResponse>, Status> {
+        Ok(Response::new(template::v0::ListGroupTemplatesResponse {
+            templates: self.templates.list().await,
+        }))
+    }
+
+    async fn delete_group_template(
+        &self,
+        request: Request<template::v0::DeleteGroupTemplateRequest>,
+    ) -> Result<Response<template::v0::DeleteGroupTemplateResponse>, Status> {
+        let name = request.into_inner().name;
+        let existed = self.templates.remove(&name).await.map_err(to_status)?;
+        if !existed {
+            return Err(Status::not_found(format!(
+                "template `{name}` does not exist"
+            )));
+        }
+        Ok(Response::new(template::v0::DeleteGroupTemplateResponse {}))
+    }
+}
+
+fn to_status(err: templates::Error) -> Status {
+    match err {
+        templates::Error::NotLoaded => Status::failed_precondition(err.to_string()),
+        err => Status::internal(err.to_string()),
+    }
+}
+
+/// Rename the group and its units (to `<name>-<n>`), as names have to be unique.
+fn rename(group: &mut AddGroupRequest, name: &str) {
+    let (group_name, unit_names): (_, Vec<&mut String>) = match &mut group.template {
+        Some(Template::GroundTemplate(t)) => (
+            &mut t.name,
+            t.units.iter_mut().map(|u| &mut u.name).collect(),
+        ),
+        Some(Template::ShipTemplate(t)) => (
+            &mut t.name,
+            t.units.iter_mut().map(|u| &mut u.name).collect(),
+        ),
+        Some(Template::HelicopterTemplate(t)) => (
+            &mut t.name,
+            t.units.iter_mut().map(|u| &mut u.name).collect(),
+        ),
+        Some(Template::PlaneTemplate(t)) => (
+            &mut t.name,
+            t.units.iter_mut().map(|u| &mut u.name).collect(),
+        ),
+        None => return,
+    };
+    *group_name = name.to_string();
+    for (i, unit_name) in unit_names.into_iter().enumerate() {
+        *unit_name = format!("{}-{}", name, i + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stubs::coalition::v0::add_group_request::{
+        GroundGroupTemplate, GroundUnitTemplate, PlaneGroupTemplate, PlaneUnitTemplate,
+    };
+
+    use super::*;
+
+    fn unit_names<'a>(names: impl Iterator<Item = &'a String>) -> Vec<&'a str> {
+        names.map(|name| name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_rename_ground_group() {
+        let mut group = AddGroupRequest {
+            template: Some(Template::GroundTemplate(GroundGroupTemplate {
+                name: String::from("Armor"),
+                units: vec![
+                    GroundUnitTemplate {
+                        name: String::from("Armor-1"),
+                        ..Default::default()
+                    },
+                    GroundUnitTemplate {
+                        name: String::from("Tank"),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        rename(&mut group, "Copy");
+
+        match group.template {
+            Some(Template::GroundTemplate(t)) => {
+                assert_eq!(t.name, "Copy");
+                assert_eq!(
+                    unit_names(t.units.iter().map(|u| &u.name)),
+                    vec!["Copy-1", "Copy-2"]
+                );
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_rename_plane_group() {
+        let mut group = AddGroupRequest {
+            template: Some(Template::PlaneTemplate(PlaneGroupTemplate {
+                name: String::from("CAP"),
+                units: vec![PlaneUnitTemplate {
+                    name: String::from("CAP-1"),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        rename(&mut group, "CAP");
+
+        match group.template {
+            Some(Template::PlaneTemplate(t)) => {
+                assert_eq!(t.name, "CAP");
+                assert_eq!(unit_names(t.units.iter().map(|u| &u.name)), vec!["CAP-1"]);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_rename_without_template() {
+        let mut group = AddGroupRequest::default();
+        rename(&mut group, "Copy");
+        assert_eq!(group, AddGroupRequest::default());
+    }
+}
@@ -70,6 +70,22 @@ impl UnitService for MissionRpc {
         Ok(Response::new(res))
     }
 
+    async fn get_transforms(
+        &self,
+        request: Request<unit::v0::GetTransformsRequest>,
+    ) -> Result<Response<unit::v0::GetTransformsResponse>, Status> {
+        let res = self.request("getUnitTransforms", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn get_static_transforms(
+        &self,
+        request: Request<unit::v0::GetStaticTransformsRequest>,
+    ) -> Result<Response<unit::v0::GetStaticTransformsResponse>, Status> {
+        let res = self.request("getStaticTransforms", request).await?;
+        Ok(Response::new(res))
+    }
+
     async fn destroy(
         &self,
         request: Request<unit::v0::DestroyRequest>,
@@ -85,4 +101,52 @@ impl UnitService for MissionRpc {
         let res = self.request("staticDestroy", request).await?;
         Ok(Response::new(res))
     }
+
+    async fn get_life(
+        &self,
+        request: Request<unit::v0::GetLifeRequest>,
+    ) -> Result<Response<unit::v0::GetLifeResponse>, Status> {
+        let res = self.request("getUnitLife", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn get_fuel(
+        &self,
+        request: Request<unit::v0::GetFuelRequest>,
+    ) -> Result<Response<unit::v0::GetFuelResponse>, Status> {
+        let res = self.request("getUnitFuel", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn get_ammo(
+        &self,
+        request: Request<unit::v0::GetAmmoRequest>,
+    ) -> Result<Response<unit::v0::GetAmmoResponse>, Status> {
+        let res = self.request("getUnitAmmo", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn get_sensors(
+        &self,
+        request: Request<unit::v0::GetSensorsRequest>,
+    ) -> Result<Response<unit::v0::GetSensorsResponse>, Status> {
+        let res = self.request("getUnitSensors", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn is_in_air(
+        &self,
+        request: Request<unit::v0::IsInAirRequest>,
+    ) -> Result<Response<unit::v0::IsInAirResponse>, Status> {
+        let res = self.request("isUnitInAir", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn get_number(
+        &self,
+        request: Request<unit::v0::GetNumberRequest>,
+    ) -> Result<Response<unit::v0::GetNumberResponse>, Status> {
+        let res = self.request("getUnitNumber", request).await?;
+        Ok(Response::new(res))
+    }
 }
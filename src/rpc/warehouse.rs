@@ -0,0 +1,64 @@
+use stubs::warehouse::v0::warehouse_service_server::WarehouseService;
+use stubs::*;
+use tonic::{Request, Response, Status};
+
+use super::MissionRpc;
+
+#[tonic::async_trait]
+impl WarehouseService for MissionRpc {
+    async fn get_inventory(
+        &self,
+        request: Request<warehouse::v0::GetInventoryRequest>,
+    ) -> Result<Response<warehouse::v0::GetInventoryResponse>, Status> {
+        let res = self.request("getInventory", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn add_item(
+        &self,
+        request: Request<warehouse::v0::AddItemRequest>,
+    ) -> Result<Response<warehouse::v0::AddItemResponse>, Status> {
+        let res = self.request("warehouseAddItem", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn remove_item(
+        &self,
+        request: Request<warehouse::v0::RemoveItemRequest>,
+    ) -> Result<Response<warehouse::v0::RemoveItemResponse>, Status> {
+        let res = self.request("warehouseRemoveItem", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn set_item(
+        &self,
+        request: Request<warehouse::v0::SetItemRequest>,
+    ) -> Result<Response<warehouse::v0::SetItemResponse>, Status> {
+        let res = self.request("warehouseSetItem", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn add_liquid(
+        &self,
+        request: Request<warehouse::v0::AddLiquidRequest>,
+    ) -> Result<Response<warehouse::v0::AddLiquidResponse>, Status> {
+        let res = self.request("warehouseAddLiquid", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn remove_liquid(
+        &self,
+        request: Request<warehouse::v0::RemoveLiquidRequest>,
+    ) -> Result<Response<warehouse::v0::RemoveLiquidResponse>, Status> {
+        let res = self.request("warehouseRemoveLiquid", request).await?;
+        Ok(Response::new(res))
+    }
+
+    async fn set_liquid(
+        &self,
+        request: Request<warehouse::v0::SetLiquidRequest>,
+    ) -> Result<Response<warehouse::v0::SetLiquidResponse>, Status> {
+        let res = self.request("warehouseSetLiquid", request).await?;
+        Ok(Response::new(res))
+    }
+}
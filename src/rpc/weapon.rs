@@ -50,6 +50,14 @@ impl WeaponService for MissionRpc {
         Ok(Response::new(res))
     }
 
+    async fn get_transforms(
+        &self,
+        request: Request<weapon::v0::GetTransformsRequest>,
+    ) -> Result<Response<weapon::v0::GetTransformsResponse>, Status> {
+        let res = self.request("getWeaponTransforms", request).await?;
+        Ok(Response::new(res))
+    }
+
     async fn get_tracked_weapon_ids(
         &self,
         request: Request<weapon::v0::GetTrackedWeaponIdsRequest>,
@@ -58,6 +66,14 @@ impl WeaponService for MissionRpc {
         Ok(Response::new(res))
     }
 
+    async fn get_tracked_weapons(
+        &self,
+        request: Request<weapon::v0::GetTrackedWeaponsRequest>,
+    ) -> Result<Response<weapon::v0::GetTrackedWeaponsResponse>, Status> {
+        let res = self.request("getTrackedWeapons", request).await?;
+        Ok(Response::new(res))
+    }
+
     async fn destroy(
         &self,
         request: Request<weapon::v0::DestroyRequest>,
@@ -1,23 +1,38 @@
 use std::future::Future;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
 use dcs_module_ipc::IPC;
 use futures_util::FutureExt;
+use stubs::access::v0::access_service_server::AccessServiceServer;
+use stubs::airbase::v0::airbase_service_server::AirbaseServiceServer;
+use stubs::atis::v0::atis_service_server::AtisServiceServer;
 use stubs::atmosphere::v0::atmosphere_service_server::AtmosphereServiceServer;
+use stubs::chat::v0::chat_service_server::ChatServiceServer;
 use stubs::coalition::v0::coalition_service_server::CoalitionServiceServer;
 use stubs::controller::v0::controller_service_server::ControllerServiceServer;
+use stubs::coordinate::v0::coordinate_service_server::CoordinateServiceServer;
 use stubs::custom::v0::custom_service_server::CustomServiceServer;
+use stubs::geometry::v0::geometry_service_server::GeometryServiceServer;
 use stubs::group::v0::group_service_server::GroupServiceServer;
 use stubs::hook::v0::hook_service_server::HookServiceServer;
+use stubs::hook::v0::register_decision_handler_response::Decision;
+use stubs::land::v0::land_service_server::LandServiceServer;
 use stubs::mission::v0::mission_service_server::MissionServiceServer;
+use stubs::mission::v0::stream_events_response::{BanEnforcedEvent, Event};
 use stubs::mission::v0::StreamEventsResponse;
 use stubs::net::v0::net_service_server::NetServiceServer;
+use stubs::player::v0::player_service_server::PlayerServiceServer;
+use stubs::rotation::v0::rotation_service_server::RotationServiceServer;
+use stubs::stats::v0::stats_service_server::StatsServiceServer;
+use stubs::template::v0::template_service_server::TemplateServiceServer;
 use stubs::timer::v0::timer_service_server::TimerServiceServer;
 use stubs::trigger::v0::trigger_service_server::TriggerServiceServer;
 use stubs::tts::v0::tts_service_server::{TtsService, TtsServiceServer};
 use stubs::unit::v0::unit_service_server::UnitServiceServer;
+use stubs::warehouse::v0::warehouse_service_server::WarehouseServiceServer;
 use stubs::weapon::v0::weapon_service_server::WeaponServiceServer;
 use stubs::world::v0::world_service_server::WorldServiceServer;
 use tokio::runtime::Runtime;
@@ -25,10 +40,23 @@ use tokio::sync::oneshot::{self, Receiver};
 use tokio::time::sleep;
 use tonic::transport;
 
+use crate::access::Access;
+use crate::atis::AtisBroadcasts;
+use crate::chat::ChatCommands;
 use crate::config::{Config, SrsConfig, TtsConfig};
-use crate::rpc::{HookRpc, MissionRpc, Tts};
+use crate::decisions::{Decisions, Verdict};
+use crate::gci::Gci;
+use crate::player_registry::PlayerRegistry;
+use crate::player_stats::PlayerStats;
+use crate::rotation::MissionRotation;
+use crate::rpc::{
+    AccessRpc, AtisRpc, ChatRpc, HookRpc, MissionRpc, PlayerRpc, PlayerStatsRpc, RotationRpc,
+    TemplateRpc, Tts,
+};
 use crate::shutdown::{Shutdown, ShutdownHandle};
+use crate::sorties::Sorties;
 use crate::stats::Stats;
+use crate::templates::GroupTemplates;
 
 pub struct Server {
     runtime: Runtime,
@@ -36,6 +64,7 @@ pub struct Server {
     after_shutdown: Option<oneshot::Sender<()>>,
     state: ServerState,
     tts: Arc<Tts>,
+    gci: Option<Gci>,
 }
 
 #[derive(Clone)]
@@ -45,6 +74,15 @@ struct ServerState {
     ipc_mission: IPC<StreamEventsResponse>,
     ipc_hook: IPC<()>,
     stats: Stats,
+    sorties: Sorties,
+    player_stats: Option<PlayerStats>,
+    chat_commands: ChatCommands,
+    decisions: Decisions,
+    access: Option<Access>,
+    atis: AtisBroadcasts,
+    player_registry: Option<PlayerRegistry>,
+    rotation: MissionRotation,
+    templates: GroupTemplates,
     tts_config: TtsConfig,
     srs_config: SrsConfig,
 }
@@ -55,24 +93,93 @@ impl Server {
         let ipc_hook = IPC::default();
         let runtime = Runtime::new()?;
         let shutdown = Shutdown::new();
+
+        let player_stats_path = PathBuf::from(&config.write_dir).join("DCS-gRPC/stats.sqlite3");
+        let player_stats = match PlayerStats::open(&player_stats_path) {
+            Ok(player_stats) => Some(player_stats),
+            Err(err) => {
+                log::error!(
+                    "Failed to open player stats database at {}: {}",
+                    player_stats_path.display(),
+                    err
+                );
+                None
+            }
+        };
+
+        let access_path = PathBuf::from(&config.write_dir).join("DCS-gRPC/access.sqlite3");
+        let access = match Access::open(&access_path) {
+            Ok(access) => Some(access),
+            Err(err) => {
+                log::error!(
+                    "Failed to open access database at {}: {}",
+                    access_path.display(),
+                    err
+                );
+                None
+            }
+        };
+
+        let player_registry_path =
+            PathBuf::from(&config.write_dir).join("DCS-gRPC/players.sqlite3");
+        let player_registry = match PlayerRegistry::open(&player_registry_path) {
+            Ok(player_registry) => Some(player_registry),
+            Err(err) => {
+                log::error!(
+                    "Failed to open player registry database at {}: {}",
+                    player_registry_path.display(),
+                    err
+                );
+                None
+            }
+        };
+
+        let tts = Arc::new(Tts::new(
+            config.tts.clone().unwrap_or_default(),
+            config.srs.clone().unwrap_or_default(),
+            ipc_mission.clone(),
+            shutdown.handle(),
+        ));
+        let rotation = MissionRotation::new(
+            PathBuf::from(&config.write_dir).join("DCS-gRPC/rotation.json"),
+            config.rotation.clone(),
+            ipc_mission.clone(),
+            ipc_hook.clone(),
+            tts.clone(),
+        );
+        let stats = Stats::new(shutdown.handle());
+        let mission_rpc = MissionRpc::new(ipc_mission.clone(), stats.clone(), shutdown.handle());
+        let atis = AtisBroadcasts::new(mission_rpc.clone(), tts.clone(), shutdown.handle());
+        let gci = Gci::new(config.gci.clone(), mission_rpc, tts.clone());
+
         Ok(Self {
             runtime,
             after_shutdown: None,
             state: ServerState {
                 addr: format!("{}:{}", config.host, config.port).parse()?,
                 eval_enabled: config.eval_enabled,
-                ipc_mission: ipc_mission.clone(),
+                ipc_mission,
                 ipc_hook,
-                stats: Stats::new(shutdown.handle()),
+                stats,
+                sorties: Sorties::default(),
+                player_stats,
+                chat_commands: ChatCommands::default(),
+                decisions: Decisions::new(
+                    Duration::from_millis(config.decision_timeout),
+                    config.decision_default,
+                ),
+                access,
+                atis,
+                player_registry,
+                rotation,
+                templates: GroupTemplates::new(
+                    PathBuf::from(&config.write_dir).join("DCS-gRPC/templates.json"),
+                ),
                 tts_config: config.tts.clone().unwrap_or_default(),
                 srs_config: config.srs.clone().unwrap_or_default(),
             },
-            tts: Arc::new(Tts::new(
-                config.tts.clone().unwrap_or_default(),
-                config.srs.clone().unwrap_or_default(),
-                ipc_mission,
-                shutdown.handle(),
-            )),
+            tts,
+            gci,
             shutdown,
         })
     }
@@ -99,6 +206,47 @@ impl Server {
             self.state.ipc_mission.clone(),
             self.shutdown.handle().signal(),
         ));
+
+        self.runtime.spawn(crate::kills::run_in_background(
+            self.state.ipc_mission.clone(),
+            self.shutdown.handle().signal(),
+        ));
+
+        self.runtime
+            .spawn(self.state.sorties.clone().run_in_background(
+                self.state.ipc_mission.clone(),
+                self.shutdown.handle().signal(),
+            ));
+
+        if let Some(player_stats) = &self.state.player_stats {
+            self.runtime.spawn(player_stats.clone().run_in_background(
+                self.state.ipc_mission.clone(),
+                self.state.sorties.clone(),
+                self.shutdown.handle().signal(),
+            ));
+        }
+
+        self.runtime.spawn(
+            self.state
+                .rotation
+                .clone()
+                .run_in_background(self.shutdown.handle().signal()),
+        );
+
+        if let Some(player_registry) = &self.state.player_registry {
+            self.runtime
+                .spawn(player_registry.clone().run_in_background(
+                    self.state.ipc_mission.clone(),
+                    self.shutdown.handle().signal(),
+                ));
+        }
+
+        if let Some(gci) = &self.gci {
+            self.runtime.spawn(
+                gci.clone()
+                    .run_in_background(self.shutdown.handle().signal()),
+            );
+        }
     }
 
     pub fn stop_blocking(mut self) {
@@ -125,6 +273,73 @@ impl Server {
         &self.state.stats
     }
 
+    /// Handle a chat message in case it is a registered chat command. Returns whether to hide the
+    /// message, and a reply to send to the player if the command could not be parsed.
+    pub fn chat_command(
+        &self,
+        time: f64,
+        player_id: u32,
+        ucid: &str,
+        message: &str,
+        coalition: i32,
+    ) -> (bool, Option<String>) {
+        let handled = match self
+            .state
+            .chat_commands
+            .handle(player_id, ucid, message, coalition)
+        {
+            Some(handled) => handled,
+            None => return (false, None),
+        };
+
+        match handled.result {
+            Ok(event) => {
+                log::debug!("Received chat command: {:#?}", event);
+                self.runtime
+                    .block_on(self.state.ipc_mission.event(StreamEventsResponse {
+                        time,
+                        event: Some(Event::ChatCommand(event)),
+                    }));
+                (handled.hide, None)
+            }
+            Err(reply) => (handled.hide, Some(reply)),
+        }
+    }
+
+    /// Check whether a connecting player is banned or not whitelisted. Returns the reason to show
+    /// to the player if the player is not allowed to connect.
+    pub fn check_access(
+        &self,
+        time: f64,
+        addr: String,
+        name: String,
+        ucid: String,
+    ) -> Option<String> {
+        let denied = self.state.access.as_ref()?.check(&ucid, &addr)?;
+        log::info!("Rejected {} ({}, {}): {}", name, ucid, addr, denied.reason);
+        self.runtime
+            .block_on(self.state.ipc_mission.event(StreamEventsResponse {
+                time,
+                event: Some(Event::BanEnforced(BanEnforcedEvent {
+                    addr,
+                    name,
+                    ucid,
+                    ban_id: denied.ban_id,
+                    reason: denied.reason.clone(),
+                })),
+            }));
+        Some(denied.reason)
+    }
+
+    /// Ask the registered decision handler (if any) whether to allow the given decision, waiting
+    /// at most for the configured decision timeout.
+    pub fn decide(&self, decision: Decision) -> Verdict {
+        match self.state.decisions.submit(decision) {
+            Ok(pending) => self.runtime.block_on(self.state.decisions.wait(pending)),
+            Err(verdict) => verdict,
+        }
+    }
+
     pub fn block_on<F: Future>(&self, future: F) -> F::Output {
         self.runtime.block_on(future)
     }
@@ -200,13 +415,22 @@ async fn try_run(
         ipc_mission,
         ipc_hook,
         stats,
+        sorties,
+        player_stats,
+        chat_commands,
+        decisions,
+        access,
+        atis,
+        player_registry,
+        rotation,
+        templates,
         tts_config,
         srs_config,
     } = state;
 
     let mut mission_rpc =
         MissionRpc::new(ipc_mission.clone(), stats.clone(), shutdown_signal.clone());
-    let mut hook_rpc = HookRpc::new(ipc_hook, stats, shutdown_signal.clone());
+    let mut hook_rpc = HookRpc::new(ipc_hook, stats, decisions, shutdown_signal.clone());
 
     if eval_enabled {
         mission_rpc.enable_eval();
@@ -214,14 +438,32 @@ async fn try_run(
     }
 
     transport::Server::builder()
+        .add_service(AccessServiceServer::new(AccessRpc::new(access)))
+        .add_service(AirbaseServiceServer::new(mission_rpc.clone()))
+        .add_service(AtisServiceServer::new(AtisRpc::new(atis)))
         .add_service(AtmosphereServiceServer::new(mission_rpc.clone()))
+        .add_service(ChatServiceServer::new(ChatRpc::new(chat_commands)))
         .add_service(CoalitionServiceServer::new(mission_rpc.clone()))
         .add_service(ControllerServiceServer::new(mission_rpc.clone()))
+        .add_service(CoordinateServiceServer::new(mission_rpc.clone()))
         .add_service(CustomServiceServer::new(mission_rpc.clone()))
+        .add_service(GeometryServiceServer::new(mission_rpc.clone()))
         .add_service(GroupServiceServer::new(mission_rpc.clone()))
         .add_service(HookServiceServer::new(hook_rpc))
+        .add_service(LandServiceServer::new(mission_rpc.clone()))
         .add_service(MissionServiceServer::new(mission_rpc.clone()))
         .add_service(NetServiceServer::new(mission_rpc.clone()))
+        .add_service(PlayerServiceServer::new(PlayerRpc::new(player_registry)))
+        .add_service(RotationServiceServer::new(RotationRpc::new(rotation)))
+        .add_service(StatsServiceServer::new(PlayerStatsRpc::new(
+            sorties,
+            player_stats,
+            shutdown_signal.clone(),
+        )))
+        .add_service(TemplateServiceServer::new(TemplateRpc::new(
+            mission_rpc.clone(),
+            templates,
+        )))
         .add_service(TimerServiceServer::new(mission_rpc.clone()))
         .add_service(TriggerServiceServer::new(mission_rpc.clone()))
         .add_service(TtsServiceServer::new(Tts::new(
@@ -231,6 +473,7 @@ async fn try_run(
             shutdown_signal.clone(),
         )))
         .add_service(UnitServiceServer::new(mission_rpc.clone()))
+        .add_service(WarehouseServiceServer::new(mission_rpc.clone()))
         .add_service(WeaponServiceServer::new(mission_rpc.clone()))
         .add_service(WorldServiceServer::new(mission_rpc))
         .serve_with_shutdown(addr, after_shutdown.map(|_| ()))
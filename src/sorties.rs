@@ -0,0 +1,513 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use dcs_module_ipc::IPC;
+use futures_util::StreamExt;
+use stubs::common::v0::{initiator, Initiator, Unit};
+use stubs::mission::v0::stream_events_response::{
+    AttributedKillEvent, BirthEvent, CrashEvent, DeadEvent, EjectionEvent, Event, HitEvent,
+    LandEvent, MissionEndEvent, PilotDeadEvent, PlayerEnterUnitEvent, PlayerLeaveUnitEvent,
+    ShotEvent, TakeoffEvent,
+};
+use stubs::mission::v0::StreamEventsResponse;
+use stubs::stats::v0::{Sortie, SortieOutcome, WeaponsExpended};
+use tokio::sync::{broadcast, RwLock};
+
+/// Tracks the sorties of all players of the current mission.
+#[derive(Clone)]
+pub struct Sorties(Arc<Inner>);
+
+struct Inner {
+    state: RwLock<State>,
+    updates: broadcast::Sender<Sortie>,
+}
+
+#[derive(Default)]
+struct State {
+    /// All sorties of the mission, the index being the sortie's id minus one.
+    sorties: Vec<Sortie>,
+    /// The index of the latest sortie flown in a unit, by the id of the unit.
+    by_unit: HashMap<u32, usize>,
+    /// The time since which the unit of a sortie is in the air, by sortie index.
+    airborne_since: HashMap<usize, f64>,
+}
+
+impl Default for Sorties {
+    fn default() -> Self {
+        let (updates, _) = broadcast::channel(128);
+        Self(Arc::new(Inner {
+            state: Default::default(),
+            updates,
+        }))
+    }
+}
+
+impl Sorties {
+    /// All sorties, optionally only the ones of the given player.
+    pub async fn list(&self, player_name: Option<&str>) -> Vec<Sortie> {
+        let state = self.0.state.read().await;
+        state
+            .sorties
+            .iter()
+            .filter(|sortie| match player_name {
+                Some(name) => sortie.player_name == name,
+                None => true,
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub async fn get(&self, id: u32) -> Option<Sortie> {
+        let state = self.0.state.read().await;
+        let ix = usize::try_from(id).ok()?.checked_sub(1)?;
+        state.sorties.get(ix).cloned()
+    }
+
+    /// Receive every sortie whenever it starts, changes or ends.
+    pub fn subscribe(&self) -> broadcast::Receiver<Sortie> {
+        self.0.updates.subscribe()
+    }
+
+    /// End all sorties that are still in progress at the given mission time (e.g. because the
+    /// server shuts down) and return them.
+    pub async fn end_all(&self, time: f64) -> Vec<Sortie> {
+        let mut state = self.0.state.write().await;
+        let ended = state
+            .end_all(time)
+            .into_iter()
+            .map(|ix| state.sorties[ix].clone())
+            .collect::<Vec<_>>();
+        for sortie in &ended {
+            // an error only means that there is currently no subscriber
+            let _ = self.0.updates.send(sortie.clone());
+        }
+        ended
+    }
+
+    pub async fn run_in_background(
+        self,
+        ipc: IPC<StreamEventsResponse>,
+        mut shutdown_signal: impl Future<Output = ()> + Unpin,
+    ) {
+        let mut events = ipc.events().await;
+
+        loop {
+            // wait for either the shutdown signal or the next event, whatever happens first
+            let StreamEventsResponse { time, event } = tokio::select! {
+                _ = &mut shutdown_signal => {
+                    break
+                }
+                Some(event) = events.next() => event,
+                else => break,
+            };
+
+            if let Some(event) = event {
+                let updated = self.0.state.write().await.handle(time, event);
+                for sortie in updated {
+                    // an error only means that there is currently no subscriber
+                    let _ = self.0.updates.send(sortie);
+                }
+            }
+        }
+    }
+}
+
+impl State {
+    /// Update the sorties with the given event and return all sorties that changed.
+    fn handle(&mut self, time: f64, event: Event) -> Vec<Sortie> {
+        let mut updated = Vec::new();
+
+        match event {
+            Event::Birth(BirthEvent { initiator, .. })
+            | Event::PlayerEnterUnit(PlayerEnterUnitEvent { initiator }) => {
+                if let Some(ix) = initiator_unit(initiator).and_then(|unit| self.start(time, unit))
+                {
+                    updated.push(ix);
+                }
+            }
+
+            Event::Takeoff(TakeoffEvent { initiator, .. }) => {
+                if let Some(ix) = self.in_progress(initiator) {
+                    self.sorties[ix].takeoff_time.get_or_insert(time);
+                    self.airborne_since.insert(ix, time);
+                    updated.push(ix);
+                }
+            }
+
+            Event::Land(LandEvent { initiator, .. }) => {
+                if let Some(ix) = self.in_progress(initiator) {
+                    self.touch_down(ix, time);
+                    self.sorties[ix].landing_time = Some(time);
+                    updated.push(ix);
+                }
+            }
+
+            Event::Shot(ShotEvent {
+                initiator,
+                weapon: Some(weapon),
+            }) => {
+                if let Some(ix) = self.in_progress(initiator) {
+                    let weapon_type = weapon.r#type.unwrap_or_default();
+                    let expended = &mut self.sorties[ix].weapons_expended;
+                    match expended.iter_mut().find(|w| w.weapon_type == weapon_type) {
+                        Some(w) => w.count += 1,
+                        None => expended.push(WeaponsExpended {
+                            weapon_type,
+                            count: 1,
+                        }),
+                    }
+                    updated.push(ix);
+                }
+            }
+
+            Event::Hit(HitEvent { initiator, .. }) => {
+                if let Some(ix) = self.in_progress(initiator) {
+                    self.sorties[ix].hits += 1;
+                    updated.push(ix);
+                }
+            }
+
+            Event::AttributedKill(AttributedKillEvent { victim, killer, .. }) => {
+                let killer = match killer {
+                    Some(killer) => killer,
+                    None => return Vec::new(),
+                };
+
+                if let Some(ix) = self.in_progress_of(killer.id) {
+                    self.sorties[ix].kills += 1;
+                    updated.push(ix);
+                }
+
+                // The unit might have already been reported as crashed (or its pilot as ejected)
+                // before the kill got attributed, in which case the outcome is corrected.
+                if let Some(&ix) = victim.and_then(|victim| self.by_unit.get(&victim.id)) {
+                    if matches!(
+                        self.sorties[ix].outcome(),
+                        SortieOutcome::Crashed | SortieOutcome::Ejected
+                    ) {
+                        self.sorties[ix].set_outcome(SortieOutcome::Killed);
+                        updated.push(ix);
+                    } else if self.end(ix, time, SortieOutcome::Killed) {
+                        updated.push(ix);
+                    }
+                }
+            }
+
+            Event::Ejection(EjectionEvent { initiator, .. }) => {
+                if let Some(ix) = self.in_progress(initiator) {
+                    if self.end(ix, time, SortieOutcome::Ejected) {
+                        updated.push(ix);
+                    }
+                }
+            }
+
+            Event::Crash(CrashEvent { initiator })
+            | Event::Dead(DeadEvent { initiator })
+            | Event::PilotDead(PilotDeadEvent { initiator }) => {
+                if let Some(ix) = self.in_progress(initiator) {
+                    if self.end(ix, time, SortieOutcome::Crashed) {
+                        updated.push(ix);
+                    }
+                }
+            }
+
+            Event::PlayerLeaveUnit(PlayerLeaveUnitEvent { initiator }) => {
+                if let Some(ix) = self.in_progress(initiator) {
+                    if self.end(ix, time, self.left_outcome(ix)) {
+                        updated.push(ix);
+                    }
+                }
+            }
+
+            Event::MissionEnd(MissionEndEvent {}) => {
+                updated.extend(self.end_all(time));
+            }
+
+            _ => {}
+        }
+
+        updated.sort_unstable();
+        updated.dedup();
+        updated
+            .into_iter()
+            .map(|ix| self.sorties[ix].clone())
+            .collect()
+    }
+
+    /// Start a new sortie for the given unit, if it is controlled by a player and there isn't
+    /// already a sortie in progress for it (e.g. due to both a `Birth` and `PlayerEnterUnit`
+    /// event).
+    fn start(&mut self, time: f64, unit: Unit) -> Option<usize> {
+        let player_name = unit.player_name.filter(|name| !name.is_empty())?;
+        if self.in_progress_of(unit.id).is_some() {
+            return None;
+        }
+
+        let ix = self.sorties.len();
+        let mut sortie = Sortie {
+            id: ix as u32 + 1,
+            player_name,
+            unit_name: unit.name,
+            airframe: unit.r#type.unwrap_or_default(),
+            coalition: unit.coalition,
+            start_time: time,
+            ..Default::default()
+        };
+        sortie.set_outcome(SortieOutcome::InProgress);
+        self.sorties.push(sortie);
+        self.by_unit.insert(unit.id, ix);
+
+        // players can spawn in the air
+        if unit.in_air {
+            self.airborne_since.insert(ix, time);
+        }
+
+        Some(ix)
+    }
+
+    /// End the given sortie with the given outcome. Returns `false` if the sortie already ended.
+    fn end(&mut self, ix: usize, time: f64, outcome: SortieOutcome) -> bool {
+        if self.sorties[ix].outcome() != SortieOutcome::InProgress {
+            return false;
+        }
+
+        self.touch_down(ix, time);
+        let sortie = &mut self.sorties[ix];
+        sortie.end_time = Some(time);
+        sortie.set_outcome(outcome);
+        true
+    }
+
+    /// End all sorties that are still in progress as if their players left their units. Returns
+    /// the indices of the ended sorties.
+    fn end_all(&mut self, time: f64) -> Vec<usize> {
+        let mut in_progress = self
+            .by_unit
+            .values()
+            .copied()
+            .filter(|ix| self.sorties[*ix].outcome() == SortieOutcome::InProgress)
+            .collect::<Vec<_>>();
+        in_progress.sort_unstable();
+        for &ix in &in_progress {
+            self.end(ix, time, self.left_outcome(ix));
+        }
+        in_progress
+    }
+
+    /// The outcome of a sortie whose player left the unit.
+    fn left_outcome(&self, ix: usize) -> SortieOutcome {
+        if self.airborne_since.contains_key(&ix) {
+            SortieOutcome::Abandoned
+        } else {
+            SortieOutcome::Landed
+        }
+    }
+
+    /// Add the time since the last takeoff to the flight time of the given sortie.
+    fn touch_down(&mut self, ix: usize, time: f64) {
+        if let Some(since) = self.airborne_since.remove(&ix) {
+            self.sorties[ix].flight_time += (time - since).max(0.0);
+        }
+    }
+
+    fn in_progress(&self, initiator: Option<Initiator>) -> Option<usize> {
+        initiator_unit(initiator).and_then(|unit| self.in_progress_of(unit.id))
+    }
+
+    fn in_progress_of(&self, unit_id: u32) -> Option<usize> {
+        self.by_unit
+            .get(&unit_id)
+            .copied()
+            .filter(|ix| self.sorties[*ix].outcome() == SortieOutcome::InProgress)
+    }
+}
+
+fn initiator_unit(initiator: Option<Initiator>) -> Option<Unit> {
+    match initiator?.initiator? {
+        initiator::Initiator::Unit(unit) => Some(unit),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(id: u32, in_air: bool) -> Unit {
+        Unit {
+            id,
+            name: format!("Unit {id}"),
+            player_name: Some(format!("Player {id}")),
+            r#type: Some(String::from("F-16C_50")),
+            in_air,
+            ..Default::default()
+        }
+    }
+
+    fn initiator(unit: Unit) -> Option<Initiator> {
+        Some(Initiator {
+            initiator: Some(initiator::Initiator::Unit(unit)),
+        })
+    }
+
+    fn birth(unit: Unit) -> Event {
+        Event::Birth(BirthEvent {
+            initiator: initiator(unit),
+            ..Default::default()
+        })
+    }
+
+    fn takeoff(id: u32) -> Event {
+        Event::Takeoff(TakeoffEvent {
+            initiator: initiator(unit(id, false)),
+            ..Default::default()
+        })
+    }
+
+    fn outcomes(state: &State) -> Vec<SortieOutcome> {
+        state
+            .sorties
+            .iter()
+            .map(|sortie| sortie.outcome())
+            .collect()
+    }
+
+    #[test]
+    fn test_start_once() {
+        let mut state = State::default();
+        assert_eq!(state.handle(1.0, birth(unit(1, false))).len(), 1);
+        let updated = state.handle(
+            1.0,
+            Event::PlayerEnterUnit(PlayerEnterUnitEvent {
+                initiator: initiator(unit(1, false)),
+            }),
+        );
+        assert!(updated.is_empty());
+        assert_eq!(state.sorties.len(), 1);
+
+        let sortie = &state.sorties[0];
+        assert_eq!(sortie.id, 1);
+        assert_eq!(sortie.player_name, "Player 1");
+        assert_eq!(sortie.airframe, "F-16C_50");
+        assert_eq!(sortie.outcome(), SortieOutcome::InProgress);
+
+        // units without a player don't start a sortie
+        let mut ai = unit(2, false);
+        ai.player_name = None;
+        assert!(state.handle(2.0, birth(ai)).is_empty());
+        assert_eq!(state.sorties.len(), 1);
+    }
+
+    #[test]
+    fn test_crash_corrected_to_killed() {
+        let mut state = State::default();
+        state.handle(0.0, birth(unit(1, false)));
+        state.handle(10.0, takeoff(1));
+        state.handle(
+            100.0,
+            Event::Crash(CrashEvent {
+                initiator: initiator(unit(1, true)),
+            }),
+        );
+        assert_eq!(outcomes(&state), vec![SortieOutcome::Crashed]);
+        assert_eq!(state.sorties[0].flight_time, 90.0);
+
+        let updated = state.handle(
+            101.0,
+            Event::AttributedKill(AttributedKillEvent {
+                victim: Some(unit(1, true)),
+                killer: Some(unit(2, true)),
+                ..Default::default()
+            }),
+        );
+        assert_eq!(updated.len(), 1);
+        assert_eq!(outcomes(&state), vec![SortieOutcome::Killed]);
+        // the end of the sortie isn't changed
+        assert_eq!(state.sorties[0].end_time, Some(100.0));
+    }
+
+    #[test]
+    fn test_ejection_corrected_to_killed() {
+        let mut state = State::default();
+        state.handle(0.0, birth(unit(1, true)));
+        state.handle(0.0, birth(unit(2, true)));
+        state.handle(
+            50.0,
+            Event::Ejection(EjectionEvent {
+                initiator: initiator(unit(1, true)),
+                ..Default::default()
+            }),
+        );
+        assert_eq!(
+            outcomes(&state),
+            vec![SortieOutcome::Ejected, SortieOutcome::InProgress]
+        );
+
+        state.handle(
+            50.0,
+            Event::AttributedKill(AttributedKillEvent {
+                victim: Some(unit(1, true)),
+                killer: Some(unit(2, true)),
+                ..Default::default()
+            }),
+        );
+        assert_eq!(
+            outcomes(&state),
+            vec![SortieOutcome::Killed, SortieOutcome::InProgress]
+        );
+        assert_eq!(state.sorties[1].kills, 1);
+    }
+
+    #[test]
+    fn test_player_leave_unit() {
+        let mut state = State::default();
+        state.handle(0.0, birth(unit(1, false)));
+        state.handle(0.0, birth(unit(2, false)));
+        state.handle(10.0, takeoff(2));
+        for id in [1, 2] {
+            state.handle(
+                100.0,
+                Event::PlayerLeaveUnit(PlayerLeaveUnitEvent {
+                    initiator: initiator(unit(id, false)),
+                }),
+            );
+        }
+        assert_eq!(
+            outcomes(&state),
+            vec![SortieOutcome::Landed, SortieOutcome::Abandoned]
+        );
+        assert_eq!(state.sorties[0].flight_time, 0.0);
+        assert_eq!(state.sorties[1].flight_time, 90.0);
+    }
+
+    #[test]
+    fn test_mission_end() {
+        let mut state = State::default();
+        state.handle(0.0, birth(unit(1, false)));
+        state.handle(0.0, birth(unit(2, false)));
+        state.handle(0.0, birth(unit(3, true)));
+        state.handle(
+            50.0,
+            Event::Crash(CrashEvent {
+                initiator: initiator(unit(2, false)),
+            }),
+        );
+
+        let updated = state.handle(100.0, Event::MissionEnd(MissionEndEvent {}));
+        assert_eq!(
+            updated.iter().map(|sortie| sortie.id).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert_eq!(
+            outcomes(&state),
+            vec![
+                SortieOutcome::Landed,
+                SortieOutcome::Crashed,
+                SortieOutcome::Abandoned
+            ]
+        );
+        assert_eq!(state.sorties[2].flight_time, 100.0);
+        assert_eq!(state.sorties[2].end_time, Some(100.0));
+    }
+}
@@ -13,15 +13,15 @@ use stubs::common::v0::{
 use stubs::group::v0::group_service_server::GroupService;
 use stubs::group::v0::GetUnitsRequest;
 use stubs::mission::v0::stream_events_response::{BirthEvent, DeadEvent, Event};
-use stubs::mission::v0::stream_units_response::{UnitGone, Update};
+use stubs::mission::v0::stream_units_response::{UnitDelta, UnitGone, Update};
 use stubs::mission::v0::{StreamUnitsRequest, StreamUnitsResponse};
 use stubs::unit::v0::unit_service_server::UnitService;
-use stubs::unit::v0::{GetStaticTransformRequest, GetStaticTransformResponse};
-use stubs::unit::v0::{GetTransformRequest, GetTransformResponse};
+use stubs::unit::v0::{GetStaticTransformResponse, GetStaticTransformsRequest};
+use stubs::unit::v0::{GetTransformResponse, GetTransformsRequest};
 use tokio::sync::mpsc::error::SendError;
 use tokio::sync::mpsc::Sender;
 use tokio::time::MissedTickBehavior;
-use tonic::{Code, Request, Status};
+use tonic::{Request, Status};
 
 use crate::rpc::MissionRpc;
 
@@ -32,12 +32,38 @@ pub async fn stream_units(
     tx: Sender<Result<StreamUnitsResponse, Status>>,
 ) -> Result<(), Error> {
     // initialize the state for the current units stream instance
-    let poll_rate = opts.poll_rate.unwrap_or(5);
-    let max_backoff = Duration::from_secs(opts.max_backoff.unwrap_or(30).max(poll_rate) as u64);
-    let poll_rate = Duration::from_secs(poll_rate as u64);
+    let poll_rate = match opts.poll_rate_ms {
+        Some(poll_rate_ms) => Duration::from_millis(poll_rate_ms as u64),
+        None => Duration::from_secs(opts.poll_rate.unwrap_or(5) as u64),
+    }
+    .max(MIN_POLL_RATE);
+    let category_poll_rates = opts
+        .category_poll_rates
+        .iter()
+        .filter_map(|rate| {
+            Some((
+                GroupCategory::from_i32(rate.category)?,
+                Duration::from_millis(rate.poll_rate_ms as u64).max(MIN_POLL_RATE),
+            ))
+        })
+        .collect::<HashMap<_, _>>();
+    // the stream ticks at the fastest of all requested poll rates, units with a slower poll rate
+    // are skipped until their poll rate elapsed
+    let tick_rate = category_poll_rates
+        .values()
+        .copied()
+        .fold(poll_rate, Duration::min);
+    let max_backoff = category_poll_rates
+        .values()
+        .copied()
+        .fold(poll_rate, Duration::max)
+        .max(Duration::from_secs(opts.max_backoff.unwrap_or(30) as u64));
+    let position_tolerance = opts.position_tolerance.map(|tolerance| tolerance.max(0.0));
     let category = GroupCategory::from_i32(opts.category).unwrap_or(GroupCategory::Unspecified);
     let include_static_objects = opts.include_static_objects.unwrap_or(false);
     log::info!("Include Statics ? {}", include_static_objects);
+    let compact = opts.compact.unwrap_or(false);
+    let include_vitals = opts.include_vitals.unwrap_or(false);
     let mut state = State {
         units: HashMap::new(),
         statics: HashMap::new(),
@@ -45,7 +71,11 @@ pub async fn stream_units(
             rpc,
             tx,
             poll_rate,
+            category_poll_rates,
             max_backoff,
+            position_tolerance,
+            compact,
+            include_vitals,
         },
     };
 
@@ -82,7 +112,7 @@ pub async fn stream_units(
         state.units.extend(
             units
                 .into_iter()
-                .map(|unit| (unit.name.clone(), UnitState::new(unit))),
+                .map(|unit| (unit.name.clone(), UnitState::new(unit, &state.ctx))),
         )
     }
 
@@ -138,7 +168,7 @@ pub async fn stream_units(
     let mut events = state.ctx.rpc.events().await;
 
     // create an interval used to poll the mission for updates
-    let mut interval = tokio::time::interval(poll_rate);
+    let mut interval = tokio::time::interval(tick_rate);
     interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
     loop {
@@ -152,9 +182,10 @@ pub async fn stream_units(
             }
 
             // poll units for updates
-            _ = interval.tick() => {
-                update_units(&mut state).await?;
-                update_static_objects(&mut state).await?;
+            now = interval.tick() => {
+                let now = now.into_std();
+                update_units(&mut state, now).await?;
+                update_static_objects(&mut state, now).await?;
             }
         }
     }
@@ -172,10 +203,31 @@ struct State {
 struct Context {
     rpc: MissionRpc,
     tx: Sender<Result<StreamUnitsResponse, Status>>,
+    /// The poll rate used for static objects and for units without a category specific poll rate.
     poll_rate: Duration,
+    category_poll_rates: HashMap<GroupCategory, Duration>,
     max_backoff: Duration,
+    /// The position error tolerance in meters used to schedule polls based on a unit's speed.
+    position_tolerance: Option<f64>,
+    /// Whether to send [UnitDelta]s instead of full units for transform-only changes.
+    compact: bool,
+    /// Whether to poll and send the life and ammunition of units.
+    include_vitals: bool,
+}
+
+impl Context {
+    /// The poll rate to use for units of the given category.
+    fn poll_rate_for(&self, category: GroupCategory) -> Duration {
+        self.category_poll_rates
+            .get(&category)
+            .copied()
+            .unwrap_or(self.poll_rate)
+    }
 }
 
+/// The lower bound for all poll rates, to not flood the mission with requests.
+const MIN_POLL_RATE: Duration = Duration::from_millis(10);
+
 /// Update the given [State] based on the given [Event].
 async fn handle_event(
     state: &mut State,
@@ -207,7 +259,9 @@ async fn handle_event(
                         update: Some(Update::Unit(unit.clone())),
                     }))
                     .await?;
-                state.units.insert(unit.name.clone(), UnitState::new(unit));
+                state
+                    .units
+                    .insert(unit.name.clone(), UnitState::new(unit, &state.ctx));
             }
         }
 
@@ -284,67 +338,97 @@ async fn handle_event(
 }
 
 /// Updates all units inside of the provided [State].
-async fn update_units(state: &mut State) -> Result<(), Error> {
-    let mut units = std::mem::take(&mut state.units);
-    // Update all units in parallel (will queue a request for each unit, but the execution will
-    // still be throttled by the throughputLimit setting).
-    futures_util::future::try_join_all(
-        units
-            .values_mut()
-            .map(|unit_state| update_unit(&state.ctx, unit_state)),
+async fn update_units(state: &mut State, now: Instant) -> Result<(), Error> {
+    // Retrieve the transforms of all units that are due for an update with a single request.
+    let names = state
+        .units
+        .values()
+        .filter(|unit_state| unit_state.should_update(now))
+        .map(|unit_state| unit_state.unit.name.clone())
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        return Ok(());
+    }
+
+    let mut transforms = UnitService::get_transforms(
+        &state.ctx.rpc,
+        Request::new(GetTransformsRequest {
+            names: names.clone(),
+            include_vitals: Some(state.ctx.include_vitals),
+        }),
     )
-    .await?;
+    .await?
+    .into_inner()
+    .transforms;
+
+    for name in names {
+        if let Some(unit_state) = state.units.get_mut(&name) {
+            update_unit(&state.ctx, unit_state, transforms.remove(&name), now).await?;
+        }
+    }
 
     // remove state for all units that are gone
-    units.retain(|_, v| !v.is_gone);
-    state.units = units;
+    state.units.retain(|_, v| !v.is_gone);
 
     Ok(())
 }
 
 /// Updates all the static objects inside the provided [State].
-async fn update_static_objects(state: &mut State) -> Result<(), Error> {
-    let mut statics = std::mem::take(&mut state.statics);
-    // Update all units in parallel (will queue a request for each unit, but the execution will
-    // still be throttled by the throughputLimit setting).
-    futures_util::future::try_join_all(
-        statics
-            .values_mut()
-            .map(|static_state| update_static_object(&state.ctx, static_state)),
+async fn update_static_objects(state: &mut State, now: Instant) -> Result<(), Error> {
+    // Retrieve the transforms of all static objects that are due for an update with a single
+    // request.
+    let names = state
+        .statics
+        .values()
+        .filter(|static_state| static_state.should_update(now))
+        .map(|static_state| static_state.static_object.name.clone())
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        return Ok(());
+    }
+
+    let mut transforms = UnitService::get_static_transforms(
+        &state.ctx.rpc,
+        Request::new(GetStaticTransformsRequest {
+            names: names.clone(),
+        }),
     )
-    .await?;
+    .await?
+    .into_inner()
+    .transforms;
+
+    for name in names {
+        if let Some(static_state) = state.statics.get_mut(&name) {
+            update_static_object(&state.ctx, static_state, transforms.remove(&name), now).await?;
+        }
+    }
 
     // remove state for all units that are gone
-    statics.retain(|_, v| !v.is_gone);
-    state.statics = statics;
+    state.statics.retain(|_, v| !v.is_gone);
 
     Ok(())
 }
 
-async fn update_unit(ctx: &Context, unit_state: &mut UnitState) -> Result<(), Error> {
-    if !unit_state.should_update() {
-        return Ok(());
-    }
-
-    match unit_state.update(ctx).await {
-        Ok(changed) => {
-            if changed {
+async fn update_unit(
+    ctx: &Context,
+    unit_state: &mut UnitState,
+    transform: Option<GetTransformResponse>,
+    now: Instant,
+) -> Result<(), Error> {
+    match transform {
+        Some(transform) => {
+            let changes = unit_state.update(transform, ctx, now);
+            if changes.any() {
                 ctx.tx
                     .send(Ok(StreamUnitsResponse {
                         time: unit_state.update_time,
-                        update: Some(Update::Unit(unit_state.unit.clone())),
+                        update: Some(unit_state.to_update(&changes, ctx.compact)),
                     }))
                     .await?;
-                unit_state.backoff = Duration::ZERO;
-                unit_state.last_changed = Instant::now();
             }
-
-            unit_state.last_checked = Instant::now();
-
-            Ok(())
         }
         // if the unit was not found, flag it as gone, and continue with the next unit for now
-        Err(err) if err.code() == Code::NotFound => {
+        None => {
             ctx.tx
                 .send(Ok(StreamUnitsResponse {
                     // The time provided here is just the last time an update was received for the
@@ -360,37 +444,31 @@ async fn update_unit(ctx: &Context, unit_state: &mut UnitState) -> Result<(), Er
                 .await?;
 
             unit_state.is_gone = true;
-
-            Ok(())
         }
-        Err(err) => Err(err.into()),
     }
-}
 
-async fn update_static_object(ctx: &Context, static_state: &mut StaticState) -> Result<(), Error> {
-    if !static_state.should_update() {
-        return Ok(());
-    }
+    Ok(())
+}
 
-    match static_state.update(ctx).await {
-        Ok(changed) => {
-            if changed {
+async fn update_static_object(
+    ctx: &Context,
+    static_state: &mut StaticState,
+    transform: Option<GetStaticTransformResponse>,
+    now: Instant,
+) -> Result<(), Error> {
+    match transform {
+        Some(transform) => {
+            if static_state.update(transform, ctx, now) {
                 ctx.tx
                     .send(Ok(StreamUnitsResponse {
                         time: static_state.update_time,
                         update: Some(Update::Static(static_state.static_object.clone())),
                     }))
                     .await?;
-                static_state.backoff = Duration::ZERO;
-                static_state.last_changed = Instant::now();
             }
-
-            static_state.last_checked = Instant::now();
-
-            Ok(())
         }
         // if the unit was not found, flag it as gone, and continue with the next unit for now
-        Err(err) if err.code() == Code::NotFound => {
+        None => {
             ctx.tx
                 .send(Ok(StreamUnitsResponse {
                     // The time provided here is just the last time an update was received for the
@@ -406,17 +484,18 @@ async fn update_static_object(ctx: &Context, static_state: &mut StaticState) ->
                 .await?;
 
             static_state.is_gone = true;
-
-            Ok(())
         }
-        Err(err) => Err(err.into()),
     }
+
+    Ok(())
 }
 
 /// The last know information about a unit and various other information to track whether it is
 /// worth checking the unit for updates or not.
 struct UnitState {
     unit: Unit,
+    /// The poll rate of the unit's category, used as the lower bound for its backoff.
+    poll_rate: Duration,
     backoff: Duration,
     /// Time of the update in seconds relative to the mission start.
     update_time: f64,
@@ -436,9 +515,15 @@ struct StaticState {
 }
 
 impl UnitState {
-    fn new(unit: Unit) -> Self {
+    fn new(unit: Unit, ctx: &Context) -> Self {
+        let category = unit
+            .group
+            .as_ref()
+            .and_then(|group| GroupCategory::from_i32(group.category))
+            .unwrap_or(GroupCategory::Unspecified);
         Self {
             unit,
+            poll_rate: ctx.poll_rate_for(category),
             backoff: Duration::ZERO,
             update_time: 0.0,
             last_checked: Instant::now(),
@@ -449,21 +534,19 @@ impl UnitState {
 
     /// Whether the unit should be checked for updates or not. This can be used to check stationary
     /// units less often.
-    fn should_update(&self) -> bool {
-        self.last_checked.elapsed() >= self.backoff
+    fn should_update(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_checked) >= self.backoff
     }
 
-    /// Check the unit for updates and return whether the unit got changed or not.
-    async fn update(&mut self, ctx: &Context) -> Result<bool, Status> {
-        let mut changed = false;
+    /// Update the unit with the given transform and return which parts of the unit got changed.
+    fn update(
+        &mut self,
+        transform: GetTransformResponse,
+        ctx: &Context,
+        now: Instant,
+    ) -> UnitChanges {
+        let mut changes = UnitChanges::default();
 
-        let res = UnitService::get_transform(
-            &ctx.rpc,
-            Request::new(GetTransformRequest {
-                name: self.unit.name.clone(),
-            }),
-        )
-        .await?;
         let GetTransformResponse {
             time,
             position,
@@ -473,54 +556,119 @@ impl UnitState {
             in_air,
             fuel,
             cargos_on_board,
-        } = res.into_inner();
+            vitals,
+        } = transform;
 
         self.update_time = time;
 
         if let Some((before, after)) = self.unit.position.as_mut().zip(position) {
             if !position_equalish(before, &after) {
                 *before = after;
-                changed = true;
+                changes.position = true;
             }
         }
         if let Some((before, after)) = self.unit.orientation.as_mut().zip(orientation) {
             if !orientation_equalish(before, &after) {
                 *before = after;
-                changed = true;
+                changes.orientation = true;
             }
         }
         if let Some((before, after)) = self.unit.velocity.as_mut().zip(velocity) {
             if !velocity_equalish(before, &after) {
                 *before = after;
-                changed = true;
+                changes.velocity = true;
             }
         }
         if let Some((before, after)) = self.unit.player_name.as_mut().zip(player_name) {
             if !after.eq(&*before) {
                 *before = after;
-                changed = true;
+                changes.player_name = true;
             }
         }
 
+        if ctx.include_vitals
+            && (vitals != self.unit.vitals || !fuel_equalish(fuel, self.unit.fuel))
+        {
+            self.unit.vitals = vitals;
+            changes.vitals = true;
+        }
+
         // Just pass through the current boolean value for in_air / fuel / cargos
         self.unit.in_air = in_air;
         self.unit.fuel = fuel;
         self.unit.cargos_on_board = cargos_on_board;
 
         // keep track of when it was last checked and changed and determine a corresponding backoff
-        self.last_checked = Instant::now();
-        if changed {
-            self.last_changed = Instant::now();
-            self.backoff = Duration::ZERO;
+        self.last_checked = now;
+        if changes.any() {
+            self.last_changed = now;
+            self.backoff = match ctx.position_tolerance {
+                Some(tolerance) => self.speed_backoff(tolerance, ctx.max_backoff),
+                None => self.poll_rate,
+            };
         } else {
-            self.backoff = if self.backoff.is_zero() {
-                ctx.poll_rate
+            self.backoff = if self.backoff < self.poll_rate {
+                self.poll_rate
             } else {
                 (self.backoff * 2).min(ctx.max_backoff)
             }
         }
 
-        Ok(changed)
+        changes
+    }
+
+    /// Determine the backoff for a moving unit based on its current speed, so that it is polled
+    /// again once it could have moved about `tolerance` meters.
+    fn speed_backoff(&self, tolerance: f64, max_backoff: Duration) -> Duration {
+        let speed = self
+            .unit
+            .velocity
+            .as_ref()
+            .and_then(|velocity| velocity.velocity.as_ref())
+            .map(|v| (v.x * v.x + v.y * v.y + v.z * v.z).sqrt())
+            .unwrap_or_default();
+        if speed <= f64::EPSILON {
+            return self.poll_rate;
+        }
+
+        Duration::from_secs_f64((tolerance / speed).min(max_backoff.as_secs_f64()))
+            .max(self.poll_rate)
+    }
+
+    /// Create the update that should be sent out for the given changes. In compact mode, changes
+    /// that only affect the transform of the unit are sent as a [UnitDelta].
+    fn to_update(&self, changes: &UnitChanges, compact: bool) -> Update {
+        if compact && !changes.player_name && !changes.vitals {
+            Update::UnitDelta(UnitDelta {
+                id: self.unit.id,
+                position: self.unit.position.clone().filter(|_| changes.position),
+                orientation: self
+                    .unit
+                    .orientation
+                    .clone()
+                    .filter(|_| changes.orientation),
+                velocity: self.unit.velocity.clone().filter(|_| changes.velocity),
+            })
+        } else {
+            Update::Unit(self.unit.clone())
+        }
+    }
+}
+
+/// The parts of a unit that changed during an update.
+#[derive(Default)]
+struct UnitChanges {
+    position: bool,
+    orientation: bool,
+    velocity: bool,
+    player_name: bool,
+    /// The life, ammunition or fuel (only tracked if vitals are included).
+    vitals: bool,
+}
+
+impl UnitChanges {
+    fn any(&self) -> bool {
+        self.position || self.orientation || self.velocity || self.player_name || self.vitals
     }
 }
 
@@ -538,28 +686,25 @@ impl StaticState {
 
     /// Whether the unit should be checked for updates or not. This can be used to check stationary
     /// units less often.
-    fn should_update(&self) -> bool {
-        self.last_checked.elapsed() >= self.backoff
+    fn should_update(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_checked) >= self.backoff
     }
 
-    /// Check the unit for updates and return whether the unit got changed or not.
-    async fn update(&mut self, ctx: &Context) -> Result<bool, Status> {
+    /// Update the static object with the given transform and return whether it got changed or not.
+    fn update(
+        &mut self,
+        transform: GetStaticTransformResponse,
+        ctx: &Context,
+        now: Instant,
+    ) -> bool {
         let mut changed = false;
 
-        let res = UnitService::get_static_transform(
-            &ctx.rpc,
-            Request::new(GetStaticTransformRequest {
-                name: self.static_object.name.clone(),
-            }),
-        )
-        .await?;
-
         let GetStaticTransformResponse {
             time,
             position,
             orientation,
             velocity,
-        } = res.into_inner();
+        } = transform;
 
         self.update_time = time;
 
@@ -583,19 +728,19 @@ impl StaticState {
         }
 
         // keep track of when it was last checked and changed and determine a corresponding backoff
-        self.last_checked = Instant::now();
+        self.last_checked = now;
         if changed {
-            self.last_changed = Instant::now();
-            self.backoff = Duration::ZERO;
+            self.last_changed = now;
+            self.backoff = ctx.poll_rate;
         } else {
-            self.backoff = if self.backoff.is_zero() {
+            self.backoff = if self.backoff < ctx.poll_rate {
                 ctx.poll_rate
             } else {
                 (self.backoff * 2).min(ctx.max_backoff)
             }
         }
 
-        Ok(changed)
+        changed
     }
 }
 
@@ -703,8 +848,290 @@ fn degrees_equalish(a: f64, b: f64) -> bool {
     (a - b).abs() < EPSILON
 }
 
+/// Check whether two fuel fractions are equal, taking an epsilon into account.
+fn fuel_equalish(a: f64, b: f64) -> bool {
+    const EPSILON: f64 = 0.0001;
+    (a - b).abs() < EPSILON
+}
+
 /// Check whether two speeds are equal, taking an epsilon into account.
 fn speed_equalish(a: f64, b: f64) -> bool {
     const EPSILON: f64 = 0.001;
     (a - b).abs() < EPSILON
 }
+
+#[cfg(test)]
+mod tests {
+    use dcs_module_ipc::IPC;
+    use stubs::common::v0::UnitVitals;
+
+    use super::*;
+    use crate::shutdown::Shutdown;
+    use crate::stats::Stats;
+
+    fn context(compact: bool, include_vitals: bool) -> Context {
+        let shutdown = Shutdown::new();
+        Context {
+            rpc: MissionRpc::new(
+                IPC::default(),
+                Stats::new(shutdown.handle()),
+                shutdown.handle(),
+            ),
+            tx: tokio::sync::mpsc::channel(1).0,
+            poll_rate: Duration::from_secs(1),
+            category_poll_rates: HashMap::new(),
+            max_backoff: Duration::from_secs(30),
+            position_tolerance: None,
+            compact,
+            include_vitals,
+        }
+    }
+
+    fn unit() -> Unit {
+        Unit {
+            id: 1,
+            name: String::from("Aerial-1-1"),
+            position: Some(Position {
+                lat: 41.0,
+                lon: 42.0,
+                alt: 1000.0,
+                ..Default::default()
+            }),
+            orientation: Some(Orientation {
+                heading: 90.0,
+                ..Default::default()
+            }),
+            velocity: Some(Velocity {
+                heading: 90.0,
+                speed: 200.0,
+                velocity: Some(Vector {
+                    x: 0.0,
+                    y: 0.0,
+                    z: 200.0,
+                }),
+            }),
+            player_name: Some(String::from("player")),
+            fuel: 0.5,
+            vitals: Some(UnitVitals {
+                life: 10.0,
+                initial_life: 10.0,
+                ammo: Vec::new(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// The transform of an unchanged unit.
+    fn transform_of(unit: &Unit) -> GetTransformResponse {
+        GetTransformResponse {
+            time: 1.0,
+            position: unit.position.clone(),
+            orientation: unit.orientation.clone(),
+            velocity: unit.velocity.clone(),
+            player_name: unit.player_name.clone(),
+            in_air: unit.in_air,
+            fuel: unit.fuel,
+            cargos_on_board: unit.cargos_on_board.clone(),
+            vitals: unit.vitals.clone(),
+        }
+    }
+
+    #[test]
+    fn test_unchanged() {
+        let ctx = context(true, true);
+        let mut state = UnitState::new(unit(), &ctx);
+        let changes = state.update(transform_of(&unit()), &ctx, Instant::now());
+        assert!(!changes.any());
+    }
+
+    #[test]
+    fn test_compact_position_only() {
+        let ctx = context(true, false);
+        let mut state = UnitState::new(unit(), &ctx);
+        let mut transform = transform_of(&unit());
+        transform.position.as_mut().unwrap().lat = 41.1;
+
+        let changes = state.update(transform.clone(), &ctx, Instant::now());
+        assert_eq!(
+            state.to_update(&changes, ctx.compact),
+            Update::UnitDelta(UnitDelta {
+                id: 1,
+                position: transform.position,
+                orientation: None,
+                velocity: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_compact_orientation_and_velocity() {
+        let ctx = context(true, false);
+        let mut state = UnitState::new(unit(), &ctx);
+        let mut transform = transform_of(&unit());
+        transform.orientation.as_mut().unwrap().heading = 95.0;
+        transform.velocity.as_mut().unwrap().heading = 95.0;
+
+        let changes = state.update(transform.clone(), &ctx, Instant::now());
+        assert_eq!(
+            state.to_update(&changes, ctx.compact),
+            Update::UnitDelta(UnitDelta {
+                id: 1,
+                position: None,
+                orientation: transform.orientation,
+                velocity: transform.velocity,
+            })
+        );
+    }
+
+    #[test]
+    fn test_not_compact() {
+        let ctx = context(false, false);
+        let mut state = UnitState::new(unit(), &ctx);
+        let mut transform = transform_of(&unit());
+        transform.position.as_mut().unwrap().lat = 41.1;
+
+        let changes = state.update(transform, &ctx, Instant::now());
+        let mut expected = unit();
+        expected.position.as_mut().unwrap().lat = 41.1;
+        assert_eq!(
+            state.to_update(&changes, ctx.compact),
+            Update::Unit(expected)
+        );
+    }
+
+    #[test]
+    fn test_compact_player_name() {
+        let ctx = context(true, false);
+        let mut state = UnitState::new(unit(), &ctx);
+        let mut transform = transform_of(&unit());
+        transform.position.as_mut().unwrap().lat = 41.1;
+        transform.player_name = Some(String::from("other"));
+
+        let changes = state.update(transform, &ctx, Instant::now());
+        match state.to_update(&changes, ctx.compact) {
+            Update::Unit(unit) => {
+                assert_eq!(unit.player_name.as_deref(), Some("other"));
+                assert_eq!(unit.position.unwrap().lat, 41.1);
+            }
+            update => panic!("expected a full unit, got {:?}", update),
+        }
+    }
+
+    #[test]
+    fn test_compact_vitals() {
+        let ctx = context(true, true);
+        let mut state = UnitState::new(unit(), &ctx);
+        let mut transform = transform_of(&unit());
+        transform.vitals.as_mut().unwrap().life = 5.0;
+
+        let changes = state.update(transform, &ctx, Instant::now());
+        match state.to_update(&changes, ctx.compact) {
+            Update::Unit(unit) => assert_eq!(unit.vitals.unwrap().life, 5.0),
+            update => panic!("expected a full unit, got {:?}", update),
+        }
+
+        // fuel is part of the vitals
+        let mut transform = transform_of(&state.unit);
+        transform.fuel = 0.4;
+        let changes = state.update(transform, &ctx, Instant::now());
+        match state.to_update(&changes, ctx.compact) {
+            Update::Unit(unit) => assert_eq!(unit.fuel, 0.4),
+            update => panic!("expected a full unit, got {:?}", update),
+        }
+    }
+
+    #[test]
+    fn test_vitals_not_included() {
+        let ctx = context(true, false);
+        let mut state = UnitState::new(unit(), &ctx);
+        let mut transform = transform_of(&unit());
+        transform.vitals.as_mut().unwrap().life = 5.0;
+        transform.fuel = 0.4;
+
+        let changes = state.update(transform, &ctx, Instant::now());
+        assert!(!changes.any());
+    }
+
+    /// A unit state with the lowest possible poll rate, moving at the given speed (m/s).
+    fn moving(speed: f64) -> UnitState {
+        let ctx = context(false, false);
+        let mut state = UnitState::new(unit(), &ctx);
+        state.poll_rate = MIN_POLL_RATE;
+        state.unit.velocity.as_mut().unwrap().velocity = Some(Vector {
+            x: speed * 0.6,
+            y: 0.0,
+            z: speed * 0.8,
+        });
+        state
+    }
+
+    #[test]
+    fn test_speed_backoff() {
+        let max_backoff = Duration::from_secs(30);
+        // the time it takes to move 100m
+        assert_eq!(
+            moving(100.0).speed_backoff(100.0, max_backoff),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            moving(400.0).speed_backoff(100.0, max_backoff),
+            Duration::from_millis(250)
+        );
+        // stationary units are polled at the poll rate until they stop changing
+        assert_eq!(moving(0.0).speed_backoff(100.0, max_backoff), MIN_POLL_RATE);
+        let mut state = moving(0.0);
+        state.unit.velocity = None;
+        assert_eq!(state.speed_backoff(100.0, max_backoff), MIN_POLL_RATE);
+    }
+
+    #[test]
+    fn test_speed_backoff_bounds() {
+        let max_backoff = Duration::from_secs(30);
+        for speed in [0.0, 1e-9, 0.1, 1.0, 3.0, 100.0, 343.0, 1000.0, 1e4, 1e9] {
+            for tolerance in [0.0, 0.1, 1.0, 50.0, 1000.0, 1e9] {
+                let backoff = moving(speed).speed_backoff(tolerance, max_backoff);
+                assert!(
+                    backoff >= MIN_POLL_RATE && backoff <= max_backoff,
+                    "speed: {}, tolerance: {}, backoff: {:?}",
+                    speed,
+                    tolerance,
+                    backoff
+                );
+            }
+        }
+
+        // slow units are capped to the max backoff, fast ones to the poll rate of the unit
+        assert_eq!(moving(0.1).speed_backoff(100.0, max_backoff), max_backoff);
+        assert_eq!(moving(1e4).speed_backoff(1.0, max_backoff), MIN_POLL_RATE);
+        let mut state = moving(1e4);
+        state.poll_rate = Duration::from_secs(1);
+        assert_eq!(
+            state.speed_backoff(1.0, max_backoff),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn test_update_uses_speed_backoff() {
+        let mut ctx = context(false, false);
+        ctx.position_tolerance = Some(100.0);
+        let mut state = UnitState::new(unit(), &ctx);
+        let mut transform = transform_of(&unit());
+        transform.position.as_mut().unwrap().lat = 41.1;
+
+        // moving at 200m/s
+        state.update(transform.clone(), &ctx, Instant::now());
+        assert_eq!(state.backoff, Duration::from_secs(1));
+        state.poll_rate = MIN_POLL_RATE;
+        transform.position.as_mut().unwrap().lat = 41.2;
+        state.update(transform.clone(), &ctx, Instant::now());
+        assert_eq!(state.backoff, Duration::from_millis(500));
+
+        // without changes, the backoff doubles up to the max backoff
+        for _ in 0..10 {
+            state.update(transform.clone(), &ctx, Instant::now());
+            assert!(state.backoff <= ctx.max_backoff);
+        }
+        assert_eq!(state.backoff, ctx.max_backoff);
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use stubs::template::v0::GroupTemplate;
+use tokio::sync::Mutex;
+
+/// Group templates, persisted so that they are available across missions.
+#[derive(Clone)]
+pub struct GroupTemplates(Arc<Inner>);
+
+struct Inner {
+    path: PathBuf,
+    /// Whether the persisted templates could be loaded. If not, changes are refused, so that the
+    /// file isn't overwritten (and its templates lost) before it got fixed.
+    loaded: bool,
+    templates: Mutex<BTreeMap<String, GroupTemplate>>,
+}
+
+impl GroupTemplates {
+    /// Load the templates persisted at `path`.
+    pub fn new(path: PathBuf) -> Self {
+        let (templates, loaded) = match load(&path) {
+            Ok(templates) => (templates, true),
+            Err(err) => {
+                log::error!(
+                    "Failed to read group templates from {} (changes to templates are refused \
+                     until the file is fixed and the server restarted): {}",
+                    path.display(),
+                    err
+                );
+                (Vec::new(), false)
+            }
+        };
+
+        Self(Arc::new(Inner {
+            path,
+            loaded,
+            templates: Mutex::new(
+                templates
+                    .into_iter()
+                    .map(|template| (template.name.clone(), template))
+                    .collect(),
+            ),
+        }))
+    }
+
+    pub async fn list(&self) -> Vec<GroupTemplate> {
+        let templates = self.0.templates.lock().await;
+        templates.values().cloned().collect()
+    }
+
+    pub async fn get(&self, name: &str) -> Option<GroupTemplate> {
+        let templates = self.0.templates.lock().await;
+        templates.get(name).cloned()
+    }
+
+    /// Add the template (replacing any existing template of the same name) and persist it.
+    pub async fn insert(&self, template: GroupTemplate) -> Result<(), Error> {
+        if !self.0.loaded {
+            return Err(Error::NotLoaded);
+        }
+        let mut templates = self.0.templates.lock().await;
+        let mut changed = templates.clone();
+        changed.insert(template.name.clone(), template);
+        self.persist(&changed).await?;
+        *templates = changed;
+        Ok(())
+    }
+
+    /// Remove a template and persist the change. Returns whether the template existed.
+    pub async fn remove(&self, name: &str) -> Result<bool, Error> {
+        if !self.0.loaded {
+            return Err(Error::NotLoaded);
+        }
+        let mut templates = self.0.templates.lock().await;
+        let mut changed = templates.clone();
+        if changed.remove(name).is_none() {
+            return Ok(false);
+        }
+        self.persist(&changed).await?;
+        *templates = changed;
+        Ok(true)
+    }
+
+    /// Write the templates to disk. The caller holds the lock of the templates while awaiting
+    /// this, so that concurrent changes are written in order.
+    async fn persist(&self, templates: &BTreeMap<String, GroupTemplate>) -> Result<(), Error> {
+        let data = serde_json::to_vec_pretty(&templates.values().collect::<Vec<_>>())?;
+        let path = self.0.path.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(&path, data)?;
+            Ok(())
+        })
+        .await?
+    }
+}
+
+fn load(path: &Path) -> Result<Vec<GroupTemplate>, Error> {
+    match std::fs::read(path) {
+        Ok(data) => Ok(serde_json::from_slice(&data)?),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Join(#[from] tokio::task::JoinError),
+    #[error("the persisted group templates could not be loaded, see the server log")]
+    NotLoaded,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    /// A path in a directory of its own, which is removed again once dropped.
+    struct TempPath(PathBuf);
+
+    impl TempPath {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "dcs-grpc-templates-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = std::fs::remove_dir_all(&dir);
+            Self(dir.join("templates.json"))
+        }
+    }
+
+    impl Drop for TempPath {
+        fn drop(&mut self) {
+            if let Some(dir) = self.0.parent() {
+                let _ = std::fs::remove_dir_all(dir);
+            }
+        }
+    }
+
+    fn template(name: &str) -> GroupTemplate {
+        GroupTemplate {
+            name: name.to_string(),
+            captured_at: 1,
+            group: None,
+        }
+    }
+
+    fn names(templates: &GroupTemplates) -> Vec<String> {
+        block_on(templates.list())
+            .into_iter()
+            .map(|template| template.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let path = TempPath::new("round-trip");
+        let templates = GroupTemplates::new(path.0.clone());
+        assert!(names(&templates).is_empty());
+
+        block_on(templates.insert(template("b"))).unwrap();
+        block_on(templates.insert(template("a"))).unwrap();
+        block_on(templates.insert(template("c"))).unwrap();
+        assert!(block_on(templates.remove("c")).unwrap());
+        assert!(!block_on(templates.remove("c")).unwrap());
+
+        let reloaded = GroupTemplates::new(path.0.clone());
+        assert_eq!(names(&reloaded), vec!["a", "b"]);
+        assert_eq!(block_on(reloaded.get("a")), Some(template("a")));
+        assert_eq!(block_on(reloaded.get("c")), None);
+    }
+
+    #[test]
+    fn test_refuse_changes_after_failed_load() {
+        let path = TempPath::new("failed-load");
+        std::fs::create_dir_all(path.0.parent().unwrap()).unwrap();
+        std::fs::write(&path.0, b"[{\"name\": ").unwrap();
+
+        let templates = GroupTemplates::new(path.0.clone());
+        assert!(names(&templates).is_empty());
+        assert!(matches!(
+            block_on(templates.insert(template("a"))),
+            Err(Error::NotLoaded)
+        ));
+        assert!(matches!(
+            block_on(templates.remove("a")),
+            Err(Error::NotLoaded)
+        ));
+
+        // the broken file is left untouched, so that it can be fixed
+        assert_eq!(std::fs::read(&path.0).unwrap(), b"[{\"name\": ");
+        assert!(names(&templates).is_empty());
+    }
+}
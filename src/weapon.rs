@@ -2,20 +2,24 @@ use std::collections::HashMap;
 use std::time::Duration;
 
 use futures_util::stream::StreamExt;
-use stubs::common::v0::{Orientation, Position, Vector, Velocity, Weapon};
+use stubs::common::v0::{
+    initiator, Coalition, Initiator, Orientation, Position, Unit, Vector, Velocity, Weapon,
+};
 
-use stubs::mission::v0::stream_events_response::{Event, ShotEvent};
+use stubs::mission::v0::stream_events_response::{Event, HitEvent, ShotEvent};
 
-use stubs::weapon::v0::stream_weapons_response::{Update, WeaponGone};
+use stubs::weapon::v0::get_tracked_weapons_response::TrackedWeapon;
+use stubs::weapon::v0::stream_weapons_response::{Update, WeaponGone, WeaponHit, WeaponTerminated};
 use stubs::weapon::v0::weapon_service_server::WeaponService;
 use stubs::weapon::v0::{
-    GetTransformRequest, GetTransformResponse, StreamWeaponsRequest, StreamWeaponsResponse,
+    GetTrackedWeaponsRequest, GetTransformResponse, GetTransformsRequest, StreamWeaponsRequest,
+    StreamWeaponsResponse,
 };
 
 use tokio::sync::mpsc::error::SendError;
 use tokio::sync::mpsc::Sender;
 use tokio::time::MissedTickBehavior;
-use tonic::{Code, Request, Status};
+use tonic::{Request, Status};
 
 use crate::rpc::MissionRpc;
 
@@ -30,17 +34,45 @@ pub async fn stream_weapons(
     let poll_rate = Duration::from_millis(poll_rate as u64);
     let mut state = State {
         weapons: HashMap::new(),
+        filter: Filter {
+            types: opts.types,
+            categories: opts.categories,
+            launcher_coalition: Coalition::from_i32(opts.launcher_coalition)
+                .unwrap_or(Coalition::All),
+            launcher_unit_names: opts.launcher_unit_names,
+        },
         ctx: Context { rpc, tx, poll_rate },
     };
 
-    // We are purely reactive on events and do not track non-event based as there isn't a function
-    // to get all airborne weapons (that i know of), so we can do an initial sync of weapons in
-    // flight - which is sufficient for our needs anyhow as we want the initiator which is only
-    // reliably existing from the event
-
-    // initiate an event stream used to update the state
+    // initiate an event stream used to update the state (before the initial sync, to not miss
+    // any weapons fired in between)
     let mut events = state.ctx.rpc.events().await;
 
+    // initial sync of all weapons that are already in flight, which are the weapons the mission
+    // is tracking since they got fired
+    let tracked = WeaponService::get_tracked_weapons(
+        &state.ctx.rpc,
+        Request::new(GetTrackedWeaponsRequest {}),
+    )
+    .await?
+    .into_inner();
+    for TrackedWeapon { weapon, launcher } in tracked.weapons {
+        if let Some(weapon) = weapon {
+            if state.filter.matches(&weapon, launcher.as_ref()) {
+                let weapon_state = WeaponState::new(weapon, tracked.time);
+                state
+                    .ctx
+                    .tx
+                    .send(Ok(StreamWeaponsResponse {
+                        time: weapon_state.update_time,
+                        update: Some(Update::Weapon(weapon_state.weapon.clone())),
+                    }))
+                    .await?;
+                state.weapons.insert(weapon_state.weapon.id, weapon_state);
+            }
+        }
+    }
+
     // create an interval used to poll the mission for updates
     let mut interval = tokio::time::interval(poll_rate);
     interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
@@ -66,9 +98,58 @@ pub async fn stream_weapons(
 /// The state of an active units stream.
 struct State {
     weapons: HashMap<u32, WeaponState>,
+    filter: Filter,
     ctx: Context,
 }
 
+/// The filters of a weapons stream. Empty filters match all weapons.
+struct Filter {
+    types: Vec<String>,
+    categories: Vec<i32>,
+    launcher_coalition: Coalition,
+    launcher_unit_names: Vec<String>,
+}
+
+impl Filter {
+    /// Whether the given weapon, launched by the given unit, is part of the stream.
+    fn matches(&self, weapon: &Weapon, launcher: Option<&Unit>) -> bool {
+        if !self.types.is_empty()
+            && !weapon
+                .r#type
+                .as_ref()
+                .map(|t| self.types.contains(t))
+                .unwrap_or(false)
+        {
+            return false;
+        }
+
+        if !self.categories.is_empty()
+            && !weapon
+                .category
+                .map(|c| self.categories.contains(&c))
+                .unwrap_or(false)
+        {
+            return false;
+        }
+
+        if self.launcher_coalition != Coalition::All
+            && launcher.map(|unit| unit.coalition) != Some(self.launcher_coalition.into())
+        {
+            return false;
+        }
+
+        if !self.launcher_unit_names.is_empty()
+            && !launcher
+                .map(|unit| self.launcher_unit_names.contains(&unit.name))
+                .unwrap_or(false)
+        {
+            return false;
+        }
+
+        true
+    }
+}
+
 /// Various structs and options used to handle unit updates.
 struct Context {
     rpc: MissionRpc,
@@ -78,24 +159,61 @@ struct Context {
 
 /// Update the given [State] based on the given [Event].
 async fn handle_event(state: &mut State, time: f64, event: Event) -> Result<(), Error> {
-    // When a weapon is birthed (shot), we need to add it to the list
-    // to monitor next update
-    if let Event::Shot(ShotEvent {
-        weapon: Some(weapon),
-        ..
-    }) = event
-    {
-        state
-            .ctx
-            .tx
-            .send(Ok(StreamWeaponsResponse {
-                time,
-                update: Some(Update::Weapon(weapon.clone())),
-            }))
-            .await?;
-
-        // And add to our monitored events
-        state.weapons.insert(weapon.id, WeaponState::new(weapon));
+    match event {
+        // When a weapon is birthed (shot), we need to add it to the list
+        // to monitor next update
+        Event::Shot(ShotEvent {
+            initiator,
+            weapon: Some(weapon),
+        }) => {
+            // the weapon might already be known from the initial sync
+            if state.weapons.contains_key(&weapon.id) {
+                return Ok(());
+            }
+
+            let launcher = match initiator {
+                Some(Initiator {
+                    initiator: Some(initiator::Initiator::Unit(unit)),
+                }) => Some(unit),
+                _ => None,
+            };
+            if !state.filter.matches(&weapon, launcher.as_ref()) {
+                return Ok(());
+            }
+
+            state
+                .ctx
+                .tx
+                .send(Ok(StreamWeaponsResponse {
+                    time,
+                    update: Some(Update::Weapon(weapon.clone())),
+                }))
+                .await?;
+
+            // And add to our monitored events
+            state
+                .weapons
+                .insert(weapon.id, WeaponState::new(weapon, time));
+        }
+
+        // Remember what a weapon hit, to report it once the weapon is gone. The weapon's position
+        // at the time of the hit is the most accurate impact point we are going to get.
+        Event::Hit(HitEvent {
+            weapon: Some(weapon),
+            target,
+            ..
+        }) => {
+            if let Some(weapon_state) = state.weapons.get_mut(&weapon.id) {
+                weapon_state.update_position(weapon.position);
+                if weapon.velocity.is_some() {
+                    weapon_state.weapon.velocity = weapon.velocity;
+                }
+                weapon_state.update_time = time;
+                weapon_state.hit = Some(WeaponHit { time, target });
+            }
+        }
+
+        _ => {}
     }
 
     Ok(())
@@ -103,28 +221,41 @@ async fn handle_event(state: &mut State, time: f64, event: Event) -> Result<(),
 
 /// Updates all units inside of the provided [State].
 async fn update_weapons(state: &mut State) -> Result<(), Error> {
-    let mut weapons = std::mem::take(&mut state.weapons);
-    // Update all weapons in parallel (will queue a request for each unit, but the execution will
-    // still be throttled by the throughputLimit setting).
-    futures_util::future::try_join_all(
-        weapons
-            .values_mut()
-            .map(|weapon_state| update_weapon(&state.ctx, weapon_state)),
+    // weapons are always checked because they tend not to be stationary like ground units :)
+    let ids = state.weapons.keys().copied().collect::<Vec<_>>();
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    // Retrieve the transforms of all weapons with a single request.
+    let mut transforms = WeaponService::get_transforms(
+        &state.ctx.rpc,
+        Request::new(GetTransformsRequest { ids: ids.clone() }),
     )
-    .await?;
+    .await?
+    .into_inner()
+    .transforms;
+
+    for id in ids {
+        if let Some(weapon_state) = state.weapons.get_mut(&id) {
+            update_weapon(&state.ctx, weapon_state, transforms.remove(&id)).await?;
+        }
+    }
 
     // remove state for all units that are gone
-    weapons.retain(|_, v| !v.is_gone);
-    state.weapons = weapons;
+    state.weapons.retain(|_, v| !v.is_gone);
 
     Ok(())
 }
 
-async fn update_weapon(ctx: &Context, weapon_state: &mut WeaponState) -> Result<(), Error> {
-    // weapons are always checked because they tend not to be stationary like ground units :)
-    match weapon_state.update(ctx).await {
-        Ok(changed) => {
-            if changed {
+async fn update_weapon(
+    ctx: &Context,
+    weapon_state: &mut WeaponState,
+    transform: Option<GetTransformResponse>,
+) -> Result<(), Error> {
+    match transform {
+        Some(transform) => {
+            if weapon_state.update(transform) {
                 ctx.tx
                     .send(Ok(StreamWeaponsResponse {
                         time: weapon_state.update_time,
@@ -132,11 +263,15 @@ async fn update_weapon(ctx: &Context, weapon_state: &mut WeaponState) -> Result<
                     }))
                     .await?;
             }
-
-            Ok(())
         }
         // if the unit was not found, flag it as gone, and continue with the next unit for now
-        Err(err) if err.code() == Code::NotFound => {
+        None => {
+            ctx.tx
+                .send(Ok(StreamWeaponsResponse {
+                    time: weapon_state.update_time,
+                    update: Some(Update::Terminated(weapon_state.terminated())),
+                }))
+                .await?;
             ctx.tx
                 .send(Ok(StreamWeaponsResponse {
                     // The time provided here is just the last time an update was received for the
@@ -151,53 +286,85 @@ async fn update_weapon(ctx: &Context, weapon_state: &mut WeaponState) -> Result<
                 .await?;
 
             weapon_state.is_gone = true;
-
-            Ok(())
         }
-        Err(err) => Err(err.into()),
     }
+
+    Ok(())
 }
 
 /// The last know information about a unit and various other information to track whether it is
 /// worth checking the unit for updates or not.
 struct WeaponState {
     weapon: Weapon,
+    /// Time of the update in seconds relative to the mission start.
     update_time: f64,
+    /// Time the weapon was fired (or first seen) in seconds relative to the mission start.
+    launch_time: f64,
+    /// The distance in meters the weapon travelled so far.
+    distance: f64,
+    hit: Option<WeaponHit>,
     is_gone: bool,
 }
 
 impl WeaponState {
-    fn new(weapon: Weapon) -> Self {
+    fn new(weapon: Weapon, time: f64) -> Self {
         Self {
             weapon,
-            update_time: 0.0,
+            update_time: time,
+            launch_time: time,
+            distance: 0.0,
+            hit: None,
             is_gone: false,
         }
     }
 
-    /// Check the unit for updates and return whether the unit got changed or not.
-    async fn update(&mut self, ctx: &Context) -> Result<bool, Status> {
+    /// Update the position of the weapon and keep track of the distance it travelled. Returns
+    /// whether the position got changed or not.
+    fn update_position(&mut self, position: Option<Position>) -> bool {
+        if let Some((before, after)) = self.weapon.position.as_mut().zip(position) {
+            if !position_equalish(before, &after) {
+                let (du, dv, dalt) = (
+                    after.u - before.u,
+                    after.v - before.v,
+                    after.alt - before.alt,
+                );
+                self.distance += (du * du + dv * dv + dalt * dalt).sqrt();
+                *before = after;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// The last known state of the weapon.
+    fn terminated(&self) -> WeaponTerminated {
+        WeaponTerminated {
+            id: self.weapon.id,
+            r#type: self.weapon.r#type.clone(),
+            position: self.weapon.position.clone(),
+            velocity: self.weapon.velocity.clone(),
+            flight_time: (self.update_time - self.launch_time).max(0.0),
+            distance: self.distance,
+            hit: self.hit.clone(),
+        }
+    }
+
+    /// Update the weapon with the given transform and return whether it got changed or not.
+    fn update(&mut self, transform: GetTransformResponse) -> bool {
         let mut changed = false;
 
-        let res = WeaponService::get_transform(
-            &ctx.rpc,
-            Request::new(GetTransformRequest { id: self.weapon.id }),
-        )
-        .await?;
         let GetTransformResponse {
             time,
             position,
             orientation,
             velocity,
-        } = res.into_inner();
+        } = transform;
 
         self.update_time = time;
 
-        if let Some((before, after)) = self.weapon.position.as_mut().zip(position) {
-            if !position_equalish(before, &after) {
-                *before = after;
-                changed = true;
-            }
+        if self.update_position(position) {
+            changed = true;
         }
         if let Some((before, after)) = self.weapon.orientation.as_mut().zip(orientation) {
             if !orientation_equalish(before, &after) {
@@ -212,7 +379,7 @@ impl WeaponState {
             }
         }
 
-        Ok(changed)
+        changed
     }
 }
 
@@ -11,6 +11,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             "dcs.mission.v0.StreamEventsResponse.event",
             "#[serde(tag = \"type\")]",
         )
+        .type_attribute(
+            "dcs.hook.v0.RegisterDecisionHandlerResponse.decision",
+            "#[serde(tag = \"type\")]",
+        )
         .type_attribute(
             "dcs.common.v0.Unit",
             "#[serde(from = \"UnitIntermediate\")]",
@@ -143,6 +143,7 @@ pub mod v0 {
                 group,
                 number_in_group,
                 obj_id,
+                vitals: None,
             }
         }
     }
@@ -3,20 +3,32 @@
 #![allow(clippy::derive_partial_eq_without_eq)]
 #![allow(clippy::large_enum_variant)]
 
+pub mod access;
+pub mod airbase;
+pub mod atis;
 pub mod atmosphere;
+pub mod chat;
 pub mod coalition;
 pub mod common;
 pub mod controller;
+pub mod coordinate;
 pub mod custom;
+pub mod geometry;
 pub mod group;
 pub mod hook;
+pub mod land;
 pub mod mission;
 pub mod net;
+pub mod player;
+pub mod rotation;
+pub mod stats;
+pub mod template;
 pub mod timer;
 pub mod trigger;
 pub mod tts;
 pub mod unit;
 mod utils;
+pub mod warehouse;
 pub mod weapon;
 pub mod world;
 
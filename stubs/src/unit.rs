@@ -1,5 +1,5 @@
 pub mod v0 {
-    use crate::common::v0::{RawTransform, Transform};
+    use crate::common::v0::{RawTransform, Transform, UnitVitals};
 
     tonic::include_proto!("dcs.unit.v0");
 
@@ -8,6 +8,7 @@ pub mod v0 {
     struct GetTransformResponseIntermediate {
         time: f64,
         raw_transform: Option<RawTransform>,
+        vitals: Option<UnitVitals>,
     }
 
     impl From<GetTransformResponseIntermediate> for GetTransformResponse {
@@ -15,6 +16,7 @@ pub mod v0 {
             let GetTransformResponseIntermediate {
                 time,
                 raw_transform,
+                vitals,
             } = i;
             let transform = Transform::from(raw_transform.unwrap_or_default());
             GetTransformResponse {
@@ -26,6 +28,7 @@ pub mod v0 {
                 in_air: transform.in_air,
                 fuel: transform.fuel,
                 cargos_on_board: transform.cargos_on_board,
+                vitals,
             }
         }
     }
@@ -0,0 +1,3 @@
+pub mod v0 {
+    tonic::include_proto!("dcs.warehouse.v0");
+}